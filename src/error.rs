@@ -1,5 +1,7 @@
 //! Error types for the MuPDF MCP server.
 
+use rmcp::model::ErrorCode;
+use rmcp::ErrorData as McpError;
 use thiserror::Error;
 
 /// Errors that can occur in the MuPDF MCP server.
@@ -25,6 +27,10 @@ pub enum MupdfServerError {
     #[error("Document is not a PDF")]
     NotAPdf,
 
+    /// No embedded file with the given name exists in the document.
+    #[error("Embedded file not found: {0}")]
+    EmbeddedFileNotFound(String),
+
     /// Invalid text format requested.
     #[error("Invalid text format: {0} (valid formats: plain, html, json, xml)")]
     InvalidTextFormat(String),
@@ -33,10 +39,54 @@ pub enum MupdfServerError {
     #[error("Invalid image format: {0} (valid formats: png, svg)")]
     InvalidImageFormat(String),
 
+    /// Invalid or conflicting tool parameters.
+    #[error("Invalid parameters: {0}")]
+    InvalidParams(String),
+
+    /// A regular expression failed to compile.
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(String),
+
+    /// A render would produce a pixmap above the configured pixel limit.
+    #[error("Render too large: {width}x{height} pixels exceeds the limit of {max_pixels} pixels")]
+    RenderTooLarge {
+        width: u64,
+        height: u64,
+        max_pixels: u64,
+    },
+
+    /// A download or upload exceeds its size limit.
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// The store is full and every document in it is in use.
+    #[error("Document store is full: all {max} documents are in use")]
+    StoreFull { max: usize },
+
+    /// A file path source is outside the configured allowed directories.
+    #[error("Path not allowed: {0}")]
+    PathNotAllowed(String),
+
+    /// URL sources are disabled for this server.
+    #[error("URL sources are not allowed: {0}")]
+    UrlNotAllowed(String),
+
+    /// An output file exists and overwriting was not requested.
+    #[error("File already exists: {0}")]
+    FileAlreadyExists(String),
+
+    /// Writing an encrypted PDF failed.
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+
     /// Base64 decoding error.
     #[error("Base64 decode error: {0}")]
     Base64Error(#[from] base64::DecodeError),
 
+    /// HTTP error while downloading a document.
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] reqwest::Error),
+
     /// IO error.
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
@@ -59,6 +109,36 @@ impl MupdfServerError {
     pub fn internal(msg: impl Into<String>) -> Self {
         Self::Internal(msg.into())
     }
+
+    /// HTTP-style status code describing the error category.
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::DocumentNotFound(_) | Self::EmbeddedFileNotFound(_) => 404,
+            Self::InvalidPageNumber { .. }
+            | Self::InvalidTextFormat(_)
+            | Self::InvalidImageFormat(_)
+            | Self::InvalidParams(_)
+            | Self::InvalidRegex(_)
+            | Self::Base64Error(_) => 400,
+            Self::PasswordRequired => 401,
+            Self::InvalidPassword | Self::PathNotAllowed(_) | Self::UrlNotAllowed(_) => 403,
+            Self::FileAlreadyExists(_) => 409,
+            Self::RenderTooLarge { .. } | Self::PayloadTooLarge(_) => 413,
+            Self::NotAPdf => 415,
+            Self::HttpError(_) => 502,
+            Self::StoreFull { .. } => 503,
+            Self::EncryptionError(_)
+            | Self::IoError(_)
+            | Self::MupdfError(_)
+            | Self::JsonError(_)
+            | Self::Internal(_) => 500,
+        }
+    }
+
+    /// Convert into an MCP error carrying [`code`](Self::code) as its error code.
+    pub fn to_mcp_error(&self) -> McpError {
+        McpError::new(ErrorCode(self.code()), self.to_string(), None)
+    }
 }
 
 /// Result type for MuPDF MCP server operations.
@@ -9,5 +9,5 @@ pub mod state;
 pub mod tools;
 
 pub use error::{MupdfServerError, Result};
-pub use server::MupdfServer;
-pub use state::DocumentStore;
+pub use server::{MupdfServer, MupdfServerBuilder, HTTP_MCP_PATH};
+pub use state::{DocumentStore, InsertResult};
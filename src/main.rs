@@ -1,13 +1,90 @@
 //! MuPDF MCP Server entry point.
 //!
-//! This binary starts the MCP server using STDIO transport.
+//! This binary starts the MCP server using STDIO transport, or the
+//! streamable HTTP transport with `--transport http`.
+//!
+//! With `--snapshot-file <path>`, open documents are restored from `path`
+//! on startup and saved back to it on clean shutdown.
+//!
+//! Store limits and file sandboxing (`--allowed-path`, `--max-documents`,
+//! `--document-ttl-seconds`, ...) map onto [`MupdfServerBuilder`] options.
+//!
+//! [`MupdfServerBuilder`]: mupdf_rs_mcp_server::MupdfServerBuilder
 
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
 use mupdf_rs_mcp_server::MupdfServer;
 use rmcp::ServiceExt;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// MCP transport to serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Transport {
+    /// MCP over standard input and output.
+    Stdio,
+    /// MCP over streamable HTTP (POST/GET/DELETE on `/mcp`).
+    Http,
+}
+
+/// Command-line arguments.
+#[derive(Debug, Parser)]
+#[command(version, about = "MCP server exposing MuPDF PDF capabilities")]
+struct Args {
+    /// Transport to serve MCP over.
+    #[arg(long, value_enum, default_value_t = Transport::Stdio)]
+    transport: Transport,
+
+    /// Port to listen on with `--transport http`.
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+
+    /// Address to listen on with `--transport http`.
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    host: IpAddr,
+
+    /// Restore open documents from this file on startup and save them on
+    /// clean shutdown.
+    #[arg(long, value_name = "PATH")]
+    snapshot_file: Option<PathBuf>,
+
+    /// Only allow file path sources, and enable `export_document`, inside
+    /// this directory. May be given several times.
+    #[arg(long = "allowed-path", value_name = "DIR")]
+    allowed_paths: Vec<PathBuf>,
+
+    /// Allow or refuse URL sources (default: allowed unless
+    /// `--allowed-path` is given).
+    #[arg(long, value_name = "BOOL")]
+    allow_url_sources: Option<bool>,
+
+    /// Keep at most this many documents open, evicting the least recently
+    /// accessed.
+    #[arg(long, value_name = "N")]
+    max_documents: Option<usize>,
+
+    /// Close documents not accessed for this many seconds.
+    #[arg(long, value_name = "SECONDS")]
+    document_ttl_seconds: Option<u64>,
+
+    /// How often to sweep for expired documents, in seconds.
+    #[arg(long, value_name = "SECONDS")]
+    sweep_interval_seconds: Option<u64>,
+
+    /// Reject render requests with a scale factor above this.
+    #[arg(long, value_name = "SCALE")]
+    max_render_scale: Option<f32>,
+
+    /// Reject renders whose pixmap would exceed this many megapixels.
+    #[arg(long, value_name = "N")]
+    max_render_megapixels: Option<u64>,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
     // Initialize logging to stderr (important for STDIO transport)
     tracing_subscriber::registry()
         .with(
@@ -20,13 +97,64 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Starting MuPDF MCP Server v{}", env!("CARGO_PKG_VERSION"));
 
     // Create the server
-    let server = MupdfServer::new();
+    let mut builder = MupdfServer::builder();
+    if !args.allowed_paths.is_empty() {
+        builder.allowed_paths(args.allowed_paths.clone());
+    }
+    if let Some(allow) = args.allow_url_sources {
+        builder.allow_url_sources(allow);
+    }
+    if let Some(n) = args.max_documents {
+        builder.max_documents(n);
+    }
+    if let Some(seconds) = args.document_ttl_seconds {
+        builder.document_ttl_seconds(seconds);
+    }
+    if let Some(seconds) = args.sweep_interval_seconds {
+        builder.sweep_interval_seconds(seconds);
+    }
+    if let Some(scale) = args.max_render_scale {
+        builder.max_render_scale(scale);
+    }
+    if let Some(n) = args.max_render_megapixels {
+        builder.max_render_megapixels(n);
+    }
+    let server = builder.build();
+
+    if let Some(path) = args.snapshot_file.as_deref().filter(|path| path.exists()) {
+        // A corrupt or outdated snapshot should not keep the server from starting
+        match server.store().import_snapshot(path) {
+            Ok(loaded) => {
+                tracing::info!("Restored {} document(s) from {}", loaded, path.display())
+            }
+            Err(e) => tracing::warn!("Ignoring snapshot {}: {}", path.display(), e),
+        }
+    }
+
+    match args.transport {
+        Transport::Stdio => {
+            // Serve over STDIO
+            let service = server.clone().serve(rmcp::transport::stdio()).await?;
+
+            // Wait for the service to complete
+            service.waiting().await?;
+        }
+        Transport::Http => {
+            // Serve over HTTP until Ctrl-C
+            server
+                .clone()
+                .serve_http(SocketAddr::new(args.host, args.port))
+                .await?;
+        }
+    }
 
-    // Serve over STDIO
-    let service = server.serve(rmcp::transport::stdio()).await?;
+    if let Some(path) = &args.snapshot_file {
+        server.store().export_snapshot(path)?;
+        tracing::info!("Saved open documents to {}", path.display());
+    }
 
-    // Wait for the service to complete
-    service.waiting().await?;
+    let closed = server.shutdown()?;
+    tracing::info!("Closed {} open document(s)", closed);
 
     tracing::info!("MuPDF MCP Server stopped");
     Ok(())
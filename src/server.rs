@@ -5,27 +5,933 @@ use rmcp::model::{
     ListToolsResult, PaginatedRequestParams, ServerCapabilities, Tool,
 };
 use rmcp::service::RequestContext;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
 use rmcp::{ErrorData as McpError, ServerHandler};
 use serde_json::Value;
 use std::borrow::Cow;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
 
 use crate::state::DocumentStore;
 use crate::tools;
 
+/// Path of the MCP endpoint served by [`MupdfServer::serve_http`].
+pub const HTTP_MCP_PATH: &str = "/mcp";
+
+/// Default interval between expired-document sweeps.
+const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Builder for configuring a [`MupdfServer`] before construction.
+#[derive(Debug, Clone)]
+pub struct MupdfServerBuilder {
+    max_documents: Option<usize>,
+    document_ttl: Option<u64>,
+    sweep_interval: Duration,
+    max_render_scale: Option<f32>,
+    max_render_megapixels: Option<u64>,
+    allowed_paths: Option<Vec<PathBuf>>,
+    allow_url_sources: Option<bool>,
+}
+
+impl Default for MupdfServerBuilder {
+    fn default() -> Self {
+        Self {
+            max_documents: None,
+            document_ttl: None,
+            sweep_interval: DEFAULT_SWEEP_INTERVAL,
+            max_render_scale: None,
+            max_render_megapixels: None,
+            allowed_paths: None,
+            allow_url_sources: None,
+        }
+    }
+}
+
+impl MupdfServerBuilder {
+    /// Create a builder with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `n` documents open, evicting the least recently accessed.
+    pub fn max_documents(&mut self, n: usize) -> &mut Self {
+        self.max_documents = Some(n);
+        self
+    }
+
+    /// Close documents that have not been accessed for `seconds`.
+    pub fn document_ttl_seconds(&mut self, seconds: u64) -> &mut Self {
+        self.document_ttl = Some(seconds);
+        self
+    }
+
+    /// How often to sweep for expired documents (default 60, minimum 1).
+    pub fn sweep_interval_seconds(&mut self, seconds: u64) -> &mut Self {
+        self.sweep_interval = Duration::from_secs(seconds.max(1));
+        self
+    }
+
+    /// Reject render requests with a scale factor above `scale`.
+    pub fn max_render_scale(&mut self, scale: f32) -> &mut Self {
+        self.max_render_scale = Some(scale);
+        self
+    }
+
+    /// Reject renders whose pixmap would exceed `n` megapixels (default 50).
+    pub fn max_render_megapixels(&mut self, n: u64) -> &mut Self {
+        self.max_render_megapixels = Some(n);
+        self
+    }
+
+    /// Only allow file path sources inside these directories.
+    pub fn allowed_paths(&mut self, paths: Vec<PathBuf>) -> &mut Self {
+        self.allowed_paths = Some(paths);
+        self
+    }
+
+    /// Allow or refuse URL sources (default: allowed unless
+    /// [`allowed_paths`](Self::allowed_paths) is set).
+    pub fn allow_url_sources(&mut self, allow: bool) -> &mut Self {
+        self.allow_url_sources = Some(allow);
+        self
+    }
+
+    /// Build the server.
+    ///
+    /// When a document TTL is set and a Tokio runtime is available, this
+    /// spawns a background task that purges expired documents.
+    pub fn build(&self) -> MupdfServer {
+        let mut store = DocumentStore::new();
+        if let Some(ttl) = self.document_ttl {
+            store = store.ttl_seconds(ttl);
+        }
+        if let Some(max) = self.max_documents {
+            store = store.max_documents(max);
+        }
+        if let Some(scale) = self.max_render_scale {
+            store = store.max_render_scale(scale);
+        }
+        if let Some(megapixels) = self.max_render_megapixels {
+            store = store.max_render_pixels(megapixels.saturating_mul(1_000_000));
+        }
+        if let Some(paths) = &self.allowed_paths {
+            store = store.allowed_paths(paths.clone());
+        }
+        if let Some(allow) = self.allow_url_sources {
+            store = store.allow_url_sources(allow);
+        }
+
+        let sweeper = store
+            .ttl()
+            .and_then(|_| spawn_sweeper(store.clone(), self.sweep_interval));
+
+        MupdfServer {
+            store,
+            sweeper: sweeper.map(|handle| Arc::new(Sweeper(handle))),
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Handle to the sweeper task; aborts the task when the last server clone
+/// is dropped.
+struct Sweeper(JoinHandle<()>);
+
+impl Drop for Sweeper {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Spawn a task that periodically purges expired documents from the store.
+fn spawn_sweeper(store: DocumentStore, interval: Duration) -> Option<JoinHandle<()>> {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        tracing::warn!("No Tokio runtime available; expired documents will not be swept");
+        return None;
+    };
+
+    Some(handle.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match store.purge_expired() {
+                Ok(ids) if !ids.is_empty() => {
+                    tracing::info!("Purged {} expired document(s)", ids.len());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to purge expired documents: {}", e),
+            }
+        }
+    }))
+}
+
 /// MuPDF MCP Server.
 ///
-/// Provides PDF reading and manipulation capabilities via MCP.
+/// Provides PDF reading and manipulation capabilities via MCP. Clones share
+/// the same document store.
+#[derive(Clone)]
 pub struct MupdfServer {
     /// Document store for stateful operations.
     store: DocumentStore,
+    /// Background task purging expired documents, if a TTL is configured.
+    sweeper: Option<Arc<Sweeper>>,
+    /// When the server was built, for uptime reporting.
+    started_at: Instant,
 }
 
 impl MupdfServer {
     /// Create a new MuPDF MCP server.
     pub fn new() -> Self {
-        Self {
-            store: DocumentStore::new(),
+        MupdfServerBuilder::default().build()
+    }
+
+    /// Create a new server whose documents expire after `ttl` seconds
+    /// without access.
+    pub fn new_with_ttl(ttl: u64) -> Self {
+        MupdfServerBuilder::new().document_ttl_seconds(ttl).build()
+    }
+
+    /// Create a builder for configuring the server.
+    pub fn builder() -> MupdfServerBuilder {
+        MupdfServerBuilder::new()
+    }
+
+    /// The document store backing this server.
+    pub fn store(&self) -> &DocumentStore {
+        &self.store
+    }
+
+    /// Stop background tasks, close every open document and drop pending
+    /// uploads.
+    ///
+    /// Returns the number of documents that were closed.
+    pub fn shutdown(&self) -> crate::error::Result<usize> {
+        if let Some(sweeper) = &self.sweeper {
+            sweeper.0.abort();
+        }
+        self.store.purge_all()
+    }
+
+    /// Serve MCP over the streamable HTTP transport at `addr` until Ctrl-C.
+    ///
+    /// The endpoint is [`HTTP_MCP_PATH`]. Each MCP session gets a clone of
+    /// this server, so all sessions share the same document store.
+    pub async fn serve_http(self, addr: SocketAddr) -> anyhow::Result<()> {
+        let service = StreamableHttpService::new(
+            move || Ok(self.clone()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let router = axum::Router::new().nest_service(HTTP_MCP_PATH, service);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(
+            "Serving MCP over HTTP at http://{}{}",
+            listener.local_addr()?,
+            HTTP_MCP_PATH
+        );
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Run a tool call inside a `tool` tracing span.
+    ///
+    /// The span carries the tool name and, when present in the arguments,
+    /// the `document_id` and `page`. The outcome and duration are logged
+    /// when the call finishes.
+    pub async fn handle_tool_call(
+        &self,
+        name: &str,
+        args: serde_json::Map<String, Value>,
+    ) -> Result<CallToolResult, McpError> {
+        let span = tracing::info_span!(
+            "tool",
+            tool.name = %name,
+            document_id = tracing::field::Empty,
+            page = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+        );
+        if let Some(document_id) = args.get("document_id").and_then(Value::as_str) {
+            span.record("document_id", document_id);
+        }
+        if let Some(page) = args.get("page").and_then(Value::as_i64) {
+            span.record("page", page);
+        }
+
+        async move {
+            let start = Instant::now();
+            let outcome = self.dispatch_tool(name, args);
+            let duration_ms = start.elapsed().as_millis() as u64;
+            tracing::Span::current().record("duration_ms", duration_ms);
+
+            let ok = matches!(&outcome, Ok(result) if result.is_error != Some(true));
+            tracing::info!(
+                result = if ok { "ok" } else { "error" },
+                duration_ms,
+                "tool call finished"
+            );
+            outcome
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Deserialize the arguments of a tool call and run the tool.
+    fn dispatch_tool(
+        &self,
+        name: &str,
+        args: serde_json::Map<String, Value>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = match name {
+            "import_document" => {
+                let params: tools::ImportDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::import_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "upload_chunk" => {
+                let params: tools::UploadChunkParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::upload_chunk(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "finalize_stream" => {
+                let params: tools::FinalizeStreamParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::finalize_stream(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "import_document_subrange" => {
+                let params: tools::ImportFromSubrangeParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::import_document_subrange(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "close_all_documents" => {
+                let params: tools::CloseAllDocumentsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::close_all_documents(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "close_document" => {
+                let params: tools::CloseDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::close_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "export_document" => {
+                let params: tools::ExportDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::export_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "copy_document" => {
+                let params: tools::CopyDocumentParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::copy_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "list_documents" => {
+                let params: tools::ListDocumentsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::list_documents(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "set_document_tag" => {
+                let params: tools::SetDocumentTagParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::set_document_tag(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_document_tag" => {
+                let params: tools::GetDocumentTagParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_document_tag(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "list_document_tags" => {
+                let params: tools::ListDocumentTagsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::list_document_tags(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_store_stats" => tools::get_store_stats(&self.store, self.started_at)
+                .map(|r| serde_json::to_string(&r).unwrap()),
+            "get_page_count" => {
+                let params: tools::GetPageCountParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_count(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_metadata" => {
+                let params: tools::GetMetadataParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_metadata(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_xmp_metadata" => {
+                let params: tools::GetXmpMetadataParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_xmp_metadata(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_fonts" => {
+                let params: tools::GetFontsParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_fonts(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "validate_pdf" => {
+                let params: tools::ValidatePdfParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::validate_pdf(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_pdf_objects_summary" => {
+                let params: tools::GetPdfObjectsSummaryParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_pdf_objects_summary(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_pdf_version" => {
+                let params: tools::GetPdfVersionParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_pdf_version(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_document_format" => {
+                let params: tools::GetDocumentFormatParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_document_format(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_outlines" => {
+                let params: tools::GetOutlinesParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_outlines(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_document_outline_as_markdown" => {
+                let params: tools::GetOutlineMarkdownParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_document_outline_as_markdown(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_outline_depth" => {
+                let params: tools::GetOutlineDepthParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_outline_depth(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_outline_flat" => {
+                let params: tools::GetOutlineFlatParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_outline_flat(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_chapter_info" => {
+                let params: tools::GetPageChapterInfoParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_chapter_info(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "resolve_location" => {
+                let params: tools::ResolveLocationParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::resolve_location(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_security_info" => {
+                let params: tools::GetSecurityInfoParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_security_info(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_action_tree" => {
+                let params: tools::GetActionTreeParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_action_tree(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_structural_tree" => {
+                let params: tools::GetStructuralTreeParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_structural_tree(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "check_accessibility" => {
+                let params: tools::CheckAccessibilityParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::check_accessibility(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_form_fields" => {
+                let params: tools::GetFormFieldsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_form_fields(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_labels" => {
+                let params: tools::GetPageLabelsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_labels(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "resolve_page_label" => {
+                let params: tools::ResolvePageLabelParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::resolve_page_label(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_embedded_files" => {
+                let params: tools::GetEmbeddedFilesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_embedded_files(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "extract_embedded_file" => {
+                let params: tools::ExtractEmbeddedFileParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::extract_embedded_file(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_named_destinations" => {
+                let params: tools::GetNamedDestinationsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_named_destinations(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "resolve_named_destination" => {
+                let params: tools::ResolveNamedDestinationParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::resolve_named_destination(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_document_statistics" => {
+                let params: tools::GetDocumentStatsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_document_statistics(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_size_preset" => {
+                let params: tools::GetPageSizePresetParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_size_preset(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_bounds" => {
+                let params: tools::GetPageBoundsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_bounds(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_display_bounds" => {
+                let params: tools::GetPageDisplayBoundsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_display_bounds(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text" => {
+                let params: tools::GetPageTextParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "extract_text_from_page_range" => {
+                let params: tools::ExtractTextRangeParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::extract_text_from_page_range(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_lines" => {
+                let params: tools::GetPageTextLinesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_lines(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_text_blocks_with_reading_zones" => {
+                let params: tools::GetTextBlocksWithZonesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_text_blocks_with_reading_zones(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_reading_order" => {
+                let params: tools::GetPageTextReadingOrderParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_reading_order(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_words_at_point" => {
+                let params: tools::GetPageWordsAtPointParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_words_at_point(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_tables" => {
+                let params: tools::GetPageTablesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_tables(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_words" => {
+                let params: tools::GetPageWordsParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_words(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_chars" => {
+                let params: tools::GetPageCharsParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_chars(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_structured" => {
+                let params: tools::GetPageTextStructuredParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_structured(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_spans" => {
+                let params: tools::GetPageTextSpansParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_spans(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_fonts_on_page" => {
+                let params: tools::GetFontsOnPageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_fonts_on_page(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_encoding" => {
+                let params: tools::GetPageTextEncodingParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_encoding(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_language" => {
+                let params: tools::GetPageTextLanguageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_language(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_text_diff" => {
+                let params: tools::GetPageTextDiffParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_text_diff(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "search_page_context" => {
+                let params: tools::SearchPageContextParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::search_page_context(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_text_for_region" => {
+                let params: tools::GetTextForRegionParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_text_for_region(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_pages_bounds" => {
+                let params: tools::GetPagesBoundsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_pages_bounds(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_rotation" => {
+                let params: tools::GetPageRotationParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_rotation(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_links" => {
+                let params: tools::GetPageLinksParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_links(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_links_all" => {
+                let params: tools::GetPageLinksAllParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_links_all(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "search_page" => {
+                let params: tools::SearchPageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::search_page(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "search_document" => {
+                let params: tools::SearchDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::search_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "search_document_regex" => {
+                let params: tools::SearchDocumentRegexParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::search_document_regex(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "render_page" => {
+                let params: tools::RenderPageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::render_page(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_color_space_usage" => {
+                let params: tools::GetPageColorSpaceParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_color_space_usage(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_ink_coverage" => {
+                let params: tools::GetPageInkCoverageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_ink_coverage(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_color_info" => {
+                let params: tools::GetPageColorInfoParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_color_info(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "render_page_region" => {
+                let params: tools::RenderPageRegionParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::render_page_region(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "render_pages" => {
+                let params: tools::RenderPagesParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::render_pages(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "render_all_pages" => {
+                let params: tools::RenderAllPagesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::render_all_pages(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "extract_page_images" => {
+                let params: tools::ExtractPageImagesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::extract_page_images(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_image_blocks" => {
+                let params: tools::GetPageImageBlocksParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_image_blocks(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_annotations" => {
+                let params: tools::GetPageAnnotationsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_annotations(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_annotations_all" => {
+                let params: tools::GetAnnotationsAllParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_annotations_all(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "get_page_annotations_summary" => {
+                let params: tools::GetAnnotationsSummaryParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::get_page_annotations_summary(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "split_document" => {
+                let params: tools::SplitDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::split_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "merge_documents" => {
+                let params: tools::MergeDocumentsParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::merge_documents(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "flatten_document" => {
+                let params: tools::FlattenDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::flatten_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "optimize_document" => {
+                let params: tools::OptimizeDocumentParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::optimize_document(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "add_text_watermark" => {
+                let params: tools::AddTextWatermarkParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::add_text_watermark(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "rotate_pages" => {
+                let params: tools::RotatePagesParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::rotate_pages(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "delete_pages" => {
+                let params: tools::DeletePagesParams = serde_json::from_value(Value::Object(args))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::delete_pages(&self.store, params).map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "set_document_password" => {
+                let params: tools::SetDocumentPasswordParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::set_document_password(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "create_document_from_pages" => {
+                let params: tools::CreateDocumentFromPagesParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::create_document_from_pages(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_get_bookmarks" => {
+                let params: tools::OneshotGetBookmarksParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_get_bookmarks(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_extract_full_text" => {
+                let params: tools::OneshotExtractFullTextParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_extract_full_text(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_get_page_text" => {
+                let params: tools::OneshotGetPageTextParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_get_page_text(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_render_page" => {
+                let params: tools::OneshotRenderPageParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_render_page(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_get_page_count" => {
+                let params: tools::OneshotGetPageCountParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_get_page_count(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_get_metadata" => {
+                let params: tools::OneshotGetMetadataParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_get_metadata(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            "oneshot_search" => {
+                let params: tools::OneshotSearchParams =
+                    serde_json::from_value(Value::Object(args))
+                        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                tools::oneshot_search(&self.store, params)
+                    .map(|r| serde_json::to_string(&r).unwrap())
+            }
+            _ => {
+                return Err(McpError::invalid_params(
+                    format!("Unknown tool: {}", name),
+                    None,
+                ))
+            }
+        };
+
+        match result {
+            Ok(json) => Ok(CallToolResult::success(vec![Content::text(json)])),
+            Err(e) => Err(e.to_mcp_error()),
         }
     }
 
@@ -41,6 +947,51 @@ impl MupdfServer {
             meta: None,
         }
     }
+
+    /// JSON schema for a `DocumentSource` (file path, file paths, base64
+    /// content or URL).
+    fn document_source_schema() -> Value {
+        serde_json::json!({
+            "oneOf": [
+                {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string", "description": "File path to PDF" }
+                    },
+                    "required": ["path"]
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "paths": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "maxItems": 1000,
+                            "description": "File paths whose pages are combined, in order, into one PDF (e.g. multi-file TIFF)"
+                        }
+                    },
+                    "required": ["paths"]
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "base64": { "type": "string", "description": "Base64-encoded PDF content" },
+                        "filename": { "type": "string", "description": "Optional filename hint" }
+                    },
+                    "required": ["base64"]
+                },
+                {
+                    "type": "object",
+                    "properties": {
+                        "url": { "type": "string", "description": "HTTP(S) URL to download the document from" },
+                        "filename": { "type": "string", "description": "Optional filename hint" },
+                        "timeout_seconds": { "type": "integer", "default": 30, "description": "Download timeout in seconds" }
+                    },
+                    "required": ["url"]
+                }
+            ]
+        })
+    }
 }
 
 impl Default for MupdfServer {
@@ -70,7 +1021,7 @@ impl ServerHandler for MupdfServer {
                     import_document → get document_id → use document_id with other tools → close_document. \
                  \
                  2. ONESHOT (for single operation, no state management): \
-                    Use tools prefixed with 'oneshot_' - they accept file path or base64 directly and handle everything in one call. \
+                    Use tools prefixed with 'oneshot_' - they accept a file path, several file paths, base64 content or a URL directly and handle everything in one call. \
                  \
                  Choose ONESHOT when you only need one operation. Choose STATEFUL when you need multiple operations on the same document."
                     .to_string(),
@@ -89,34 +1040,55 @@ impl ServerHandler for MupdfServer {
                 // Session Management (STATEFUL API - requires document_id)
                 Self::make_tool(
                     "import_document",
-                    "[STATEFUL] Import a document to the server. Returns a document_id for subsequent operations. Use this when you need multiple operations on the same document. Remember to call close_document when done.",
+                    "[STATEFUL] Import a document from a file path, several file paths (combined into one PDF), base64 content or an HTTP(S) URL. Returns a document_id for subsequent operations. Use this when you need multiple operations on the same document. Remember to call close_document when done.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "source": {
-                                "oneOf": [
-                                    {
-                                        "type": "object",
-                                        "properties": {
-                                            "path": { "type": "string", "description": "File path to PDF" }
-                                        },
-                                        "required": ["path"]
-                                    },
-                                    {
-                                        "type": "object",
-                                        "properties": {
-                                            "base64": { "type": "string", "description": "Base64-encoded PDF content" },
-                                            "filename": { "type": "string", "description": "Optional filename hint" }
-                                        },
-                                        "required": ["base64"]
-                                    }
-                                ]
-                            },
+                            "source": Self::document_source_schema(),
                             "password": { "type": "string", "description": "Password for encrypted documents" }
                         },
                         "required": ["source"]
                     }),
                 ),
+                Self::make_tool(
+                    "upload_chunk",
+                    "[STATEFUL] Upload one base64 chunk of a large document. Send chunks in order starting at chunk_index 0 (which starts a new upload), then call finalize_stream. Decoded chunk sizes must be multiples of 3 bytes except for the last. Uploads are limited to 256 MiB.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "stream_id": { "type": "string", "description": "Caller-chosen ID grouping the chunks of one upload" },
+                            "chunk_index": { "type": "integer", "minimum": 0, "description": "Position of this chunk, starting at 0" },
+                            "data": { "type": "string", "description": "Base64-encoded chunk data" }
+                        },
+                        "required": ["stream_id", "chunk_index", "data"]
+                    }),
+                ),
+                Self::make_tool(
+                    "finalize_stream",
+                    "[STATEFUL] Import the chunks uploaded with upload_chunk as a document. Returns a document_id like import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "stream_id": { "type": "string" },
+                            "filename": { "type": "string", "description": "Original filename, used to detect the format" },
+                            "password": { "type": "string", "description": "Password for encrypted documents" }
+                        },
+                        "required": ["stream_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "import_document_subrange",
+                    "[STATEFUL] Import a page range of an open PDF as a new document. Returns a new document_id like import_document. Requires source_document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source_document_id": { "type": "string" },
+                            "start_page": { "type": "integer", "description": "First page (0-indexed; negative counts from the end, -1 = last page)" },
+                            "end_page": { "type": "integer", "description": "Last page, inclusive (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["source_document_id", "start_page", "end_page"]
+                    }),
+                ),
                 Self::make_tool(
                     "close_document",
                     "[STATEFUL] Close a document and free its memory. Always call this after you're done with a document imported via import_document.",
@@ -129,133 +1101,1215 @@ impl ServerHandler for MupdfServer {
                     }),
                 ),
                 Self::make_tool(
-                    "list_documents",
-                    "[STATEFUL] List all open documents with their IDs and page counts.",
+                    "close_all_documents",
+                    "[STATEFUL] Close every open document, or only those not accessed for more than older_than_seconds, and return the closed document IDs. Use for bulk cleanup instead of closing documents one by one.",
                     serde_json::json!({
                         "type": "object",
-                        "properties": {}
+                        "properties": {
+                            "older_than_seconds": { "type": "integer", "minimum": 0, "description": "Only close documents idle for longer than this. Closes all documents if omitted" }
+                        }
                     }),
                 ),
-                // Document Operations (STATEFUL API - requires document_id)
                 Self::make_tool(
-                    "get_page_count",
-                    "[STATEFUL] Get the total number of pages. Requires document_id from import_document.",
+                    "export_document",
+                    "[STATEFUL] Write an open PDF, including unsaved changes, to a file path. Fails if the file exists unless overwrite is true. Only available when the server restricts file access to allowed directories, and the path must be inside them (symlinks are refused). Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "document_id": { "type": "string" }
+                            "document_id": { "type": "string" },
+                            "output_path": { "type": "string", "description": "File to write" },
+                            "overwrite": { "type": "boolean", "default": false, "description": "Replace an existing file" }
                         },
-                        "required": ["document_id"]
+                        "required": ["document_id", "output_path"]
                     }),
                 ),
                 Self::make_tool(
-                    "get_metadata",
-                    "[STATEFUL] Get document metadata (title, author, subject, keywords, etc.). Requires document_id from import_document.",
+                    "copy_document",
+                    "[STATEFUL] Duplicate an open PDF (including unsaved changes) as a new, independent document. Returns the new document_id. Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "document_id": { "type": "string" }
+                            "document_id": { "type": "string", "description": "Document to copy" }
                         },
                         "required": ["document_id"]
                     }),
                 ),
                 Self::make_tool(
-                    "get_outlines",
-                    "[STATEFUL] Get document outlines (table of contents/bookmarks) with page numbers. Requires document_id from import_document.",
+                    "list_documents",
+                    "[STATEFUL] List open documents (oldest first by default) with their IDs, page counts and access statistics. Pass next_cursor back as cursor to get the next page.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "document_id": { "type": "string" }
-                        },
-                        "required": ["document_id"]
+                            "cursor": { "type": "string", "description": "Cursor from a previous call's next_cursor (default ordering only)" },
+                            "limit": { "type": "integer", "minimum": 1, "description": "Maximum number of documents to return. Defaults to all" },
+                            "sort_by": { "type": "string", "enum": ["created", "last_accessed", "access_count", "size"], "default": "created" },
+                            "descending": { "type": "boolean", "default": false, "description": "Sort in descending order" },
+                            "tag_filter": {
+                                "type": "object",
+                                "additionalProperties": { "type": "string" },
+                                "description": "Only list documents carrying all of these tags with these values"
+                            }
+                        }
                     }),
                 ),
-                // Page Operations (STATEFUL API - requires document_id)
                 Self::make_tool(
-                    "get_page_bounds",
-                    "[STATEFUL] Get the dimensions (width, height) of a page. Requires document_id from import_document.",
+                    "set_document_tag",
+                    "[STATEFUL] Attach a key/value label to a document (e.g. key \"name\", value \"contract_jan2024\"). Tags appear in list_documents and can be used to filter it. Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
                             "document_id": { "type": "string" },
-                            "page": { "type": "integer", "description": "Page number (0-indexed)" }
+                            "key": { "type": "string", "description": "Tag name" },
+                            "value": { "type": "string", "description": "Tag value; replaces any previous value" }
                         },
-                        "required": ["document_id", "page"]
+                        "required": ["document_id", "key", "value"]
                     }),
                 ),
                 Self::make_tool(
-                    "get_page_text",
-                    "[STATEFUL] Extract text from a page in various formats (plain, html, json, xml). Requires document_id from import_document.",
+                    "get_document_tag",
+                    "[STATEFUL] Read a single tag of a document (null if unset). Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
                             "document_id": { "type": "string" },
-                            "page": { "type": "integer", "description": "Page number (0-indexed)" },
-                            "format": { "type": "string", "enum": ["plain", "html", "json", "xml"], "default": "plain" }
+                            "key": { "type": "string", "description": "Tag name" }
                         },
-                        "required": ["document_id", "page"]
+                        "required": ["document_id", "key"]
                     }),
                 ),
                 Self::make_tool(
-                    "search_page",
-                    "[STATEFUL] Search for text on a page. Returns coordinates of all matches. Requires document_id from import_document.",
+                    "list_document_tags",
+                    "[STATEFUL] List every tag of a document. Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "document_id": { "type": "string" },
-                            "page": { "type": "integer", "description": "Page number (0-indexed)" },
-                            "query": { "type": "string", "description": "Text to search for" }
+                            "document_id": { "type": "string" }
                         },
-                        "required": ["document_id", "page", "query"]
+                        "required": ["document_id"]
                     }),
                 ),
                 Self::make_tool(
-                    "render_page",
-                    "[STATEFUL] Render a page to an image (PNG). Returns base64-encoded data. Requires document_id from import_document.",
+                    "get_store_stats",
+                    "[STATEFUL] Report document store usage: open document count, estimated memory, oldest document age, highest access count and server uptime.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {}
+                    }),
+                ),
+                // Document Operations (STATEFUL API - requires document_id)
+                Self::make_tool(
+                    "get_page_count",
+                    "[STATEFUL] Get the total number of pages. Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "document_id": { "type": "string" },
-                            "page": { "type": "integer", "description": "Page number (0-indexed)" },
-                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI)" }
+                            "document_id": { "type": "string" }
                         },
-                        "required": ["document_id", "page"]
+                        "required": ["document_id"]
                     }),
                 ),
-                // ONESHOT tools (stateless - no document_id needed)
                 Self::make_tool(
-                    "oneshot_get_bookmarks",
-                    "[ONESHOT] Extract all bookmarks with their target page numbers. No document_id needed - pass file path or base64 directly. Use this for a single operation; use STATEFUL API if you need multiple operations on the same document.",
+                    "get_metadata",
+                    "[STATEFUL] Get document metadata (title, author, subject, keywords, etc.). Requires document_id from import_document.",
                     serde_json::json!({
                         "type": "object",
                         "properties": {
-                            "source": {
-                                "oneOf": [
-                                    {
-                                        "type": "object",
-                                        "properties": {
-                                            "path": { "type": "string", "description": "File path to PDF" }
-                                        },
-                                        "required": ["path"]
-                                    },
-                                    {
-                                        "type": "object",
-                                        "properties": {
-                                            "base64": { "type": "string", "description": "Base64-encoded PDF content" },
-                                            "filename": { "type": "string", "description": "Optional filename hint" }
-                                        },
-                                        "required": ["base64"]
-                                    }
-                                ]
-                            },
-                            "password": { "type": "string", "description": "Password for encrypted documents" }
+                            "document_id": { "type": "string" }
                         },
-                        "required": ["source"]
+                        "required": ["document_id"]
                     }),
                 ),
-            ];
-
-            Ok(ListToolsResult {
+                Self::make_tool(
+                    "get_xmp_metadata",
+                    "[STATEFUL] Get the raw XMP metadata packet (XML) of a PDF, if present. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_pdf_version",
+                    "[STATEFUL] Get the PDF specification version (e.g. 1.7). Fails for non-PDF documents. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "validate_pdf",
+                    "[STATEFUL] Check a document for corruption and structural problems: pages that fail to load, a damaged cross-reference table, unreadable objects and metadata. Each check runs independently and issues are reported with severity. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_pdf_objects_summary",
+                    "[STATEFUL] Summarize low-level PDF structure: xref length, stream count, page tree depth, cross-reference streams and linearization. Fails for non-PDF documents. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_fonts",
+                    "[STATEFUL] List the fonts of a PDF with name, type, encoding, embedding and subset flags and the pages whose text uses them. Fails for non-PDF documents. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_document_format",
+                    "[STATEFUL] Detect the document type (pdf, epub, xps, cbz, ...) and its MIME type. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_outlines",
+                    "[STATEFUL] Get document outlines (table of contents/bookmarks) with page numbers. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "depth_limit": { "type": "integer", "minimum": 1, "description": "Maximum nesting depth to return (1 = top level only). Defaults to the whole tree" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_document_outline_as_markdown",
+                    "[STATEFUL] Get the table of contents as a nested Markdown list (\"- Title (p. N)\", N 0-indexed). Empty if the document has no outline. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "include_page_numbers": { "type": "boolean", "default": true, "description": "Append the 0-indexed target page to each entry" },
+                            "max_depth": { "type": "integer", "minimum": 1, "description": "Maximum nesting depth to include (1 = top level only). Defaults to the whole tree" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_outline_depth",
+                    "[STATEFUL] Get the maximum nesting depth and total number of bookmarks. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_outline_flat",
+                    "[STATEFUL] Get bookmarks as a flat list ordered by page, each with its nesting depth. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "max_depth": { "type": "integer", "minimum": 1, "description": "Maximum nesting depth to include (1 = top level only). Defaults to the whole tree" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_chapter_info",
+                    "[STATEFUL] Get the chapter a page belongs to and its index within the chapter (EPUB and other reflowable formats have chapters; PDFs have one). Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "resolve_location",
+                    "[STATEFUL] Resolve a chapter location (chapter, page_in_chapter) to its 0-indexed page number. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "chapter": { "type": "integer", "description": "Chapter index (0-indexed)" },
+                            "page_in_chapter": { "type": "integer", "description": "Page index within the chapter (0-indexed)" }
+                        },
+                        "required": ["document_id", "chapter", "page_in_chapter"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_security_info",
+                    "[STATEFUL] Get encryption status and permissions (print, modify, copy, annotate). Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                // Page Operations (STATEFUL API - requires document_id)
+                Self::make_tool(
+                    "get_action_tree",
+                    "[STATEFUL] List the actions of a PDF (open action, document scripts, page, link and form field actions) with trigger, type and script or URI, and whether any JavaScript is present. Fails for non-PDF documents. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_structural_tree",
+                    "[STATEFUL] Read the tagged structure tree (accessibility tags such as H1, P, Table, Figure) of a PDF as nested { role, alt_text, page, children } nodes. Returns is_tagged false for untagged PDFs. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "check_accessibility",
+                    "[STATEFUL] Check a PDF for basic accessibility (PDF/UA) problems: tagging, title, language, image alternate text, form field labels and reading order. Returns counts and a list of issues with severity. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_form_fields",
+                    "[STATEFUL] List interactive form fields (AcroForm) with name, type, current value, page and bounds. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_labels",
+                    "[STATEFUL] Get page label ranges (e.g. roman numerals for front matter) with style, prefix and starting number. Empty if the document defines no labels. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "resolve_page_label",
+                    "[STATEFUL] Resolve a page label as shown by viewers (e.g. \"iii\") to its physical 0-indexed page number. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "label": { "type": "string", "description": "Page label, e.g. \"iii\" or \"A-2\"" }
+                        },
+                        "required": ["document_id", "label"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_embedded_files",
+                    "[STATEFUL] List files attached to the document with name, description, MIME type, size and dates. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "extract_embedded_file",
+                    "[STATEFUL] Extract an attached file as base64. Use get_embedded_files to find file names. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "name": { "type": "string", "description": "Embedded file name from get_embedded_files" }
+                        },
+                        "required": ["document_id", "name"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_named_destinations",
+                    "[STATEFUL] List named destinations (deep-link targets) with their page and destination type. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "resolve_named_destination",
+                    "[STATEFUL] Resolve a named destination to its page, type, position and zoom. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "name": { "type": "string", "description": "Destination name" }
+                        },
+                        "required": ["document_id", "name"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_document_statistics",
+                    "[STATEFUL] Get text statistics for the whole document: character, word, line and block counts, pages with/without text, and estimated reading time. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_size_preset",
+                    "[STATEFUL] Get a page's size in points and millimetres, its orientation and the matching ISO 216, ANSI or ARCH paper size (e.g. A4, Letter), within 2 points. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_bounds",
+                    "[STATEFUL] Get the dimensions (width, height) and rotation of a page. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_display_bounds",
+                    "[STATEFUL] Get page dimensions both with and without the page rotation applied. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text",
+                    "[STATEFUL] Extract text from a page in various formats (plain, html, json, xml). Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "format": { "type": "string", "enum": ["plain", "html", "json", "xml"], "default": "plain" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "extract_text_from_page_range",
+                    "[STATEFUL] Extract the text of an inclusive page range in one call, returning per-page text and the pages joined with a separator. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "start_page": { "type": "integer", "description": "First page (0-indexed, inclusive; negative counts from the end)" },
+                            "end_page": { "type": "integer", "description": "Last page (0-indexed, inclusive; negative counts from the end)" },
+                            "format": { "type": "string", "enum": ["plain", "html", "json", "xml"], "default": "plain" },
+                            "join_separator": { "type": "string", "description": "Separator between pages in the concatenated text", "default": "\n\n---\n\n" }
+                        },
+                        "required": ["document_id", "start_page", "end_page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_lines",
+                    "[STATEFUL] Get the text lines of a page as a flat list with bounding boxes, writing mode and block index. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_reading_order",
+                    "[STATEFUL] Extract page text in visual reading order (columns left to right, top to bottom within a column; full-width headings and footers stay in place between the columns), for multi-column layouts. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "column_gap_threshold": { "type": "number", "minimum": 0, "default": 20, "description": "Minimum horizontal gap in points between two columns" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_text_blocks_with_reading_zones",
+                    "[STATEFUL] Get the text blocks of a page grouped into columns by x-position, with each column's horizontal extent and each block's column index (null for full-width blocks such as titles and footers), for detecting multi-column layouts. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "column_gap_pts": { "type": "number", "minimum": 0, "default": 20, "description": "Minimum horizontal gap in points between two columns" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_words_at_point",
+                    "[STATEFUL] Find the word under a point (e.g. a click position) on a page. Coordinates are in page points. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "x": { "type": "number", "description": "X coordinate in page points" },
+                            "y": { "type": "number", "description": "Y coordinate in page points" }
+                        },
+                        "required": ["document_id", "page", "x", "y"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_tables",
+                    "[STATEFUL] Detect tables on a page from text alignment (heuristic; ruling lines are ignored). Returns each table's bounds, cell text by row and a confidence score. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_words",
+                    "[STATEFUL] Extract the words on a page with their bounding boxes. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_chars",
+                    "[STATEFUL] Extract every character on a page with its bounding box, font name and font size. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_structured",
+                    "[STATEFUL] Get a page's text as a JSON tree (page -> blocks -> lines -> spans -> chars) with bounding boxes, parsed from MuPDF's structured-text XML. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "include_font_info": { "type": "boolean", "default": false, "description": "Include font name and size on each span" },
+                            "include_color": { "type": "boolean", "default": false, "description": "Include the #rrggbb color of each character" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_spans",
+                    "[STATEFUL] Get text spans on a page with font name, size, bold/italic flags, color and bounding boxes. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_fonts_on_page",
+                    "[STATEFUL] List the fonts used on a page, grouped by font name and size, with the number of characters set in each. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_language",
+                    "[STATEFUL] Detect the dominant script of a page's text (CJK, RTL or Latin) with a coarse language hint, for routing multilingual documents. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_encoding",
+                    "[STATEFUL] Count the characters of a page that could not be mapped to Unicode (U+FFFD), to detect text extraction problems caused by broken font encodings. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_text_diff",
+                    "[STATEFUL] Compare the text of two pages (from one or two documents) by line or word. Returns added and removed text, the number of unchanged units and a similarity score. Requires document_ids from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id_a": { "type": "string", "description": "Document of the original page" },
+                            "page_a": { "type": "integer", "description": "Original page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "document_id_b": { "type": "string", "description": "Document of the revised page" },
+                            "page_b": { "type": "integer", "description": "Revised page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "diff_granularity": { "type": "string", "enum": ["line", "word"], "default": "line", "description": "Unit of comparison" }
+                        },
+                        "required": ["document_id_a", "page_a", "document_id_b", "page_b"]
+                    }),
+                ),
+                Self::make_tool(
+                    "search_page_context",
+                    "[STATEFUL] Search for text on a page (case-insensitive) and return each hit's coordinates with the surrounding text and the full line. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "query": { "type": "string", "description": "Text to search for" },
+                            "context_chars": { "type": "integer", "minimum": 0, "default": 80, "description": "Characters of context on each side of a hit" }
+                        },
+                        "required": ["document_id", "page", "query"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_text_for_region",
+                    "[STATEFUL] Extract the text inside a rectangle of a page, in reading order. Coordinates are in page points. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "x0": { "type": "number", "description": "Left edge of the region" },
+                            "y0": { "type": "number", "description": "Top edge of the region" },
+                            "x1": { "type": "number", "description": "Right edge of the region" },
+                            "y1": { "type": "number", "description": "Bottom edge of the region" },
+                            "overlap": { "type": "number", "default": 0.5, "description": "Minimum fraction of a character inside the region (0 = any overlap)" }
+                        },
+                        "required": ["document_id", "page", "x0", "y0", "x1", "y1"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_pages_bounds",
+                    "[STATEFUL] Get the dimensions and rotation of several pages (or all pages) in one call. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "pages": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Page numbers (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_rotation",
+                    "[STATEFUL] Get the display rotation of a page in degrees (0, 90, 180 or 270). Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_links",
+                    "[STATEFUL] Get the hyperlinks on a page with bounds, URI and target page, optionally with the text under each link. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "include_text": { "type": "boolean", "default": false, "description": "Include the text under each link as link_text" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_links_all",
+                    "[STATEFUL] Get hyperlinks on every page with their page numbers, bounds, URIs and internal targets. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "include_internal": { "type": "boolean", "default": true, "description": "Include links to pages inside the document" },
+                            "include_external": { "type": "boolean", "default": true, "description": "Include links to external URIs" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "search_page",
+                    "[STATEFUL] Search for text on a page. Returns coordinates of all matches. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "query": { "type": "string", "description": "Text to search for" },
+                            "case_sensitive": { "type": "boolean", "default": true, "description": "Match letter case exactly" },
+                            "whole_word": { "type": "boolean", "default": false, "description": "Only match whole words" }
+                        },
+                        "required": ["document_id", "page", "query"]
+                    }),
+                ),
+                Self::make_tool(
+                    "search_document",
+                    "[STATEFUL] Search for text across all pages (or a page range). Returns page numbers and coordinates of all matches. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "query": { "type": "string", "description": "Text to search for" },
+                            "max_hits_per_page": { "type": "integer", "default": 100, "description": "Maximum number of hits per page" },
+                            "page_range": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "minItems": 2,
+                                "maxItems": 2,
+                                "description": "Inclusive [start, end] page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            },
+                            "case_sensitive": { "type": "boolean", "default": true, "description": "Match letter case exactly" },
+                            "whole_word": { "type": "boolean", "default": false, "description": "Only match whole words" }
+                        },
+                        "required": ["document_id", "query"]
+                    }),
+                ),
+                Self::make_tool(
+                    "search_document_regex",
+                    "[STATEFUL] Search the plain text of all pages (or a page range) with a regular expression. Returns matched text, character offsets and capture groups. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "pattern": { "type": "string", "description": "Regular expression (Rust regex syntax; no look-around or backreferences)" },
+                            "page_range": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "minItems": 2,
+                                "maxItems": 2,
+                                "description": "Inclusive [start, end] page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            },
+                            "max_matches": { "type": "integer", "default": 1000, "description": "Maximum number of matches to return" }
+                        },
+                        "required": ["document_id", "pattern"]
+                    }),
+                ),
+                Self::make_tool(
+                    "render_page",
+                    "[STATEFUL] Render a page to an image (PNG or SVG). PNG is returned as base64-encoded data, SVG as markup. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI). Mutually exclusive with dpi" },
+                            "dpi": { "type": "number", "description": "Resolution in dots per inch. Mutually exclusive with scale" },
+                            "format": { "type": "string", "enum": ["png", "svg"], "default": "png" },
+                            "colorspace": { "type": "string", "enum": ["rgb", "gray", "cmyk"], "default": "rgb", "description": "Raster colorspace. CMYK renders are returned as PSD" },
+                            "transparent_background": { "type": "boolean", "default": false, "description": "Render onto a transparent background (adds an alpha channel)" },
+                            "rotate_to_upright": { "type": "boolean", "default": true, "description": "Apply the page rotation so the image is in display orientation. False renders the page unrotated" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "render_page_region",
+                    "[STATEFUL] Render a rectangular region of a page to PNG (base64). Coordinates are in page points. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "x0": { "type": "number", "description": "Left edge of the region" },
+                            "y0": { "type": "number", "description": "Top edge of the region" },
+                            "x1": { "type": "number", "description": "Right edge of the region" },
+                            "y1": { "type": "number", "description": "Bottom edge of the region" },
+                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI)" }
+                        },
+                        "required": ["document_id", "page", "x0", "y0", "x1", "y1"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_color_info",
+                    "[STATEFUL] Classify a page as color or grayscale from a low-resolution render, and report whether it has images and its estimated ink coverage. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_ink_coverage",
+                    "[STATEFUL] Estimate the ink coverage of a page (percentage of non-white pixels) from a fast low-resolution render, for print cost estimates and blank page detection. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "sample_scale": { "type": "number", "default": 0.05, "exclusiveMinimum": 0, "maximum": 1, "description": "Render scale of the sample (0.05 is about 3.6 DPI)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_color_space_usage",
+                    "[STATEFUL] List the color spaces a PDF page declares (resources, images, transparency group), flagging ICC profiles and spot colors (Separation/DeviceN) for print production. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "render_pages",
+                    "[STATEFUL] Render several pages (at most 50) to images (PNG or SVG) in one call. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "pages": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "maxItems": 50,
+                                "description": "Page numbers (0-indexed; negative counts from the end, -1 = last page)"
+                            },
+                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI). Mutually exclusive with dpi" },
+                            "dpi": { "type": "number", "description": "Resolution in dots per inch. Mutually exclusive with scale" },
+                            "format": { "type": "string", "enum": ["png", "svg"], "default": "png" }
+                        },
+                        "required": ["document_id", "pages"]
+                    }),
+                ),
+                Self::make_tool(
+                    "render_all_pages",
+                    "[STATEFUL] Render PNG thumbnails of every page (at most max_pages, default and maximum 50) in one call. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI). Mutually exclusive with dpi" },
+                            "dpi": { "type": "number", "description": "Resolution in dots per inch. Mutually exclusive with scale" },
+                            "max_pages": { "type": "integer", "default": 50, "maximum": 50, "description": "Render at most this many pages, starting from the first" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "extract_page_images",
+                    "[STATEFUL] List the raster images drawn on a page with their bounds, pixel size and colorspace. Optionally include each image as base64 PNG. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "include_data": { "type": "boolean", "default": false, "description": "Include base64-encoded PNG data for each image" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_image_blocks",
+                    "[STATEFUL] Locate the regions of a page covered by raster images, with their transform, pixel size, colorspace and bit depth. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_annotations",
+                    "[STATEFUL] List annotations on a page (comments, highlights, stamps, ...) with type, bounds, contents, author, dates and color. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_annotations_all",
+                    "[STATEFUL] List annotations on every page with their page numbers, optionally filtered by annotation type. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "annotation_type_filter": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Only return these annotation types (case-insensitive), e.g. [\"Highlight\", \"Text\"]"
+                            }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "get_page_annotations_summary",
+                    "[STATEFUL] Count annotations by type and by page, for a quick overview before fetching details. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                // ONESHOT tools (stateless - no document_id needed)
+                Self::make_tool(
+                    "split_document",
+                    "[STATEFUL] Extract an inclusive page range into a new PDF, returned as base64. The source document is not modified. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "start_page": { "type": "integer", "description": "First page to extract (0-indexed; negative counts from the end, -1 = last page)" },
+                            "end_page": { "type": "integer", "description": "Last page to extract, inclusive (0-indexed; negative counts from the end, -1 = last page)" }
+                        },
+                        "required": ["document_id", "start_page", "end_page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "merge_documents",
+                    "[STATEFUL] Combine pages of two open documents into a new PDF, returned as base64. The result is not imported; pass it to import_document to keep working with it. Requires document_id_a and document_id_b from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id_a": { "type": "string", "description": "Document whose pages come first" },
+                            "pages_a": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Pages of the first document (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            },
+                            "document_id_b": { "type": "string", "description": "Document whose pages are appended" },
+                            "pages_b": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Pages of the second document (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            }
+                        },
+                        "required": ["document_id_a", "document_id_b"]
+                    }),
+                ),
+                Self::make_tool(
+                    "create_document_from_pages",
+                    "[STATEFUL] Assemble a new PDF from pages picked across open documents, in the given order. Returns base64, or stores the result and returns its document_id when store_result is true. Requires document_ids from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "selections": {
+                                "type": "array",
+                                "minItems": 1,
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "document_id": { "type": "string" },
+                                        "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" }
+                                    },
+                                    "required": ["document_id", "page"]
+                                },
+                                "description": "Pages to copy, in output order"
+                            },
+                            "store_result": { "type": "boolean", "default": false, "description": "Add the new PDF to the store and return its document_id instead of base64" }
+                        },
+                        "required": ["selections"]
+                    }),
+                ),
+                Self::make_tool(
+                    "flatten_document",
+                    "[STATEFUL] Bake form field values into page content and return the flattened PDF as base64, with no interactive fields left. The stored document is not changed. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "optimize_document",
+                    "[STATEFUL] Re-save a PDF with stream compression and garbage collection to reduce its size, returning the optimized PDF as base64 with before/after sizes. The stored document is not changed. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "linearize": { "type": "boolean", "default": false, "description": "Write a linearized (fast web view) PDF" },
+                            "garbage_collect": { "type": "boolean", "default": true, "description": "Drop unused objects and merge duplicates" },
+                            "compress_fonts": { "type": "boolean", "default": true, "description": "Compress embedded font streams" },
+                            "compress_images": { "type": "boolean", "default": true, "description": "Losslessly compress uncompressed image streams" }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "add_text_watermark",
+                    "[STATEFUL] Stamp a text watermark onto all or selected pages and return the watermarked PDF as base64. The stored document is not changed. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "text": { "type": "string", "description": "Watermark text (Latin-1; other characters become ?)" },
+                            "pages": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Pages to stamp (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            },
+                            "opacity": { "type": "number", "default": 0.3, "minimum": 0, "maximum": 1 },
+                            "font_size": { "type": "number", "default": 48, "description": "Font size in points" },
+                            "color": {
+                                "type": "array",
+                                "items": { "type": "number", "minimum": 0, "maximum": 1 },
+                                "minItems": 3,
+                                "maxItems": 3,
+                                "description": "RGB color components (default mid gray)"
+                            },
+                            "position": { "type": "string", "enum": ["center", "top-left", "top-right", "bottom-left", "bottom-right"], "default": "center" }
+                        },
+                        "required": ["document_id", "text"]
+                    }),
+                ),
+                Self::make_tool(
+                    "rotate_pages",
+                    "[STATEFUL] Rotate pages of a stored PDF clockwise in place, adding to their current rotation. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "pages": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Pages to rotate (0-indexed; negative counts from the end, -1 = last page)"
+                            },
+                            "degrees": { "type": "integer", "enum": [0, 90, 180, 270], "description": "Clockwise rotation added to each page" }
+                        },
+                        "required": ["document_id", "pages", "degrees"]
+                    }),
+                ),
+                Self::make_tool(
+                    "delete_pages",
+                    "[STATEFUL] Delete pages from a stored PDF in place. Page numbers refer to the document before deletion; at least one page must remain. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "pages": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "description": "Pages to delete (0-indexed; negative counts from the end, -1 = last page)"
+                            }
+                        },
+                        "required": ["document_id", "pages"]
+                    }),
+                ),
+                Self::make_tool(
+                    "set_document_password",
+                    "[STATEFUL] Return a copy of a PDF encrypted (AES-256) with new user and/or owner passwords and permissions, as base64. The stored document is not changed. Requires document_id from import_document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "document_id": { "type": "string" },
+                            "user_password": { "type": "string", "description": "Password required to open the PDF" },
+                            "owner_password": { "type": "string", "description": "Password granting full access" },
+                            "permissions": {
+                                "type": "object",
+                                "description": "Operations allowed without the owner password (default: all)",
+                                "properties": {
+                                    "can_print": { "type": "boolean" },
+                                    "can_modify": { "type": "boolean" },
+                                    "can_copy": { "type": "boolean" },
+                                    "can_annotate": { "type": "boolean" }
+                                },
+                                "required": ["can_print", "can_modify", "can_copy", "can_annotate"]
+                            }
+                        },
+                        "required": ["document_id"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_get_bookmarks",
+                    "[ONESHOT] Extract all bookmarks with their target page numbers. No document_id needed - pass a source (path, paths, base64 or url) directly. Use this for a single operation; use STATEFUL API if you need multiple operations on the same document.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" }
+                        },
+                        "required": ["source"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_extract_full_text",
+                    "[ONESHOT] Extract text from every page in a single call. No document_id needed - pass a source (path, paths, base64 or url) directly. Returns one entry per page.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" },
+                            "format": { "type": "string", "enum": ["plain", "html", "json", "xml"], "default": "plain" }
+                        },
+                        "required": ["source"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_get_page_text",
+                    "[ONESHOT] Extract text from a single page. No document_id needed - pass a source (path, paths, base64 or url) directly.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "format": { "type": "string", "enum": ["plain", "html", "json", "xml"], "default": "plain" }
+                        },
+                        "required": ["source", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_render_page",
+                    "[ONESHOT] Render a single page to PNG (base64) or SVG. No document_id needed - pass a source (path, paths, base64 or url) directly.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" },
+                            "page": { "type": "integer", "description": "Page number (0-indexed; negative counts from the end, -1 = last page)" },
+                            "scale": { "type": "number", "default": 1.0, "description": "Scale factor (1.0 = 72 DPI). Mutually exclusive with dpi" },
+                            "dpi": { "type": "number", "description": "Resolution in dots per inch. Mutually exclusive with scale" },
+                            "format": { "type": "string", "enum": ["png", "svg"], "default": "png" }
+                        },
+                        "required": ["source", "page"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_get_page_count",
+                    "[ONESHOT] Get the number of pages in a document. No document_id needed - pass a source (path, paths, base64 or url) directly.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" }
+                        },
+                        "required": ["source"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_get_metadata",
+                    "[ONESHOT] Get document metadata (title, author, subject, keywords, creator, producer, dates) and page count. No document_id needed - pass a source (path, paths, base64 or url) directly.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" }
+                        },
+                        "required": ["source"]
+                    }),
+                ),
+                Self::make_tool(
+                    "oneshot_search",
+                    "[ONESHOT] Search for text across all pages (or a page range). Returns page numbers and coordinates of all matches. No document_id needed - pass a source (path, paths, base64 or url) directly.",
+                    serde_json::json!({
+                        "type": "object",
+                        "properties": {
+                            "source": Self::document_source_schema(),
+                            "password": { "type": "string", "description": "Password for encrypted documents" },
+                            "query": { "type": "string", "description": "Text to search for" },
+                            "max_hits_per_page": { "type": "integer", "default": 100, "description": "Maximum number of hits per page" },
+                            "page_range": {
+                                "type": "array",
+                                "items": { "type": "integer" },
+                                "minItems": 2,
+                                "maxItems": 2,
+                                "description": "Inclusive [start, end] page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages"
+                            },
+                            "case_sensitive": { "type": "boolean", "default": true, "description": "Match letter case exactly" },
+                            "whole_word": { "type": "boolean", "default": false, "description": "Only match whole words" }
+                        },
+                        "required": ["source", "query"]
+                    }),
+                ),
+            ];
+
+            Ok(ListToolsResult {
                 tools,
                 next_cursor: None,
                 meta: None,
@@ -271,96 +2325,6 @@ impl ServerHandler for MupdfServer {
         let name = request.name.clone();
         let args = request.arguments.clone().unwrap_or_default();
 
-        async move {
-            let result = match name.as_ref() {
-                "import_document" => {
-                    let params: tools::ImportDocumentParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::import_document(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "close_document" => {
-                    let params: tools::CloseDocumentParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::close_document(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "list_documents" => {
-                    let params: tools::ListDocumentsParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::list_documents(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "get_page_count" => {
-                    let params: tools::GetPageCountParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::get_page_count(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "get_metadata" => {
-                    let params: tools::GetMetadataParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::get_metadata(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "get_outlines" => {
-                    let params: tools::GetOutlinesParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::get_outlines(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "get_page_bounds" => {
-                    let params: tools::GetPageBoundsParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::get_page_bounds(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "get_page_text" => {
-                    let params: tools::GetPageTextParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::get_page_text(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "search_page" => {
-                    let params: tools::SearchPageParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::search_page(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "render_page" => {
-                    let params: tools::RenderPageParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::render_page(&self.store, params)
-                        .map(|r| serde_json::to_string(&r).unwrap())
-                }
-                "oneshot_get_bookmarks" => {
-                    let params: tools::OneshotGetBookmarksParams =
-                        serde_json::from_value(Value::Object(args))
-                            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
-                    tools::oneshot_get_bookmarks(params).map(|r| serde_json::to_string(&r).unwrap())
-                }
-                _ => {
-                    return Err(McpError::invalid_params(
-                        format!("Unknown tool: {}", name),
-                        None,
-                    ))
-                }
-            };
-
-            match result {
-                Ok(json) => Ok(CallToolResult::success(vec![Content::text(json)])),
-                Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
-            }
-        }
+        async move { self.handle_tool_call(&name, args).await }
     }
 }
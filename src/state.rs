@@ -1,13 +1,20 @@
 //! Document store for managing uploaded PDF documents.
 
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use base64::Engine;
+use mupdf::pdf::PdfDocument;
 use mupdf::Document;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{MupdfServerError, Result};
+use crate::tools::manipulation::write_pdf;
+use crate::tools::session::{check_path_allowed, DocumentSource};
 
 /// Metadata about a stored document.
 #[derive(Debug, Clone)]
@@ -18,42 +25,166 @@ pub struct DocumentInfo {
     pub page_count: i32,
     /// When the document was uploaded.
     pub created_at: Instant,
+    /// Upload time as nanoseconds since the Unix epoch (stable ordering key).
+    pub created_unix_nanos: u128,
     /// When the document was last accessed.
     pub last_accessed: Instant,
+    /// Number of times the document has been accessed since import.
+    pub access_count: u64,
+    /// Size of the source document in bytes (memory usage estimate).
+    pub size_bytes: usize,
+    /// Human-readable file name, if known.
+    pub filename: Option<String>,
+    /// Where the document came from (e.g. "path:/foo.pdf", "base64:foo.pdf").
+    pub source_hint: String,
+    /// Canonical file path, for documents imported through
+    /// [`DocumentStore::get_or_import`].
+    pub source_path: Option<String>,
+    /// User-supplied labels.
+    pub tags: HashMap<String, String>,
+}
+
+/// A MuPDF document handle.
+///
+/// PDF documents are kept as [`PdfDocument`] so that PDF-specific
+/// operations (object access, saving, editing) remain available. Both
+/// variants deref to [`Document`].
+pub enum DocumentHandle {
+    /// A PDF document.
+    Pdf(PdfDocument),
+    /// Any other format supported by MuPDF (EPUB, XPS, images, ...).
+    Other(Document),
+}
+
+impl DocumentHandle {
+    /// Wrap a document, upgrading it to a [`PdfDocument`] if it is a PDF.
+    pub fn new(document: Document) -> Result<Self> {
+        if document.is_pdf() {
+            Ok(Self::Pdf(PdfDocument::try_from(document)?))
+        } else {
+            Ok(Self::Other(document))
+        }
+    }
+
+    /// The PDF-specific interface, if this is a PDF document.
+    pub fn as_pdf(&self) -> Option<&PdfDocument> {
+        match self {
+            Self::Pdf(pdf) => Some(pdf),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// The mutable PDF-specific interface, if this is a PDF document.
+    pub fn as_pdf_mut(&mut self) -> Option<&mut PdfDocument> {
+        match self {
+            Self::Pdf(pdf) => Some(pdf),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl Deref for DocumentHandle {
+    type Target = Document;
+
+    fn deref(&self) -> &Document {
+        match self {
+            Self::Pdf(pdf) => pdf,
+            Self::Other(doc) => doc,
+        }
+    }
+}
+
+impl DerefMut for DocumentHandle {
+    fn deref_mut(&mut self) -> &mut Document {
+        match self {
+            Self::Pdf(pdf) => pdf,
+            Self::Other(doc) => doc,
+        }
+    }
 }
 
 /// A stored document with its metadata.
 pub struct StoredDocument {
     /// The MuPDF document handle.
-    pub document: Document,
+    pub document: DocumentHandle,
     /// Document metadata.
     pub info: DocumentInfo,
 }
 
+/// A stored document behind its own lock, so that operations on different
+/// documents do not block each other.
+type SharedDocument = Arc<Mutex<StoredDocument>>;
+
+/// Lock a single document.
+fn lock_document(document: &SharedDocument) -> Result<MutexGuard<'_, StoredDocument>> {
+    document
+        .lock()
+        .map_err(|e| MupdfServerError::internal(format!("Failed to lock document: {}", e)))
+}
+
+/// Whether a PDF is encrypted, even if it has been unlocked.
+fn is_encrypted(pdf: &PdfDocument) -> Result<bool> {
+    Ok(pdf.trailer()?.get_dict("Encrypt")?.is_some())
+}
+
 impl StoredDocument {
-    /// Create a new stored document.
-    pub fn new(document: Document) -> Result<Self> {
+    /// Create a new stored document of `size_bytes` source bytes.
+    pub fn new(
+        document: Document,
+        size_bytes: usize,
+        filename: Option<String>,
+        source_hint: String,
+    ) -> Result<Self> {
         let page_count = document.page_count()?;
         let now = Instant::now();
         let id = Uuid::new_v4().to_string();
 
         Ok(Self {
-            document,
+            document: DocumentHandle::new(document)?,
             info: DocumentInfo {
                 id,
                 page_count,
                 created_at: now,
+                created_unix_nanos: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default(),
                 last_accessed: now,
+                access_count: 0,
+                size_bytes,
+                filename,
+                source_hint,
+                source_path: None,
+                tags: HashMap::new(),
             },
         })
     }
 
-    /// Update the last accessed timestamp.
+    /// Update the last accessed timestamp and bump the access count.
     pub fn touch(&mut self) {
         self.info.last_accessed = Instant::now();
+        self.info.access_count += 1;
     }
 }
 
+/// Result of inserting a document into the store.
+#[derive(Debug, Clone)]
+pub struct InsertResult {
+    /// ID assigned to the inserted document.
+    pub document_id: String,
+    /// ID of the document evicted to make room, if the store was full.
+    pub evicted: Option<String>,
+}
+
+/// Default largest pixmap, in pixels, that render tools may allocate (50 MP).
+pub const DEFAULT_MAX_RENDER_PIXELS: u64 = 50_000_000;
+
+/// Default largest chunked upload, in bytes (256 MiB).
+pub const DEFAULT_MAX_UPLOAD_BYTES: usize = 256 * 1024 * 1024;
+
+/// Maximum number of chunked uploads in progress at once.
+const MAX_PENDING_UPLOADS: usize = 16;
+
 /// Thread-safe document store.
 ///
 /// Note: MuPDF Document is !Send and !Sync, so we need to be careful
@@ -62,18 +193,131 @@ impl StoredDocument {
 #[derive(Clone)]
 pub struct DocumentStore {
     inner: Arc<Mutex<DocumentStoreInner>>,
+    /// Documents not accessed within this duration are purged.
+    ttl: Option<Duration>,
+    /// Maximum number of open documents before LRU eviction kicks in.
+    max_documents: Option<usize>,
+    /// Directories that file path sources must reside in (canonicalized).
+    allowed_paths: Option<Arc<[PathBuf]>>,
+    /// Whether URL sources may be downloaded; `None` allows them unless
+    /// file paths are restricted.
+    allow_url_sources: Option<bool>,
+    /// Largest scale factor accepted by render tools.
+    max_render_scale: Option<f32>,
+    /// Largest pixmap, in pixels, that render tools may allocate.
+    max_render_pixels: u64,
+    /// Largest chunked upload, in bytes.
+    max_upload_bytes: usize,
+}
+
+/// Format version written to store snapshots.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// zstd compression level used for store snapshots.
+const SNAPSHOT_COMPRESSION_LEVEL: i32 = 3;
+
+/// On-disk store snapshot (zstd-compressed JSON).
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    documents: Vec<SnapshotDocument>,
+}
+
+/// A single document in a store snapshot.
+#[derive(Serialize, Deserialize)]
+struct SnapshotDocument {
+    id: String,
+    filename: Option<String>,
+    source_hint: String,
+    source_path: Option<String>,
+    created_unix_nanos: u128,
+    access_count: u64,
+    tags: HashMap<String, String>,
+    /// Base64-encoded PDF bytes, including unsaved edits.
+    data: String,
+}
+
+/// A chunked upload that has not been finalized yet.
+struct PendingUpload {
+    /// Decoded bytes received so far.
+    data: Vec<u8>,
+    /// Index expected for the next chunk.
+    next_chunk: u32,
+    /// When the last chunk arrived.
+    last_updated: Instant,
 }
 
 struct DocumentStoreInner {
-    documents: HashMap<String, StoredDocument>,
+    documents: HashMap<String, SharedDocument>,
+    /// Canonical source path -> ID of the document imported from it.
+    path_index: HashMap<String, String>,
+    /// Stream ID -> chunked upload in progress.
+    pending_uploads: HashMap<String, PendingUpload>,
+}
+
+impl DocumentStoreInner {
+    /// Insert a document, evicting the least recently accessed one first if
+    /// the store holds `max_documents` already.
+    ///
+    /// Documents in use by another operation are never evicted; if every
+    /// document is in use, the insert fails with
+    /// [`MupdfServerError::StoreFull`].
+    fn insert_document(
+        &mut self,
+        stored: StoredDocument,
+        max_documents: Option<usize>,
+    ) -> Result<InsertResult> {
+        let id = stored.info.id.clone();
+
+        let evicted = match max_documents {
+            Some(max) if self.documents.len() >= max => Some(
+                self.documents
+                    .iter()
+                    .filter_map(|(id, d)| Some((id, d.try_lock().ok()?.info.last_accessed)))
+                    .min_by_key(|(_, last_accessed)| *last_accessed)
+                    .map(|(id, _)| id.clone())
+                    .ok_or(MupdfServerError::StoreFull { max })?,
+            ),
+            _ => None,
+        };
+        if let Some(evicted_id) = &evicted {
+            self.remove_document(evicted_id);
+        }
+
+        if let Some(path) = &stored.info.source_path {
+            self.path_index.insert(path.clone(), id.clone());
+        }
+        self.documents
+            .insert(id.clone(), Arc::new(Mutex::new(stored)));
+        Ok(InsertResult {
+            document_id: id,
+            evicted,
+        })
+    }
+
+    /// Remove a document and its path index entry.
+    ///
+    /// An operation still running on the document keeps it alive until it
+    /// finishes.
+    fn remove_document(&mut self, id: &str) -> Option<SharedDocument> {
+        let stored = self.documents.remove(id)?;
+        self.path_index.retain(|_, document_id| document_id != id);
+        Some(stored)
+    }
 }
 
-// SAFETY: DocumentStoreInner contains MuPDF Document which is !Send because it
-// contains raw pointers. However, all access to documents is guarded by a Mutex,
-// and documents are never actually moved across threads - they are created and
-// used within the critical section. The Arc<Mutex<>> wrapper ensures proper
-// synchronization.
-unsafe impl Send for DocumentStoreInner {}
+impl Drop for DocumentStoreInner {
+    fn drop(&mut self) {
+        // Free MuPDF resources deterministically when the last store handle goes away
+        self.documents.clear();
+    }
+}
+
+// SAFETY: StoredDocument contains MuPDF Document which is !Send because it
+// contains raw pointers. However, every stored document sits behind its own
+// Mutex, and documents are only used while that lock is held. The
+// Arc<Mutex<>> wrapper ensures proper synchronization.
+unsafe impl Send for StoredDocument {}
 
 impl Default for DocumentStore {
     fn default() -> Self {
@@ -87,27 +331,240 @@ impl DocumentStore {
         Self {
             inner: Arc::new(Mutex::new(DocumentStoreInner {
                 documents: HashMap::new(),
+                path_index: HashMap::new(),
+                pending_uploads: HashMap::new(),
             })),
+            ttl: None,
+            max_documents: None,
+            allowed_paths: None,
+            allow_url_sources: None,
+            max_render_scale: None,
+            max_render_pixels: DEFAULT_MAX_RENDER_PIXELS,
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES,
         }
     }
 
+    /// Create a new empty document store whose documents expire after
+    /// `seconds` without being accessed.
+    ///
+    /// Expired documents are only removed when [`purge_expired`] runs.
+    ///
+    /// [`purge_expired`]: DocumentStore::purge_expired
+    pub fn with_ttl(seconds: u64) -> Self {
+        Self::new().ttl_seconds(seconds)
+    }
+
+    /// Set the document time-to-live.
+    pub fn ttl_seconds(mut self, seconds: u64) -> Self {
+        self.ttl = Some(Duration::from_secs(seconds));
+        self
+    }
+
+    /// Limit the store to `max` documents (minimum 1).
+    ///
+    /// Inserting into a full store evicts the least recently accessed
+    /// document, or fails if every document is in use.
+    pub fn max_documents(mut self, max: usize) -> Self {
+        self.max_documents = Some(max.max(1));
+        self
+    }
+
+    /// Only allow file path sources inside these directories.
+    ///
+    /// Paths are canonicalized here; entries that cannot be resolved are
+    /// kept as given.
+    pub fn allowed_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|p| p.canonicalize().unwrap_or(p))
+            .collect();
+        self.allowed_paths = Some(paths.into());
+        self
+    }
+
+    /// Allow or refuse downloading URL sources.
+    ///
+    /// By default URLs are allowed unless file paths are restricted with
+    /// [`allowed_paths`](Self::allowed_paths).
+    pub fn allow_url_sources(mut self, allow: bool) -> Self {
+        self.allow_url_sources = Some(allow);
+        self
+    }
+
+    /// Reject render requests with a scale factor above `max`.
+    pub fn max_render_scale(mut self, max: f32) -> Self {
+        self.max_render_scale = Some(max);
+        self
+    }
+
+    /// Reject renders whose pixmap would exceed `max` pixels
+    /// (default [`DEFAULT_MAX_RENDER_PIXELS`]).
+    pub fn max_render_pixels(mut self, max: u64) -> Self {
+        self.max_render_pixels = max;
+        self
+    }
+
+    /// Reject chunked uploads larger than `max` bytes
+    /// (default [`DEFAULT_MAX_UPLOAD_BYTES`]).
+    pub fn max_upload_bytes(mut self, max: usize) -> Self {
+        self.max_upload_bytes = max;
+        self
+    }
+
+    /// The configured document time-to-live, if any.
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// Directories file path sources are restricted to, if configured.
+    pub fn allowed_path_roots(&self) -> Option<&[PathBuf]> {
+        self.allowed_paths.as_deref()
+    }
+
+    /// Whether URL sources may be downloaded.
+    pub fn url_sources_allowed(&self) -> bool {
+        self.allow_url_sources
+            .unwrap_or(self.allowed_paths.is_none())
+    }
+
+    /// The configured maximum render scale, if any.
+    pub fn render_scale_limit(&self) -> Option<f32> {
+        self.max_render_scale
+    }
+
+    /// The largest pixmap, in pixels, render tools may allocate.
+    pub fn render_pixel_limit(&self) -> u64 {
+        self.max_render_pixels
+    }
+
     /// Insert a document into the store.
     ///
-    /// Returns the document ID.
-    pub fn insert(&self, document: Document) -> Result<String> {
-        let stored = StoredDocument::new(document)?;
-        let id = stored.info.id.clone();
+    /// If the store is at capacity, the least recently accessed document is
+    /// evicted first; if every document is in use, the insert fails with
+    /// [`MupdfServerError::StoreFull`]. Returns the new document ID and the evicted ID, if any.
+    pub fn insert(&self, document: Document) -> Result<InsertResult> {
+        self.insert_with_size(document, 0)
+    }
+
+    /// Insert a document into the store, recording its size in bytes.
+    ///
+    /// Behaves like [`insert`](Self::insert); the size is reported by
+    /// [`total_memory_bytes`](Self::total_memory_bytes).
+    pub fn insert_with_size(&self, document: Document, size_bytes: usize) -> Result<InsertResult> {
+        self.insert_with_source(document, size_bytes, None, "memory".to_string())
+    }
+
+    /// Insert a document into the store, recording its size and origin.
+    ///
+    /// `filename` and `source_hint` are reported by
+    /// [`list`](Self::list) to help identify the document.
+    pub fn insert_with_source(
+        &self,
+        document: Document,
+        size_bytes: usize,
+        filename: Option<String>,
+        source_hint: String,
+    ) -> Result<InsertResult> {
+        let stored = StoredDocument::new(document, size_bytes, filename, source_hint)?;
 
         let mut inner = self.inner.lock().map_err(|e| {
             MupdfServerError::internal(format!("Failed to lock document store: {}", e))
         })?;
 
-        inner.documents.insert(id.clone(), stored);
-        Ok(id)
+        inner.insert_document(stored, self.max_documents)
     }
 
-    /// Get document info without accessing the document itself.
-    pub fn get_info(&self, id: &str) -> Result<DocumentInfo> {
+    /// Import the document at `path`, or return the ID of the document
+    /// already imported from the same file.
+    ///
+    /// Paths are compared after canonicalization, so different spellings
+    /// of one file share a single document. Encrypted files are never
+    /// shared: every import must supply the password and gets its own
+    /// document.
+    pub fn get_or_import(&self, path: &str, password: Option<&str>) -> Result<String> {
+        Ok(self.get_or_import_path(path, password)?.document_id)
+    }
+
+    /// Like [`get_or_import`](Self::get_or_import), also reporting any
+    /// document evicted to make room.
+    pub(crate) fn get_or_import_path(
+        &self,
+        path: &str,
+        password: Option<&str>,
+    ) -> Result<InsertResult> {
+        if let Some(allowed) = self.allowed_path_roots() {
+            check_path_allowed(path, allowed)?;
+        }
+        let canonical = Path::new(path)
+            .canonicalize()?
+            .to_string_lossy()
+            .into_owned();
+        if let Some(document_id) = self.find_by_path(&canonical)? {
+            return Ok(InsertResult {
+                document_id,
+                evicted: None,
+            });
+        }
+
+        let source = DocumentSource::FilePath {
+            path: path.to_string(),
+        };
+        let (document, size_bytes) = source.open_with_size(
+            password,
+            self.allowed_path_roots(),
+            self.url_sources_allowed(),
+        )?;
+        let mut stored = StoredDocument::new(
+            document,
+            size_bytes,
+            source.filename(),
+            source.source_hint(),
+        )?;
+        let encrypted = match stored.document.as_pdf() {
+            Some(pdf) => is_encrypted(pdf)?,
+            None => false,
+        };
+        if !encrypted {
+            stored.info.source_path = Some(canonical.clone());
+        }
+
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        // Another call may have imported the same file while this one was opening it
+        if !encrypted {
+            if let Some(document_id) = inner.path_index.get(&canonical).cloned() {
+                return Ok(InsertResult {
+                    document_id,
+                    evicted: None,
+                });
+            }
+        }
+
+        inner.insert_document(stored, self.max_documents)
+    }
+
+    /// ID of the document imported from a canonical path, marking it accessed.
+    fn find_by_path(&self, canonical: &str) -> Result<Option<String>> {
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        let Some(id) = inner.path_index.get(canonical).cloned() else {
+            return Ok(None);
+        };
+        let Some(document) = inner.documents.get(&id).cloned() else {
+            return Ok(None);
+        };
+        drop(inner);
+
+        lock_document(&document)?.touch();
+        Ok(Some(id))
+    }
+
+    /// Look up a document, holding the store lock only for the lookup.
+    fn document(&self, id: &str) -> Result<SharedDocument> {
         let inner = self.inner.lock().map_err(|e| {
             MupdfServerError::internal(format!("Failed to lock document store: {}", e))
         })?;
@@ -115,10 +572,132 @@ impl DocumentStore {
         inner
             .documents
             .get(id)
-            .map(|d| d.info.clone())
+            .cloned()
             .ok_or_else(|| MupdfServerError::DocumentNotFound(id.to_string()))
     }
 
+    /// Look up a document about to be modified in place.
+    ///
+    /// The document no longer matches its source file, so it is removed from
+    /// the path index: later imports of the file open it afresh instead of
+    /// sharing the edited copy. Like [`document`](Self::document), the store
+    /// lock is released before the operation runs.
+    fn document_for_edit(&self, id: &str) -> Result<SharedDocument> {
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        let document = inner
+            .documents
+            .get(id)
+            .cloned()
+            .ok_or_else(|| MupdfServerError::DocumentNotFound(id.to_string()))?;
+        inner.path_index.retain(|_, document_id| document_id != id);
+        Ok(document)
+    }
+
+    /// Snapshot of every stored document, taken under the store lock.
+    fn documents(&self) -> Result<Vec<SharedDocument>> {
+        let inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        Ok(inner.documents.values().cloned().collect())
+    }
+
+    /// Set a tag on a document, replacing any previous value for `key`.
+    ///
+    /// Tagging does not count as an access.
+    pub fn set_tag(&self, id: &str, key: String, value: String) -> Result<()> {
+        let document = self.document(id)?;
+        lock_document(&document)?.info.tags.insert(key, value);
+        Ok(())
+    }
+
+    /// Append a chunk to a pending upload.
+    ///
+    /// Chunk 0 starts the stream (discarding any earlier data under the same
+    /// ID); later chunks must arrive in order. Only a few streams may be in
+    /// progress at once, and a stream that grows past the upload size limit
+    /// is dropped. Returns the number of
+    /// chunks and bytes received so far.
+    pub fn append_upload_chunk(
+        &self,
+        stream_id: &str,
+        chunk_index: u32,
+        data: &[u8],
+    ) -> Result<(u32, usize)> {
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        if chunk_index == 0 {
+            if !inner.pending_uploads.contains_key(stream_id)
+                && inner.pending_uploads.len() >= MAX_PENDING_UPLOADS
+            {
+                return Err(MupdfServerError::InvalidParams(format!(
+                    "Too many uploads in progress (maximum {})",
+                    MAX_PENDING_UPLOADS
+                )));
+            }
+            inner.pending_uploads.insert(
+                stream_id.to_string(),
+                PendingUpload {
+                    data: Vec::new(),
+                    next_chunk: 0,
+                    last_updated: Instant::now(),
+                },
+            );
+        }
+        let upload = inner.pending_uploads.get_mut(stream_id).ok_or_else(|| {
+            MupdfServerError::InvalidParams(format!(
+                "Unknown upload stream: {} (start with chunk_index 0)",
+                stream_id
+            ))
+        })?;
+        if chunk_index != upload.next_chunk {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "Expected chunk_index {} for stream {}, got {}",
+                upload.next_chunk, stream_id, chunk_index
+            )));
+        }
+
+        if upload.data.len() + data.len() > self.max_upload_bytes {
+            inner.pending_uploads.remove(stream_id);
+            return Err(MupdfServerError::PayloadTooLarge(format!(
+                "Upload {} exceeds {} bytes",
+                stream_id, self.max_upload_bytes
+            )));
+        }
+
+        upload.data.extend_from_slice(data);
+        upload.next_chunk += 1;
+        upload.last_updated = Instant::now();
+        Ok((upload.next_chunk, upload.data.len()))
+    }
+
+    /// Remove a pending upload and return its bytes.
+    pub fn take_upload(&self, stream_id: &str) -> Result<Vec<u8>> {
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        inner
+            .pending_uploads
+            .remove(stream_id)
+            .map(|upload| upload.data)
+            .ok_or_else(|| {
+                MupdfServerError::InvalidParams(format!("Unknown upload stream: {}", stream_id))
+            })
+    }
+
+    /// Get document info without accessing the document itself.
+    pub fn get_info(&self, id: &str) -> Result<DocumentInfo> {
+        let document = self.document(id)?;
+        let info = lock_document(&document)?.info.clone();
+        Ok(info)
+    }
+
     /// Execute a function with access to a document.
     ///
     /// This is the primary way to interact with documents, as it handles
@@ -127,35 +706,87 @@ impl DocumentStore {
     where
         F: FnOnce(&Document) -> Result<T>,
     {
-        let mut inner = self.inner.lock().map_err(|e| {
-            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
-        })?;
+        // The store lock is released before the operation runs; only this
+        // document stays locked
+        let document = self.document(id)?;
+        let mut stored = lock_document(&document)?;
 
-        let stored = inner
-            .documents
-            .get_mut(id)
-            .ok_or_else(|| MupdfServerError::DocumentNotFound(id.to_string()))?;
+        stored.touch();
+        f(&stored.document)
+    }
+
+    /// Execute a function with access to the document handle, for operations
+    /// that use PDF-specific features when available.
+    pub fn with_document_handle<F, T>(&self, id: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(&DocumentHandle) -> Result<T>,
+    {
+        // The store lock is released before the operation runs; only this
+        // document stays locked
+        let document = self.document(id)?;
+        let mut stored = lock_document(&document)?;
 
         stored.touch();
         f(&stored.document)
     }
 
     /// Execute a function with mutable access to a document.
+    ///
+    /// The document is detached from its source path, so later imports of
+    /// the same file do not share the edited copy.
     pub fn with_document_mut<F, T>(&self, id: &str, f: F) -> Result<T>
     where
         F: FnOnce(&mut Document) -> Result<T>,
     {
-        let mut inner = self.inner.lock().map_err(|e| {
-            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
-        })?;
+        let document = self.document_for_edit(id)?;
+        let mut stored = lock_document(&document)?;
 
-        let stored = inner
-            .documents
-            .get_mut(id)
-            .ok_or_else(|| MupdfServerError::DocumentNotFound(id.to_string()))?;
+        stored.touch();
+        stored.info.source_path = None;
+        let result = f(&mut stored.document)?;
+        stored.info.page_count = stored.document.page_count()?;
+        Ok(result)
+    }
+
+    /// Execute a function with mutable access to a PDF document.
+    ///
+    /// The recorded page count is refreshed afterwards, so tools may add or
+    /// remove pages. The document is detached from its source path like in
+    /// [`with_document_mut`](Self::with_document_mut). Returns
+    /// [`MupdfServerError::NotAPdf`] if the document is not a PDF.
+    pub fn with_pdf_document_mut<F, T>(&self, id: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut PdfDocument) -> Result<T>,
+    {
+        let document = self.document_for_edit(id)?;
+        let mut stored = lock_document(&document)?;
+
+        stored.touch();
+        stored.info.source_path = None;
+        let pdf = stored
+            .document
+            .as_pdf_mut()
+            .ok_or(MupdfServerError::NotAPdf)?;
+        let result = f(pdf)?;
+        stored.info.page_count = stored.document.page_count()?;
+        Ok(result)
+    }
+
+    /// Execute a function with access to a PDF document.
+    ///
+    /// Returns [`MupdfServerError::NotAPdf`] if the document is not a PDF.
+    pub fn with_pdf_document<F, T>(&self, id: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(&PdfDocument) -> Result<T>,
+    {
+        // The store lock is released before the operation runs; only this
+        // document stays locked
+        let document = self.document(id)?;
+        let mut stored = lock_document(&document)?;
 
         stored.touch();
-        f(&mut stored.document)
+        let pdf = stored.document.as_pdf().ok_or(MupdfServerError::NotAPdf)?;
+        f(pdf)
     }
 
     /// Remove a document from the store.
@@ -164,20 +795,180 @@ impl DocumentStore {
             MupdfServerError::internal(format!("Failed to lock document store: {}", e))
         })?;
 
-        if inner.documents.remove(id).is_none() {
+        if inner.remove_document(id).is_none() {
             return Err(MupdfServerError::DocumentNotFound(id.to_string()));
         }
 
         Ok(())
     }
 
+    /// Remove all documents not accessed within the configured TTL, and
+    /// uploads that received no chunk within it.
+    ///
+    /// Returns the IDs of the removed documents. Does nothing if the store
+    /// has no TTL.
+    pub fn purge_expired(&self) -> Result<Vec<String>> {
+        match self.ttl {
+            Some(ttl) => self.purge_idle(Some(ttl)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Remove all documents not accessed within `idle`, or every document
+    /// if `idle` is `None`. Pending uploads idle for as long are dropped too.
+    ///
+    /// Returns the IDs of the removed documents. With an `idle` limit,
+    /// documents in use by another operation are kept.
+    pub fn purge_idle(&self, idle: Option<Duration>) -> Result<Vec<String>> {
+        let mut inner = self.inner.lock().map_err(|e| {
+            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        })?;
+
+        let purged: Vec<String> = inner
+            .documents
+            .iter()
+            .filter(|(_, d)| match idle {
+                Some(idle) => d
+                    .try_lock()
+                    .is_ok_and(|stored| stored.info.last_accessed.elapsed() > idle),
+                None => true,
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &purged {
+            inner.remove_document(id);
+        }
+        inner.pending_uploads.retain(|_, upload| match idle {
+            Some(idle) => upload.last_updated.elapsed() <= idle,
+            None => false,
+        });
+
+        Ok(purged)
+    }
+
+    /// Remove every document and pending upload from the store.
+    ///
+    /// Returns the number of documents removed.
+    pub fn purge_all(&self) -> Result<usize> {
+        Ok(self.purge_idle(None)?.len())
+    }
+
     /// List all documents in the store.
     pub fn list(&self) -> Result<Vec<DocumentInfo>> {
-        let inner = self.inner.lock().map_err(|e| {
-            MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+        self.documents()?
+            .iter()
+            .map(|d| lock_document(d).map(|stored| stored.info.clone()))
+            .collect()
+    }
+
+    /// Write every stored PDF document and its metadata to a snapshot file
+    /// at `path`, replacing any existing file.
+    ///
+    /// Documents are saved with their current contents, including edits.
+    /// Non-PDF documents cannot be saved and are skipped, as are encrypted
+    /// documents, whose contents would otherwise land on disk unprotected.
+    pub fn export_snapshot(&self, path: &Path) -> Result<()> {
+        let mut documents = Vec::new();
+        for document in self.documents()? {
+            let stored = lock_document(&document)?;
+            let Some(pdf) = stored.document.as_pdf() else {
+                tracing::warn!(
+                    document_id = %stored.info.id,
+                    "skipping non-PDF document in snapshot"
+                );
+                continue;
+            };
+            if is_encrypted(pdf)? {
+                tracing::warn!(
+                    document_id = %stored.info.id,
+                    "skipping encrypted document in snapshot"
+                );
+                continue;
+            }
+
+            let buffer = write_pdf(pdf)?;
+            let info = &stored.info;
+            documents.push(SnapshotDocument {
+                id: info.id.clone(),
+                filename: info.filename.clone(),
+                source_hint: info.source_hint.clone(),
+                source_path: info.source_path.clone(),
+                created_unix_nanos: info.created_unix_nanos,
+                access_count: info.access_count,
+                tags: info.tags.clone(),
+                data: base64::engine::general_purpose::STANDARD.encode(&buffer),
+            });
+        }
+
+        let json = serde_json::to_vec(&Snapshot {
+            version: SNAPSHOT_VERSION,
+            documents,
         })?;
+        let compressed = zstd::encode_all(json.as_slice(), SNAPSHOT_COMPRESSION_LEVEL)?;
+
+        // Write next to the target and rename, so a crash never leaves a
+        // truncated snapshot behind
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        std::fs::write(&temp, compressed)?;
+        std::fs::rename(&temp, path)?;
+
+        Ok(())
+    }
+
+    /// Load the documents of a snapshot written by
+    /// [`export_snapshot`](Self::export_snapshot), keeping their IDs.
+    ///
+    /// Documents whose ID is already in the store are skipped, as are
+    /// documents that fail to open or still need a password. Returns the
+    /// number of documents loaded.
+    pub fn import_snapshot(&self, path: &Path) -> Result<usize> {
+        let compressed = std::fs::read(path)?;
+        let json = zstd::decode_all(compressed.as_slice())?;
+        let snapshot: Snapshot = serde_json::from_slice(&json)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "unsupported snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let mut loaded = 0;
+        for entry in snapshot.documents {
+            if self.document(&entry.id).is_ok() {
+                continue;
+            }
+
+            let bytes = base64::engine::general_purpose::STANDARD.decode(&entry.data)?;
+            let document = match Document::from_bytes(&bytes, "application/pdf") {
+                Ok(document) if !document.needs_password()? => document,
+                Ok(_) => {
+                    tracing::warn!(document_id = %entry.id, "skipping encrypted snapshot document");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(document_id = %entry.id, error = %e, "skipping unreadable snapshot document");
+                    continue;
+                }
+            };
 
-        Ok(inner.documents.values().map(|d| d.info.clone()).collect())
+            let mut stored =
+                StoredDocument::new(document, bytes.len(), entry.filename, entry.source_hint)?;
+            stored.info.id = entry.id;
+            stored.info.source_path = entry.source_path;
+            stored.info.created_unix_nanos = entry.created_unix_nanos;
+            stored.info.access_count = entry.access_count;
+            stored.info.tags = entry.tags;
+
+            let mut inner = self.inner.lock().map_err(|e| {
+                MupdfServerError::internal(format!("Failed to lock document store: {}", e))
+            })?;
+            inner.insert_document(stored, self.max_documents)?;
+            loaded += 1;
+        }
+
+        Ok(loaded)
     }
 
     /// Get the number of documents in the store.
@@ -189,6 +980,19 @@ impl DocumentStore {
         Ok(inner.documents.len())
     }
 
+    /// Get the number of documents in the store (alias for [`len`](Self::len)).
+    pub fn document_count(&self) -> Result<usize> {
+        self.len()
+    }
+
+    /// Estimated memory used by all documents (sum of their source sizes).
+    pub fn total_memory_bytes(&self) -> Result<usize> {
+        self.documents()?
+            .iter()
+            .map(|d| lock_document(d).map(|stored| stored.info.size_bytes))
+            .sum()
+    }
+
     /// Check if the store is empty.
     pub fn is_empty(&self) -> Result<bool> {
         Ok(self.len()? == 0)
@@ -199,8 +1003,20 @@ impl DocumentStore {
 mod tests {
     use super::*;
 
-    // Note: These tests require actual PDF files to work.
-    // For now, we just test the basic store operations.
+    const DUMMY_PDF: &[u8] = include_bytes!("../tests/fixtures/dummy.pdf");
+
+    fn dummy_document() -> Document {
+        Document::from_bytes(DUMMY_PDF, "application/pdf").unwrap()
+    }
+
+    /// Pretend a document was last accessed `secs` seconds ago.
+    fn age_document(store: &DocumentStore, id: &str, secs: u64) {
+        let document = store.document(id).unwrap();
+        let mut stored = document.lock().unwrap();
+        stored.info.last_accessed = Instant::now()
+            .checked_sub(Duration::from_secs(secs))
+            .unwrap();
+    }
 
     #[test]
     fn test_store_new() {
@@ -214,4 +1030,241 @@ mod tests {
         let list = store.list().unwrap();
         assert!(list.is_empty());
     }
+
+    #[test]
+    fn test_purge_expired_without_ttl() {
+        let store = DocumentStore::new();
+        let id = store.insert(dummy_document()).unwrap().document_id;
+        age_document(&store, &id, 3600);
+
+        assert!(store.purge_expired().unwrap().is_empty());
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_purge_expired_removes_stale_documents() {
+        let store = DocumentStore::with_ttl(60);
+        let stale = store.insert(dummy_document()).unwrap().document_id;
+        let fresh = store.insert(dummy_document()).unwrap().document_id;
+        age_document(&store, &stale, 120);
+
+        let purged = store.purge_expired().unwrap();
+        assert_eq!(purged, vec![stale.clone()]);
+        assert!(store.get_info(&stale).is_err());
+        assert!(store.get_info(&fresh).is_ok());
+    }
+
+    #[test]
+    fn test_purge_idle() {
+        let store = DocumentStore::new();
+        let stale = store.insert(dummy_document()).unwrap().document_id;
+        let fresh = store.insert(dummy_document()).unwrap().document_id;
+        age_document(&store, &stale, 120);
+
+        let purged = store.purge_idle(Some(Duration::from_secs(60))).unwrap();
+        assert_eq!(purged, vec![stale]);
+        assert!(store.get_info(&fresh).is_ok());
+
+        assert_eq!(store.purge_idle(None).unwrap(), vec![fresh]);
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_access_refreshes_ttl() {
+        let store = DocumentStore::with_ttl(60);
+        let id = store.insert(dummy_document()).unwrap().document_id;
+        age_document(&store, &id, 120);
+
+        // Accessing the document resets its last-accessed time
+        store.with_document(&id, |_| Ok(())).unwrap();
+
+        assert!(store.purge_expired().unwrap().is_empty());
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_insert_evicts_least_recently_accessed() {
+        let store = DocumentStore::new().max_documents(2);
+        let first = store.insert(dummy_document()).unwrap().document_id;
+        let second = store.insert(dummy_document()).unwrap().document_id;
+        age_document(&store, &first, 20);
+        age_document(&store, &second, 10);
+
+        // Touch the older document so the second becomes least recently used
+        store.with_document(&first, |_| Ok(())).unwrap();
+
+        let third = store.insert(dummy_document()).unwrap();
+        assert_eq!(third.evicted, Some(second.clone()));
+        assert_eq!(store.len().unwrap(), 2);
+        assert!(store.get_info(&first).is_ok());
+        assert!(store.get_info(&second).is_err());
+        assert!(store.get_info(&third.document_id).is_ok());
+    }
+
+    #[test]
+    fn test_insert_fails_when_all_documents_in_use() {
+        let store = DocumentStore::new().max_documents(1);
+        let first = store.insert(dummy_document()).unwrap().document_id;
+
+        let document = store.document(&first).unwrap();
+        let _in_use = document.lock().unwrap();
+        let result = store.insert(dummy_document());
+        assert!(matches!(
+            result,
+            Err(MupdfServerError::StoreFull { max: 1 })
+        ));
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_insert_below_capacity_does_not_evict() {
+        let store = DocumentStore::new().max_documents(3);
+        for _ in 0..3 {
+            assert!(store.insert(dummy_document()).unwrap().evicted.is_none());
+        }
+        assert_eq!(store.len().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_purge_all() {
+        let store = DocumentStore::new();
+        assert_eq!(store.purge_all().unwrap(), 0);
+
+        store.insert(dummy_document()).unwrap();
+        store.insert(dummy_document()).unwrap();
+        assert_eq!(store.document_count().unwrap(), 2);
+
+        assert_eq!(store.purge_all().unwrap(), 2);
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_upload_size_limit() {
+        let store = DocumentStore::new().max_upload_bytes(8);
+        assert_eq!(store.append_upload_chunk("a", 0, b"12345").unwrap(), (1, 5));
+
+        let result = store.append_upload_chunk("a", 1, b"6789");
+        assert!(matches!(result, Err(MupdfServerError::PayloadTooLarge(_))));
+        // The oversized stream is dropped
+        assert!(store.take_upload("a").is_err());
+    }
+
+    #[test]
+    fn test_pending_upload_limit() {
+        let store = DocumentStore::new();
+        for i in 0..MAX_PENDING_UPLOADS {
+            store
+                .append_upload_chunk(&format!("upload-{}", i), 0, b"x")
+                .unwrap();
+        }
+
+        let result = store.append_upload_chunk("one-too-many", 0, b"x");
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+        // Restarting an existing stream is still allowed
+        store.append_upload_chunk("upload-0", 0, b"x").unwrap();
+    }
+
+    #[test]
+    fn test_purge_expired_drops_stale_uploads() {
+        let store = DocumentStore::with_ttl(60);
+        store.append_upload_chunk("stale", 0, b"x").unwrap();
+        store.append_upload_chunk("fresh", 0, b"x").unwrap();
+        store
+            .inner
+            .lock()
+            .unwrap()
+            .pending_uploads
+            .get_mut("stale")
+            .unwrap()
+            .last_updated = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap();
+
+        store.purge_expired().unwrap();
+        assert!(store.take_upload("stale").is_err());
+        assert!(store.take_upload("fresh").is_ok());
+    }
+
+    #[test]
+    fn test_purge_all_drops_uploads() {
+        let store = DocumentStore::new();
+        store.append_upload_chunk("upload", 0, b"x").unwrap();
+
+        store.purge_all().unwrap();
+        assert!(store.take_upload("upload").is_err());
+    }
+
+    #[test]
+    fn test_total_memory_bytes() {
+        let store = DocumentStore::new();
+        assert_eq!(store.total_memory_bytes().unwrap(), 0);
+
+        let first = store
+            .insert_with_size(dummy_document(), DUMMY_PDF.len())
+            .unwrap()
+            .document_id;
+        assert_eq!(store.total_memory_bytes().unwrap(), DUMMY_PDF.len());
+
+        let second = store
+            .insert_with_size(dummy_document(), 1000)
+            .unwrap()
+            .document_id;
+        assert_eq!(store.total_memory_bytes().unwrap(), DUMMY_PDF.len() + 1000);
+        assert_eq!(store.get_info(&second).unwrap().size_bytes, 1000);
+
+        store.remove(&first).unwrap();
+        assert_eq!(store.total_memory_bytes().unwrap(), 1000);
+    }
+
+    #[test]
+    fn test_get_or_import_reuses_document() {
+        let store = DocumentStore::new();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dummy.pdf");
+        let dotted = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/../fixtures/dummy.pdf"
+        );
+
+        let first = store.get_or_import(path, None).unwrap();
+        let second = store.get_or_import(dotted, None).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(store.len().unwrap(), 1);
+
+        // Closing the document drops it from the path index
+        store.remove(&first).unwrap();
+        let third = store.get_or_import(path, None).unwrap();
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn test_documents_lock_independently() {
+        let store = DocumentStore::new();
+        let busy = store.insert(dummy_document()).unwrap().document_id;
+        let other = store.insert(dummy_document()).unwrap().document_id;
+
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let worker = {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                store
+                    .with_document(&busy, |_| {
+                        started_tx.send(()).unwrap();
+                        // Hold this document until the other one was used
+                        done_rx.recv().unwrap();
+                        Ok(())
+                    })
+                    .unwrap();
+            })
+        };
+
+        started_rx.recv().unwrap();
+        let page_count = store
+            .with_document(&other, |doc| Ok(doc.page_count()?))
+            .unwrap();
+        assert_eq!(page_count, 1);
+        assert_eq!(store.len().unwrap(), 2);
+        done_tx.send(()).unwrap();
+        worker.join().unwrap();
+    }
 }
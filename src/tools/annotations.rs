@@ -0,0 +1,210 @@
+//! Annotation tools: comments, highlights, stamps and other page markup.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::state::DocumentStore;
+use crate::tools::page::validate_page_number;
+
+/// Convert an annotation color (gray, RGB or CMYK components) to RGB.
+fn color_to_rgb(components: &[f32]) -> Option<[f32; 3]> {
+    match *components {
+        [g] => Some([g, g, g]),
+        [r, g, b] => Some([r, g, b]),
+        [c, m, y, k] => Some([
+            (1.0 - c) * (1.0 - k),
+            (1.0 - m) * (1.0 - k),
+            (1.0 - y) * (1.0 - k),
+        ]),
+        _ => None,
+    }
+}
+
+// ============== Get Page Annotations ==============
+
+/// Parameters for listing the annotations on a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageAnnotationsParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// An annotation on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AnnotationEntry {
+    /// Annotation subtype (e.g. "Text", "Highlight", "Stamp").
+    pub annotation_type: String,
+    /// Bounding box on the page as `[x0, y0, x1, y1]`.
+    pub bounds: [f32; 4],
+    /// Text contents (comment body), if any.
+    pub content: Option<String>,
+    /// Author, if recorded.
+    pub author: Option<String>,
+    /// Creation date as stored in the document (PDF date string).
+    pub creation_date: Option<String>,
+    /// Last modification date as stored in the document (PDF date string).
+    pub modified_date: Option<String>,
+    /// Annotation color as RGB components in the range 0-1.
+    pub color: Option<[f32; 3]>,
+}
+
+/// Result of listing page annotations.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageAnnotationsResult {
+    /// Annotations in page order.
+    pub annotations: Vec<AnnotationEntry>,
+}
+
+/// Collect the annotations of a loaded page.
+fn page_annotations(page: &mupdf::Page) -> Result<Vec<AnnotationEntry>> {
+    let mut annotations = Vec::new();
+    for annot in page.annotations()? {
+        let rect = annot.rect()?;
+        annotations.push(AnnotationEntry {
+            annotation_type: format!("{:?}", annot.r#type()?),
+            bounds: [rect.x0, rect.y0, rect.x1, rect.y1],
+            content: annot.contents()?.filter(|s| !s.is_empty()),
+            author: annot.author()?.filter(|s| !s.is_empty()),
+            creation_date: annot.creation_date()?,
+            modified_date: annot.modification_date()?,
+            color: annot.color()?.as_deref().and_then(color_to_rgb),
+        });
+    }
+    Ok(annotations)
+}
+
+/// List the annotations on a page.
+pub fn get_page_annotations(
+    store: &DocumentStore,
+    params: GetPageAnnotationsParams,
+) -> Result<GetPageAnnotationsResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        Ok(GetPageAnnotationsResult {
+            annotations: page_annotations(&page)?,
+        })
+    })
+}
+
+// ============== Get Annotations (All Pages) ==============
+
+/// Parameters for listing the annotations of every page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetAnnotationsAllParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Only return annotations of these types (case-insensitive, e.g. ["Highlight", "Text"]).
+    #[serde(default)]
+    pub annotation_type_filter: Option<Vec<String>>,
+}
+
+/// An annotation with the page it is on.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AnnotationEntryWithPage {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Annotation details.
+    #[serde(flatten)]
+    pub annotation: AnnotationEntry,
+}
+
+/// Result of listing the annotations of every page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetAnnotationsAllResult {
+    /// Annotations in page order.
+    pub annotations: Vec<AnnotationEntryWithPage>,
+    /// Total number of pages in the document.
+    pub page_count: i32,
+    /// Number of annotations returned.
+    pub total: usize,
+}
+
+/// List the annotations on every page of a document.
+pub fn get_page_annotations_all(
+    store: &DocumentStore,
+    params: GetAnnotationsAllParams,
+) -> Result<GetAnnotationsAllResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_count = doc.page_count()?;
+        let matches_filter = |annotation: &AnnotationEntry| match &params.annotation_type_filter {
+            Some(types) => types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&annotation.annotation_type)),
+            None => true,
+        };
+
+        let mut annotations = Vec::new();
+        for page_number in 0..page_count {
+            let page = doc.load_page(page_number)?;
+            annotations.extend(
+                page_annotations(&page)?
+                    .into_iter()
+                    .filter(|a| matches_filter(a))
+                    .map(|annotation| AnnotationEntryWithPage {
+                        page: page_number,
+                        annotation,
+                    }),
+            );
+        }
+
+        Ok(GetAnnotationsAllResult {
+            total: annotations.len(),
+            annotations,
+            page_count,
+        })
+    })
+}
+
+// ============== Get Annotations Summary ==============
+
+/// Parameters for counting the annotations of a document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetAnnotationsSummaryParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Annotation counts for a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetAnnotationsSummaryResult {
+    /// Number of annotations of each subtype (e.g. "Highlight": 3).
+    pub by_type: HashMap<String, usize>,
+    /// Number of annotations on each page; `by_page[i]` is page i.
+    pub by_page: Vec<usize>,
+    /// Total number of annotations.
+    pub total: usize,
+}
+
+/// Count the annotations of a document by type and by page.
+pub fn get_page_annotations_summary(
+    store: &DocumentStore,
+    params: GetAnnotationsSummaryParams,
+) -> Result<GetAnnotationsSummaryResult> {
+    store.with_document(&params.document_id, |doc| {
+        let mut by_type = HashMap::new();
+        let mut by_page = Vec::new();
+
+        for page_number in 0..doc.page_count()? {
+            let page = doc.load_page(page_number)?;
+            let mut count = 0;
+            for annot in page.annotations()? {
+                *by_type.entry(format!("{:?}", annot.r#type()?)).or_insert(0) += 1;
+                count += 1;
+            }
+            by_page.push(count);
+        }
+
+        Ok(GetAnnotationsSummaryResult {
+            total: by_page.iter().sum(),
+            by_type,
+            by_page,
+        })
+    })
+}
@@ -1,11 +1,22 @@
 //! Document-level operations: metadata, page count, outlines, etc.
 
-use mupdf::MetadataName;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use base64::Engine;
+use mupdf::pdf::{PdfDocument, PdfObject};
+use mupdf::{DestinationKind, MetadataName, Permission, TextPageFlags, WidgetType};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
-use crate::error::Result;
-use crate::state::DocumentStore;
+use crate::error::{MupdfServerError, Result};
+use crate::state::{DocumentHandle, DocumentStore};
+use crate::tools::images::collect_page_images;
+use crate::tools::page::{default_true, validate_page_number};
+use crate::tools::pdf_object::{
+    dict_int, dict_name, dict_string, inherited_entry, name_tree_entries, number_tree_entries,
+};
+use crate::tools::text::page_font_names;
 
 // ============== Get Page Count ==============
 
@@ -64,46 +75,543 @@ pub struct GetMetadataResult {
     pub modification_date: Option<String>,
 }
 
+/// Read the metadata of an open document.
+pub(crate) fn read_metadata(doc: &mupdf::Document) -> GetMetadataResult {
+    GetMetadataResult {
+        title: doc
+            .metadata(MetadataName::Title)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        author: doc
+            .metadata(MetadataName::Author)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        subject: doc
+            .metadata(MetadataName::Subject)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        keywords: doc
+            .metadata(MetadataName::Keywords)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        creator: doc
+            .metadata(MetadataName::Creator)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        producer: doc
+            .metadata(MetadataName::Producer)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        creation_date: doc
+            .metadata(MetadataName::CreationDate)
+            .ok()
+            .filter(|s| !s.is_empty()),
+        modification_date: doc
+            .metadata(MetadataName::ModDate)
+            .ok()
+            .filter(|s| !s.is_empty()),
+    }
+}
+
 /// Get document metadata.
 pub fn get_metadata(store: &DocumentStore, params: GetMetadataParams) -> Result<GetMetadataResult> {
-    store.with_document(&params.document_id, |doc| {
-        Ok(GetMetadataResult {
-            title: doc
-                .metadata(MetadataName::Title)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            author: doc
-                .metadata(MetadataName::Author)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            subject: doc
-                .metadata(MetadataName::Subject)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            keywords: doc
-                .metadata(MetadataName::Keywords)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            creator: doc
-                .metadata(MetadataName::Creator)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            producer: doc
-                .metadata(MetadataName::Producer)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            creation_date: doc
-                .metadata(MetadataName::CreationDate)
-                .ok()
-                .filter(|s| !s.is_empty()),
-            modification_date: doc
-                .metadata(MetadataName::ModDate)
-                .ok()
-                .filter(|s| !s.is_empty()),
+    store.with_document(&params.document_id, |doc| Ok(read_metadata(doc)))
+}
+
+// ============== Get XMP Metadata ==============
+
+/// Parameters for getting the XMP metadata packet.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetXmpMetadataParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Raw XMP metadata.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetXmpMetadataResult {
+    /// XMP packet as XML, if the document has one.
+    pub xmp: Option<String>,
+    /// Whether the document carries an XMP metadata stream.
+    pub has_xmp: bool,
+}
+
+/// Read the catalog `/Metadata` stream of a PDF document.
+fn read_xmp(pdf: &PdfDocument) -> Result<Option<String>> {
+    let Some(stream) = pdf.catalog()?.get_dict("Metadata")? else {
+        return Ok(None);
+    };
+    let bytes = stream.read_stream()?;
+    // Packets are usually padded with whitespace for in-place updates
+    let xmp = String::from_utf8_lossy(&bytes).trim().to_string();
+    Ok(Some(xmp).filter(|s| !s.is_empty()))
+}
+
+/// Get the raw XMP metadata packet of a document.
+///
+/// Non-PDF documents report `has_xmp: false`.
+pub fn get_xmp_metadata(
+    store: &DocumentStore,
+    params: GetXmpMetadataParams,
+) -> Result<GetXmpMetadataResult> {
+    let xmp = match store.with_pdf_document(&params.document_id, read_xmp) {
+        Ok(xmp) => xmp,
+        Err(MupdfServerError::NotAPdf) => None,
+        Err(e) => return Err(e),
+    };
+
+    Ok(GetXmpMetadataResult {
+        has_xmp: xmp.is_some(),
+        xmp,
+    })
+}
+
+// ============== Get PDF Version ==============
+
+/// Parameters for getting the PDF version.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPdfVersionParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// PDF specification version.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPdfVersionResult {
+    /// Version string (e.g. "1.7").
+    pub version: String,
+    /// Major version number.
+    pub major: u8,
+    /// Minor version number.
+    pub minor: u8,
+}
+
+/// Parse the version out of a format string such as "PDF 1.7".
+fn parse_pdf_version(format: &str) -> Option<(u8, u8)> {
+    let version = format
+        .trim()
+        .strip_prefix("PDF")?
+        .trim_start_matches([' ', '-']);
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Get the PDF specification version of a document.
+pub fn get_pdf_version(
+    store: &DocumentStore,
+    params: GetPdfVersionParams,
+) -> Result<GetPdfVersionResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let format = pdf.metadata(MetadataName::Format)?;
+        let (major, minor) = parse_pdf_version(&format).ok_or_else(|| {
+            MupdfServerError::internal(format!("Unrecognized PDF format: {}", format))
+        })?;
+
+        Ok(GetPdfVersionResult {
+            version: format!("{}.{}", major, minor),
+            major,
+            minor,
+        })
+    })
+}
+
+// ============== Get PDF Objects Summary ==============
+
+/// Maximum page tree depth followed when measuring `/Kids` nesting.
+const MAX_PAGE_TREE_DEPTH: i32 = 64;
+
+/// Parameters for summarizing the objects of a PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPdfObjectsSummaryParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Low-level structure of a PDF file.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPdfObjectsSummaryResult {
+    /// Number of entries in the cross-reference table (including object 0).
+    pub xref_len: i32,
+    /// Number of stream objects.
+    pub stream_count: i32,
+    /// Nesting depth of the page tree (1 = all pages directly under the root).
+    pub page_tree_depth: i32,
+    /// Whether the file uses cross-reference streams (PDF 1.5+).
+    pub has_cross_ref_streams: bool,
+    /// Whether the file contains a linearization dictionary.
+    pub has_linearization_dict: bool,
+    /// Size of the imported file in bytes, if known.
+    pub file_size_bytes: Option<usize>,
+}
+
+/// Depth of a page tree node's `/Kids` hierarchy, counting the node itself.
+fn page_tree_depth(node: &PdfObject, depth: i32) -> Result<i32> {
+    if depth >= MAX_PAGE_TREE_DEPTH {
+        return Ok(depth);
+    }
+    let Some(kids) = node.get_dict("Kids")? else {
+        return Ok(depth);
+    };
+    let mut max_depth = depth;
+    for i in 0..kids.len()? as i32 {
+        if let Some(kid) = kids.get_array(i)? {
+            // Leaf pages have no /Kids and add no depth
+            if kid.get_dict("Kids")?.is_some() {
+                max_depth = max_depth.max(page_tree_depth(&kid, depth + 1)?);
+            }
+        }
+    }
+    Ok(max_depth)
+}
+
+/// Summarize the cross-reference table and object structure of a PDF.
+pub fn get_pdf_objects_summary(
+    store: &DocumentStore,
+    params: GetPdfObjectsSummaryParams,
+) -> Result<GetPdfObjectsSummaryResult> {
+    let size_bytes = store.get_info(&params.document_id)?.size_bytes;
+
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let xref_len = pdf.count_objects()?;
+
+        let mut stream_count = 0;
+        let mut has_linearization_dict = false;
+        for num in 1..xref_len {
+            let object = pdf.new_indirect(num, 0)?;
+            if object.is_stream()? {
+                stream_count += 1;
+            } else if object.is_dict()? && object.get_dict("Linearized")?.is_some() {
+                has_linearization_dict = true;
+            }
+        }
+
+        // With cross-reference streams the trailer is the stream's dictionary
+        let has_cross_ref_streams = dict_name(&pdf.trailer()?, "Type")?.as_deref() == Some("XRef");
+
+        let page_tree_depth = match pdf.catalog()?.get_dict("Pages")? {
+            Some(root) => page_tree_depth(&root, 1)?,
+            None => 0,
+        };
+
+        Ok(GetPdfObjectsSummaryResult {
+            xref_len,
+            stream_count,
+            page_tree_depth,
+            has_cross_ref_streams,
+            has_linearization_dict,
+            file_size_bytes: Some(size_bytes).filter(|&n| n > 0),
         })
     })
 }
 
+// ============== Validate PDF ==============
+
+/// Metadata fields read when checking the document information.
+const VALIDATED_METADATA: [(MetadataName, &str); 8] = [
+    (MetadataName::Title, "Title"),
+    (MetadataName::Author, "Author"),
+    (MetadataName::Subject, "Subject"),
+    (MetadataName::Keywords, "Keywords"),
+    (MetadataName::Creator, "Creator"),
+    (MetadataName::Producer, "Producer"),
+    (MetadataName::CreationDate, "CreationDate"),
+    (MetadataName::ModDate, "ModDate"),
+];
+
+/// Parameters for validating a document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ValidatePdfParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// A problem found while validating a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ValidationIssue {
+    /// Severity: "error" or "warning".
+    pub severity: String,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(message: String) -> Self {
+        Self {
+            severity: "error".to_string(),
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: "warning".to_string(),
+            message,
+        }
+    }
+}
+
+/// Result of validating a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ValidatePdfResult {
+    /// Whether no errors were found (warnings are allowed).
+    pub is_valid: bool,
+    /// Whether the document is a PDF.
+    pub is_pdf: bool,
+    /// Problems found, in check order.
+    pub issues: Vec<ValidationIssue>,
+    /// Whether the cross-reference table is intact and every object can be
+    /// read (false for non-PDF documents).
+    pub cross_ref_ok: bool,
+    /// Whether every page can be loaded and has a non-empty page box.
+    pub page_tree_ok: bool,
+    /// Whether the document information can be read.
+    pub metadata_ok: bool,
+}
+
+/// Load every page, reporting pages that fail to load or have no area.
+fn check_page_tree(doc: &mupdf::Document, issues: &mut Vec<ValidationIssue>) -> Result<bool> {
+    let mut ok = true;
+    for page_number in 0..doc.page_count()? {
+        match doc.load_page(page_number).and_then(|page| page.bounds()) {
+            Ok(bounds) if bounds.width() > 0.0 && bounds.height() > 0.0 => {}
+            Ok(_) => {
+                ok = false;
+                issues.push(ValidationIssue::error(format!(
+                    "Page {} has an empty page box",
+                    page_number
+                )));
+            }
+            Err(e) => {
+                ok = false;
+                issues.push(ValidationIssue::error(format!(
+                    "Page {} could not be loaded: {}",
+                    page_number, e
+                )));
+            }
+        }
+    }
+    Ok(ok)
+}
+
+/// Check that the cross-reference table was not repaired and that every
+/// object it lists can be read.
+fn check_cross_ref(pdf: &PdfDocument, issues: &mut Vec<ValidationIssue>) -> Result<bool> {
+    let repaired = pdf.was_repaired()?;
+    if repaired {
+        issues.push(ValidationIssue::error(
+            "Cross-reference table is damaged and was rebuilt by scanning the file".to_string(),
+        ));
+    }
+
+    let broken = (1..pdf.count_objects()?)
+        .filter(|&num| {
+            pdf.new_indirect(num, 0)
+                .and_then(|object| object.resolve())
+                .is_err()
+        })
+        .count();
+    if broken > 0 {
+        issues.push(ValidationIssue::error(format!(
+            "{} object(s) could not be read",
+            broken
+        )));
+    }
+    Ok(!repaired && broken == 0)
+}
+
+/// Read each document information field, reporting unreadable ones.
+fn check_metadata(doc: &DocumentHandle, issues: &mut Vec<ValidationIssue>) -> Result<bool> {
+    let mut ok = true;
+    if let Some(pdf) = doc.as_pdf() {
+        if let Some(info) = pdf.trailer()?.get_dict("Info")? {
+            if !info.is_dict()? {
+                ok = false;
+                issues.push(ValidationIssue::warning(
+                    "Trailer /Info is not a dictionary".to_string(),
+                ));
+            }
+        }
+    }
+    for (name, key) in VALIDATED_METADATA {
+        if let Err(e) = doc.metadata(name) {
+            ok = false;
+            issues.push(ValidationIssue::warning(format!(
+                "Metadata field {} could not be read: {}",
+                key, e
+            )));
+        }
+    }
+    Ok(ok)
+}
+
+/// Check a document for corruption and common structural problems.
+///
+/// The page tree, cross-reference table and metadata are checked
+/// independently, so a failure in one check is reported without hiding the
+/// results of the others. Metadata problems are warnings; the rest are
+/// errors.
+pub fn validate_pdf(store: &DocumentStore, params: ValidatePdfParams) -> Result<ValidatePdfResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let mut issues = Vec::new();
+
+        let page_tree_ok = check_page_tree(doc, &mut issues).unwrap_or_else(|e| {
+            issues.push(ValidationIssue::error(format!(
+                "Page tree could not be read: {}",
+                e
+            )));
+            false
+        });
+        let cross_ref_ok = match doc.as_pdf() {
+            Some(pdf) => check_cross_ref(pdf, &mut issues).unwrap_or_else(|e| {
+                issues.push(ValidationIssue::error(format!(
+                    "Cross-reference table could not be read: {}",
+                    e
+                )));
+                false
+            }),
+            None => false,
+        };
+        let metadata_ok = check_metadata(doc, &mut issues).unwrap_or_else(|e| {
+            issues.push(ValidationIssue::warning(format!(
+                "Metadata could not be read: {}",
+                e
+            )));
+            false
+        });
+
+        Ok(ValidatePdfResult {
+            is_valid: issues.iter().all(|issue| issue.severity != "error"),
+            is_pdf: doc.as_pdf().is_some(),
+            issues,
+            cross_ref_ok,
+            page_tree_ok,
+            metadata_ok,
+        })
+    })
+}
+
+// ============== Get Fonts ==============
+
+/// Parameters for listing the fonts of a PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetFontsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// A font dictionary found in a PDF.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FontEntry {
+    /// Font name from `/BaseFont`, including any subset prefix.
+    pub name: String,
+    /// Encoding name (`BaseEncoding` for custom encodings; empty if the
+    /// font's built-in encoding is used).
+    pub encoding: String,
+    /// Whether the font program is embedded in the file.
+    pub is_embedded: bool,
+    /// Whether the font is a subset (name prefixed with `ABCDEF+`).
+    pub is_subset: bool,
+    /// Font type from `/Subtype` (Type1, TrueType, Type0, CIDFontType2, ...).
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Pages (0-indexed) whose text uses this font, matched by name.
+    pub pages_used: Vec<i32>,
+}
+
+/// Result of listing fonts.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetFontsResult {
+    /// Fonts in object number order.
+    pub fonts: Vec<FontEntry>,
+}
+
+/// Whether a font name carries a subset tag (six uppercase letters and `+`).
+fn is_subset_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() > 7 && bytes[6] == b'+' && bytes[..6].iter().all(u8::is_ascii_uppercase)
+}
+
+/// Whether a font's program is embedded, looking through `/DescendantFonts`
+/// for composite fonts. Type3 glyphs always live in the file.
+fn is_font_embedded(font: &PdfObject, subtype: &str) -> Result<bool> {
+    if subtype == "Type3" {
+        return Ok(true);
+    }
+    let descriptor = match font.get_dict("DescendantFonts")? {
+        Some(descendants) => match descendants.get_array(0)? {
+            Some(descendant) => descendant.get_dict("FontDescriptor")?,
+            None => None,
+        },
+        None => font.get_dict("FontDescriptor")?,
+    };
+    let Some(descriptor) = descriptor else {
+        return Ok(false);
+    };
+    for key in ["FontFile", "FontFile2", "FontFile3"] {
+        if descriptor.get_dict(key)?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Encoding of a font: a name, or a dictionary's `/BaseEncoding`.
+fn font_encoding(font: &PdfObject) -> Result<String> {
+    let Some(encoding) = font.get_dict("Encoding")? else {
+        return Ok(String::new());
+    };
+    if encoding.is_name()? {
+        return Ok(String::from_utf8_lossy(encoding.as_name()?).into_owned());
+    }
+    if encoding.is_dict()? {
+        return Ok(dict_name(&encoding, "BaseEncoding")?.unwrap_or_else(|| "Custom".to_string()));
+    }
+    Ok(String::new())
+}
+
+/// List every font dictionary in a PDF.
+///
+/// Fonts are found by scanning all objects for `/Type /Font`, so unused
+/// fonts are included too. Page usage is matched by font name against the
+/// text of each page, which ignores the subset prefix.
+pub fn get_fonts(store: &DocumentStore, params: GetFontsParams) -> Result<GetFontsResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        // Font names used by the text of each page
+        let mut pages_by_font: BTreeMap<String, Vec<i32>> = BTreeMap::new();
+        for page_number in 0..pdf.page_count()? {
+            let page = pdf.load_page(page_number)?;
+            let text_page = page.to_text_page(TextPageFlags::empty())?;
+            for name in page_font_names(&text_page)? {
+                pages_by_font.entry(name).or_default().push(page_number);
+            }
+        }
+
+        let mut fonts = Vec::new();
+        for num in 1..pdf.count_objects()? {
+            let object = pdf.new_indirect(num, 0)?;
+            if !object.is_dict()? || dict_name(&object, "Type")?.as_deref() != Some("Font") {
+                continue;
+            }
+
+            let name = dict_name(&object, "BaseFont")?.unwrap_or_default();
+            let type_ = dict_name(&object, "Subtype")?.unwrap_or_default();
+            let is_subset = is_subset_name(&name);
+            let plain_name = if is_subset { &name[7..] } else { &name[..] };
+
+            fonts.push(FontEntry {
+                encoding: font_encoding(&object)?,
+                is_embedded: is_font_embedded(&object, &type_)?,
+                is_subset,
+                pages_used: pages_by_font.get(plain_name).cloned().unwrap_or_default(),
+                type_,
+                name,
+            });
+        }
+
+        Ok(GetFontsResult { fonts })
+    })
+}
+
 // ============== Get Outlines (Bookmarks) ==============
 
 /// Parameters for getting document outlines.
@@ -111,6 +619,10 @@ pub fn get_metadata(store: &DocumentStore, params: GetMetadataParams) -> Result<
 pub struct GetOutlinesParams {
     /// Document ID.
     pub document_id: String,
+    /// Maximum nesting depth to return (1 = top-level entries only).
+    /// Deeper entries are omitted. Defaults to the whole tree.
+    #[serde(default)]
+    pub depth_limit: Option<i32>,
 }
 
 /// A single outline entry (bookmark).
@@ -133,25 +645,38 @@ pub struct GetOutlinesResult {
     pub outlines: Vec<OutlineEntry>,
 }
 
-/// Convert MuPDF outline to our OutlineEntry format.
-fn convert_outline(outline: &mupdf::Outline) -> OutlineEntry {
+/// External URI of an outline entry (internal page references are dropped).
+fn external_uri(outline: &mupdf::Outline) -> Option<String> {
+    outline.uri.as_ref().and_then(|u| {
+        if u.starts_with("http://") || u.starts_with("https://") || u.starts_with("mailto:") {
+            Some(u.clone())
+        } else {
+            None
+        }
+    })
+}
+
+/// Convert MuPDF outline to our OutlineEntry format, keeping `levels`
+/// levels of children below this entry.
+fn convert_outline(outline: &mupdf::Outline, levels: i32) -> OutlineEntry {
     // Try to get page number from destination
     let page = outline
         .dest
         .as_ref()
         .map(|dest| dest.loc.page_number as i32);
 
-    let uri = outline.uri.as_ref().and_then(|u| {
-        // Only include external URIs, not internal page references
-        if u.starts_with("http://") || u.starts_with("https://") || u.starts_with("mailto:") {
-            Some(u.clone())
-        } else {
-            None
-        }
-    });
+    let uri = external_uri(outline);
 
     // Recursively convert children using 'down' field (it's a Vec)
-    let children: Vec<OutlineEntry> = outline.down.iter().map(convert_outline).collect();
+    let children: Vec<OutlineEntry> = if levels > 0 {
+        outline
+            .down
+            .iter()
+            .map(|child| convert_outline(child, levels - 1))
+            .collect()
+    } else {
+        Vec::new()
+    };
 
     OutlineEntry {
         title: outline.title.clone(),
@@ -161,16 +686,239 @@ fn convert_outline(outline: &mupdf::Outline) -> OutlineEntry {
     }
 }
 
+/// Number of levels below the top level to keep for a 1-based depth
+/// limit (1 = top-level entries only) named `name`; unlimited if `None`.
+fn outline_levels(limit: Option<i32>, name: &str) -> Result<i32> {
+    match limit {
+        Some(limit) if limit < 1 => Err(MupdfServerError::InvalidParams(format!(
+            "{} must be at least 1, got {}",
+            name, limit
+        ))),
+        Some(limit) => Ok(limit - 1),
+        None => Ok(i32::MAX),
+    }
+}
+
 /// Get document outlines (table of contents).
 pub fn get_outlines(store: &DocumentStore, params: GetOutlinesParams) -> Result<GetOutlinesResult> {
+    let levels = outline_levels(params.depth_limit, "depth_limit")?;
+
     store.with_document(&params.document_id, |doc| {
         let outline_vec = doc.outlines()?;
-        let outlines: Vec<OutlineEntry> = outline_vec.iter().map(convert_outline).collect();
+        let outlines: Vec<OutlineEntry> = outline_vec
+            .iter()
+            .map(|outline| convert_outline(outline, levels))
+            .collect();
 
         Ok(GetOutlinesResult { outlines })
     })
 }
 
+// ============== Get Outline Depth ==============
+
+/// Parameters for measuring the outline tree.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetOutlineDepthParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Size of the outline tree.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetOutlineDepthResult {
+    /// Deepest nesting level (1 = only top-level entries, 0 = no outline).
+    pub max_depth: i32,
+    /// Total number of outline entries at all levels.
+    pub total_entries: usize,
+}
+
+/// Walk an outline subtree, returning its depth and number of entries.
+fn outline_depth(outlines: &[mupdf::Outline]) -> (i32, usize) {
+    outlines.iter().fold((0, 0), |(depth, count), outline| {
+        let (child_depth, child_count) = outline_depth(&outline.down);
+        (depth.max(child_depth + 1), count + child_count + 1)
+    })
+}
+
+/// Get the maximum nesting depth and entry count of the document outline.
+pub fn get_outline_depth(
+    store: &DocumentStore,
+    params: GetOutlineDepthParams,
+) -> Result<GetOutlineDepthResult> {
+    store.with_document(&params.document_id, |doc| {
+        let (max_depth, total_entries) = outline_depth(&doc.outlines()?);
+        Ok(GetOutlineDepthResult {
+            max_depth,
+            total_entries,
+        })
+    })
+}
+
+// ============== Get Outline As Markdown ==============
+
+/// Parameters for rendering the outline as a Markdown list.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetOutlineMarkdownParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Append the 0-indexed target page to each entry (default true).
+    #[serde(default = "default_true")]
+    pub include_page_numbers: bool,
+    /// Maximum nesting depth to include (1 = top-level entries only).
+    /// Defaults to the whole tree.
+    #[serde(default)]
+    pub max_depth: Option<i32>,
+}
+
+/// Outline rendered as a Markdown list.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetOutlineMarkdownResult {
+    /// Nested Markdown list, two spaces of indentation per level; empty if
+    /// the document has no outline.
+    pub markdown: String,
+    /// Number of entries in the list.
+    pub entry_count: usize,
+}
+
+/// Append an outline entry and its children as Markdown list items.
+fn write_outline_markdown(
+    entry: &OutlineEntry,
+    depth: usize,
+    include_page_numbers: bool,
+    markdown: &mut String,
+    entry_count: &mut usize,
+) {
+    markdown.push_str(&"  ".repeat(depth));
+    markdown.push_str("- ");
+    markdown.push_str(&entry.title);
+    if let Some(page) = entry.page.filter(|_| include_page_numbers) {
+        markdown.push_str(&format!(" (p. {})", page));
+    }
+    markdown.push('\n');
+    *entry_count += 1;
+
+    for child in &entry.children {
+        write_outline_markdown(
+            child,
+            depth + 1,
+            include_page_numbers,
+            markdown,
+            entry_count,
+        );
+    }
+}
+
+/// Render the document outline as a nested Markdown list.
+pub fn get_document_outline_as_markdown(
+    store: &DocumentStore,
+    params: GetOutlineMarkdownParams,
+) -> Result<GetOutlineMarkdownResult> {
+    let levels = outline_levels(params.max_depth, "max_depth")?;
+
+    store.with_document(&params.document_id, |doc| {
+        let mut markdown = String::new();
+        let mut entry_count = 0;
+        for outline in doc.outlines()?.iter() {
+            write_outline_markdown(
+                &convert_outline(outline, levels),
+                0,
+                params.include_page_numbers,
+                &mut markdown,
+                &mut entry_count,
+            );
+        }
+
+        Ok(GetOutlineMarkdownResult {
+            markdown,
+            entry_count,
+        })
+    })
+}
+
+// ============== Get Outline (Flat) ==============
+
+/// Parameters for getting a flattened outline.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetOutlineFlatParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Maximum nesting depth to include (1 = top-level entries only).
+    /// Defaults to the whole tree.
+    #[serde(default)]
+    pub max_depth: Option<i32>,
+}
+
+/// A bookmark in a flattened outline.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FlatOutlineEntry {
+    /// Bookmark title.
+    pub title: String,
+    /// Target page number (0-indexed).
+    pub page: Option<i32>,
+    /// URI for external links.
+    pub uri: Option<String>,
+    /// Nesting level (0 = top level).
+    pub depth: i32,
+}
+
+/// Result of getting a flattened outline.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetOutlineFlatResult {
+    /// Bookmarks ordered by target page; entries without a page come last.
+    pub entries: Vec<FlatOutlineEntry>,
+    /// Number of entries.
+    pub total: usize,
+}
+
+/// Recursively flatten an outline, stopping below level `levels`.
+fn flatten_outline(
+    outline: &mupdf::Outline,
+    depth: i32,
+    levels: i32,
+    result: &mut Vec<FlatOutlineEntry>,
+) {
+    if depth > levels {
+        return;
+    }
+
+    result.push(FlatOutlineEntry {
+        title: outline.title.clone(),
+        page: outline
+            .dest
+            .as_ref()
+            .map(|dest| dest.loc.page_number as i32),
+        uri: external_uri(outline),
+        depth,
+    });
+
+    for child in &outline.down {
+        flatten_outline(child, depth + 1, levels, result);
+    }
+}
+
+/// Get the document outline as a flat list with nesting depth.
+pub fn get_outline_flat(
+    store: &DocumentStore,
+    params: GetOutlineFlatParams,
+) -> Result<GetOutlineFlatResult> {
+    let levels = outline_levels(params.max_depth, "max_depth")?;
+
+    store.with_document(&params.document_id, |doc| {
+        let mut entries = Vec::new();
+        for outline in &doc.outlines()? {
+            flatten_outline(outline, 0, levels, &mut entries);
+        }
+
+        // Stable sort keeps document order for entries on the same page
+        entries.sort_by_key(|e| e.page.unwrap_or(i32::MAX));
+
+        Ok(GetOutlineFlatResult {
+            total: entries.len(),
+            entries,
+        })
+    })
+}
+
 // ============== Needs Password ==============
 
 /// Parameters for checking if document needs password.
@@ -252,28 +1000,91 @@ pub fn is_reflowable(
     })
 }
 
-// ============== Resolve Link ==============
+// ============== Get Document Format ==============
 
-/// Parameters for resolving a link.
+/// Parameters for detecting the document format.
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct ResolveLinkParams {
+pub struct GetDocumentFormatParams {
     /// Document ID.
     pub document_id: String,
-    /// URI to resolve.
-    pub uri: String,
 }
 
-/// Result of link resolution.
+/// Detected document format.
 #[derive(Debug, Serialize, JsonSchema)]
-pub struct ResolveLinkResult {
-    /// Target page number (0-indexed), if internal link.
-    pub page: Option<i32>,
-    /// X coordinate on target page.
-    pub x: Option<f32>,
-    /// Y coordinate on target page.
-    pub y: Option<f32>,
-}
-
+pub struct GetDocumentFormatResult {
+    /// Short format name (e.g. "pdf", "epub", "xps", "cbz").
+    pub format: String,
+    /// Whether the document is a PDF.
+    pub is_pdf: bool,
+    /// Whether the document is reflowable.
+    pub is_reflowable: bool,
+    /// MIME type of the format.
+    pub mime_type: String,
+}
+
+/// Map MuPDF's format description (e.g. "PDF 1.7", "EPUB") to a short
+/// format name and MIME type.
+fn classify_format(description: &str) -> (&'static str, &'static str) {
+    let description = description.to_ascii_lowercase();
+    match description.split_whitespace().next().unwrap_or("") {
+        "pdf" => ("pdf", "application/pdf"),
+        "epub" => ("epub", "application/epub+zip"),
+        "xps" | "oxps" => ("xps", "application/oxps"),
+        "cbz" => ("cbz", "application/vnd.comicbook+zip"),
+        "cbr" => ("cbr", "application/vnd.comicbook-rar"),
+        "fictionbook2" | "fb2" => ("fb2", "application/x-fictionbook+xml"),
+        "mobi" => ("mobi", "application/x-mobipocket-ebook"),
+        "svg" => ("svg", "image/svg+xml"),
+        "html5" | "xhtml" | "html" => ("html", "text/html"),
+        "txt" | "text" => ("txt", "text/plain"),
+        _ => ("unknown", "application/octet-stream"),
+    }
+}
+
+/// Detect the format of a document.
+pub fn get_document_format(
+    store: &DocumentStore,
+    params: GetDocumentFormatParams,
+) -> Result<GetDocumentFormatResult> {
+    store.with_document(&params.document_id, |doc| {
+        let is_pdf = doc.is_pdf();
+        let (format, mime_type) = if is_pdf {
+            classify_format("pdf")
+        } else {
+            classify_format(&doc.metadata(MetadataName::Format).unwrap_or_default())
+        };
+
+        Ok(GetDocumentFormatResult {
+            format: format.to_string(),
+            is_pdf,
+            is_reflowable: doc.is_reflowable()?,
+            mime_type: mime_type.to_string(),
+        })
+    })
+}
+
+// ============== Resolve Link ==============
+
+/// Parameters for resolving a link.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveLinkParams {
+    /// Document ID.
+    pub document_id: String,
+    /// URI to resolve.
+    pub uri: String,
+}
+
+/// Result of link resolution.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolveLinkResult {
+    /// Target page number (0-indexed), if internal link.
+    pub page: Option<i32>,
+    /// X coordinate on target page.
+    pub x: Option<f32>,
+    /// Y coordinate on target page.
+    pub y: Option<f32>,
+}
+
 /// Resolve a link URI to a destination.
 pub fn resolve_link(store: &DocumentStore, params: ResolveLinkParams) -> Result<ResolveLinkResult> {
     store.with_document(&params.document_id, |doc| {
@@ -296,3 +1107,1340 @@ pub fn resolve_link(store: &DocumentStore, params: ResolveLinkParams) -> Result<
         }
     })
 }
+
+// ============== Chapters ==============
+
+/// Parameters for getting the chapter a page belongs to.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageChapterInfoParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Chapter location of a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageChapterInfoResult {
+    /// Chapter index (0-indexed). PDFs have a single chapter.
+    pub chapter: i32,
+    /// Page index within the chapter (0-indexed).
+    pub page_in_chapter: i32,
+    /// Number of chapters in the document.
+    pub chapter_count: i32,
+}
+
+/// Chapter and page-in-chapter of a physical page.
+fn page_location(doc: &mupdf::Document, page: i32) -> Result<(i32, i32)> {
+    let location = doc.location_from_page_number(page)?;
+    Ok((location.chapter as i32, location.page_in_chapter as i32))
+}
+
+/// Get the chapter a page belongs to (reflowable formats such as EPUB are
+/// split into chapters).
+pub fn get_page_chapter_info(
+    store: &DocumentStore,
+    params: GetPageChapterInfoParams,
+) -> Result<GetPageChapterInfoResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let (chapter, page_in_chapter) = page_location(doc, page_number)?;
+        let (last_chapter, _) = page_location(doc, doc.page_count()? - 1)?;
+
+        Ok(GetPageChapterInfoResult {
+            chapter,
+            page_in_chapter,
+            chapter_count: last_chapter + 1,
+        })
+    })
+}
+
+/// Parameters for resolving a chapter location to a page number.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveLocationParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Chapter index (0-indexed).
+    pub chapter: i32,
+    /// Page index within the chapter (0-indexed).
+    pub page_in_chapter: i32,
+}
+
+/// Physical page of a chapter location.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolveLocationResult {
+    /// Page number (0-indexed).
+    pub page_number: i32,
+}
+
+/// Physical page number of a chapter location.
+fn location_page(doc: &mupdf::Document, chapter: i32, page_in_chapter: i32) -> Result<i32> {
+    Ok(doc.page_number_from_location(mupdf::Location {
+        chapter: chapter as _,
+        page_in_chapter: page_in_chapter as _,
+    })?)
+}
+
+/// Resolve a chapter location to its physical page number.
+pub fn resolve_location(
+    store: &DocumentStore,
+    params: ResolveLocationParams,
+) -> Result<ResolveLocationResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_count = doc.page_count()?;
+        let (last_chapter, _) = page_location(doc, page_count - 1)?;
+        if params.chapter < 0 || params.chapter > last_chapter {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "chapter {} is out of range (document has {} chapters)",
+                params.chapter,
+                last_chapter + 1
+            )));
+        }
+
+        // Chapters are contiguous, so a chapter ends where the next begins
+        let chapter_start = location_page(doc, params.chapter, 0)?;
+        let chapter_end = if params.chapter < last_chapter {
+            location_page(doc, params.chapter + 1, 0)?
+        } else {
+            page_count
+        };
+        let chapter_pages = chapter_end - chapter_start;
+        if params.page_in_chapter < 0 || params.page_in_chapter >= chapter_pages {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "page_in_chapter {} is out of range (chapter {} has {} pages)",
+                params.page_in_chapter, params.chapter, chapter_pages
+            )));
+        }
+
+        Ok(ResolveLocationResult {
+            page_number: location_page(doc, params.chapter, params.page_in_chapter)?,
+        })
+    })
+}
+
+// ============== Get Security Info ==============
+
+/// Parameters for getting security information.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetSecurityInfoParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Operations permitted by the document's security handler.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PermissionsFlags {
+    /// Whether printing is allowed.
+    pub can_print: bool,
+    /// Whether modifying the content is allowed.
+    pub can_modify: bool,
+    /// Whether copying text and graphics is allowed.
+    pub can_copy: bool,
+    /// Whether adding or modifying annotations is allowed.
+    pub can_annotate: bool,
+}
+
+/// Result of getting security information.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetSecurityInfoResult {
+    /// Whether the document requires a password.
+    pub needs_password: bool,
+    /// Whether the document is encrypted.
+    pub is_encrypted: bool,
+    /// Encryption method description (e.g. "Standard V4 R4 128-bit AES").
+    pub encryption: Option<String>,
+    /// Permitted operations.
+    pub permissions: PermissionsFlags,
+}
+
+/// Get encryption status and permissions of a document.
+pub fn get_security_info(
+    store: &DocumentStore,
+    params: GetSecurityInfoParams,
+) -> Result<GetSecurityInfoResult> {
+    store.with_document(&params.document_id, |doc| {
+        // MuPDF reports "None" for unencrypted documents
+        let encryption = doc
+            .metadata(MetadataName::Encryption)
+            .ok()
+            .filter(|s| !s.is_empty() && s != "None");
+
+        Ok(GetSecurityInfoResult {
+            needs_password: doc.needs_password()?,
+            is_encrypted: encryption.is_some(),
+            encryption,
+            permissions: PermissionsFlags {
+                can_print: doc.has_permission(Permission::Print),
+                can_modify: doc.has_permission(Permission::Edit),
+                can_copy: doc.has_permission(Permission::Copy),
+                can_annotate: doc.has_permission(Permission::Annotate),
+            },
+        })
+    })
+}
+
+// ============== Get Form Fields ==============
+
+/// "Required" bit in the PDF field flags (`/Ff`).
+const FIELD_FLAG_REQUIRED: u32 = 1 << 1;
+
+/// Parameters for listing interactive form fields.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetFormFieldsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// An interactive form field.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FormFieldEntry {
+    /// Fully qualified field name.
+    pub name: String,
+    /// Field type: "text", "checkbox", "radio", "combo", "list", "signature" or "button".
+    pub field_type: String,
+    /// Current field value, if set.
+    pub value: Option<String>,
+    /// Page the field widget is on (0-indexed).
+    pub bounds_page: i32,
+    /// Widget bounding box on the page as `[x0, y0, x1, y1]`.
+    pub bounds: [f32; 4],
+    /// Whether the field must be filled in before submitting.
+    pub is_required: bool,
+}
+
+/// Result of listing form fields.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetFormFieldsResult {
+    /// Whether the document contains any form fields.
+    pub has_form: bool,
+    /// Form fields in page order.
+    pub fields: Vec<FormFieldEntry>,
+}
+
+fn widget_type_name(widget_type: WidgetType) -> &'static str {
+    match widget_type {
+        WidgetType::Text => "text",
+        WidgetType::Checkbox => "checkbox",
+        WidgetType::Radiobutton => "radio",
+        WidgetType::Combobox => "combo",
+        WidgetType::Listbox => "list",
+        WidgetType::Signature => "signature",
+        _ => "button",
+    }
+}
+
+/// List the interactive form fields (AcroForm widgets) of a document.
+pub fn get_form_fields(
+    store: &DocumentStore,
+    params: GetFormFieldsParams,
+) -> Result<GetFormFieldsResult> {
+    store.with_document(&params.document_id, |doc| {
+        let mut fields = Vec::new();
+
+        // Only PDF documents can carry forms
+        if doc.is_pdf() {
+            for page_number in 0..doc.page_count()? {
+                let page = doc.load_page(page_number)?;
+                for widget in page.widgets()? {
+                    let rect = widget.rect()?;
+                    fields.push(FormFieldEntry {
+                        name: widget.name()?,
+                        field_type: widget_type_name(widget.field_type()?).to_string(),
+                        value: widget.value()?.filter(|v| !v.is_empty()),
+                        bounds_page: page_number,
+                        bounds: [rect.x0, rect.y0, rect.x1, rect.y1],
+                        is_required: widget.field_flags()? & FIELD_FLAG_REQUIRED != 0,
+                    });
+                }
+            }
+        }
+
+        Ok(GetFormFieldsResult {
+            has_form: !fields.is_empty(),
+            fields,
+        })
+    })
+}
+
+// ============== Get Action Tree ==============
+
+/// Maximum number of `/Next` actions followed from one trigger.
+const MAX_ACTION_CHAIN: usize = 32;
+
+/// Document-level additional-action keys and their triggers.
+const DOCUMENT_TRIGGERS: [(&str, &str); 5] = [
+    ("WC", "close"),
+    ("WS", "before_save"),
+    ("DS", "after_save"),
+    ("WP", "before_print"),
+    ("DP", "after_print"),
+];
+
+/// Page-level additional-action keys and their triggers.
+const PAGE_TRIGGERS: [(&str, &str); 2] = [("O", "page_open"), ("C", "page_close")];
+
+/// Annotation and form field additional-action keys (mouse, focus,
+/// keystroke, format, validate and calculate events).
+const FIELD_TRIGGER_KEYS: [&str; 12] = [
+    "E", "X", "D", "U", "Fo", "Bl", "PO", "PC", "K", "F", "V", "C",
+];
+
+/// Parameters for listing the actions of a PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetActionTreeParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// An action attached to a document, page, link or form field.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ActionEntry {
+    /// What runs the action: "open", "close", "before_save", "after_save",
+    /// "before_print", "after_print", "page_open", "page_close", "link",
+    /// "button" or "field".
+    pub trigger: String,
+    /// Action type from `/S` (JavaScript, URI, GoTo, Launch, ...).
+    pub action_type: String,
+    /// Script source, URI or launched file, when the action has one.
+    pub content: Option<String>,
+    /// Page (0-indexed) of page, link and field actions.
+    pub page: Option<i32>,
+}
+
+/// Result of listing actions.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetActionTreeResult {
+    /// Actions in document, then page order.
+    pub actions: Vec<ActionEntry>,
+    /// Whether any action runs JavaScript.
+    pub has_javascript: bool,
+}
+
+/// Text of a string or stream entry (JavaScript may be stored either way).
+fn string_or_stream(dict: &PdfObject, key: &str) -> Result<Option<String>> {
+    let Some(value) = dict.get_dict(key)? else {
+        return Ok(None);
+    };
+    if value.is_stream()? {
+        return Ok(Some(
+            String::from_utf8_lossy(&value.read_stream()?).into_owned(),
+        ));
+    }
+    Ok(Some(value.as_string()?.to_string()))
+}
+
+/// Content of an action: its script, URI or launched file.
+fn action_content(action: &PdfObject, action_type: &str) -> Result<Option<String>> {
+    match action_type {
+        "JavaScript" => string_or_stream(action, "JS"),
+        "URI" => string_or_stream(action, "URI"),
+        "Launch" | "GoToR" | "GoToE" => match action.get_dict("F")? {
+            Some(file) if file.is_dict()? => dict_string(&file, "F"),
+            Some(file) => Ok(Some(file.as_string()?.to_string())),
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Record an action and the actions chained after it through `/Next`.
+fn collect_action(
+    action: PdfObject,
+    trigger: &str,
+    page: Option<i32>,
+    out: &mut Vec<ActionEntry>,
+) -> Result<()> {
+    let mut pending = vec![action];
+    let mut visited = 0;
+    while let Some(action) = pending.pop() {
+        if visited >= MAX_ACTION_CHAIN {
+            break;
+        }
+        visited += 1;
+
+        if action.is_array()? {
+            // An /OpenAction array is an explicit destination
+            if trigger == "open" {
+                out.push(ActionEntry {
+                    trigger: trigger.to_string(),
+                    action_type: "GoTo".to_string(),
+                    content: None,
+                    page,
+                });
+            } else {
+                for i in (0..action.len()? as i32).rev() {
+                    pending.extend(action.get_array(i)?);
+                }
+            }
+            continue;
+        }
+        if !action.is_dict()? {
+            continue;
+        }
+
+        let action_type = dict_name(&action, "S")?.unwrap_or_default();
+        out.push(ActionEntry {
+            content: action_content(&action, &action_type)?,
+            trigger: trigger.to_string(),
+            action_type,
+            page,
+        });
+        pending.extend(action.get_dict("Next")?);
+    }
+    Ok(())
+}
+
+/// List the JavaScript, URI, navigation and launch actions of a PDF.
+///
+/// Covers the open action, document-level scripts and additional actions,
+/// page additional actions, and the actions of link and widget annotations.
+pub fn get_action_tree(
+    store: &DocumentStore,
+    params: GetActionTreeParams,
+) -> Result<GetActionTreeResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let mut actions = Vec::new();
+        let catalog = pdf.catalog()?;
+
+        if let Some(action) = catalog.get_dict("OpenAction")? {
+            collect_action(action, "open", None, &mut actions)?;
+        }
+        // Document-level scripts run when the document is opened
+        if let Some(scripts) = catalog
+            .get_dict("Names")?
+            .map(|names| names.get_dict("JavaScript"))
+            .transpose()?
+            .flatten()
+        {
+            for (_, action) in name_tree_entries(&scripts)? {
+                collect_action(action, "open", None, &mut actions)?;
+            }
+        }
+        if let Some(aa) = catalog.get_dict("AA")? {
+            for (key, trigger) in DOCUMENT_TRIGGERS {
+                if let Some(action) = aa.get_dict(key)? {
+                    collect_action(action, trigger, None, &mut actions)?;
+                }
+            }
+        }
+
+        for page_number in 0..pdf.page_count()? {
+            let page = pdf.find_page(page_number)?;
+            let page_ref = Some(page_number);
+            if let Some(aa) = page.get_dict("AA")? {
+                for (key, trigger) in PAGE_TRIGGERS {
+                    if let Some(action) = aa.get_dict(key)? {
+                        collect_action(action, trigger, page_ref, &mut actions)?;
+                    }
+                }
+            }
+
+            let Some(annots) = page.get_dict("Annots")? else {
+                continue;
+            };
+            for i in 0..annots.len()? as i32 {
+                let Some(annot) = annots.get_array(i)? else {
+                    continue;
+                };
+                let (action_trigger, aa_trigger) = match dict_name(&annot, "Subtype")?.as_deref() {
+                    Some("Link") => ("link", "link"),
+                    Some("Widget") => ("button", "field"),
+                    _ => ("link", "field"),
+                };
+                if let Some(action) = annot.get_dict("A")? {
+                    collect_action(action, action_trigger, page_ref, &mut actions)?;
+                }
+                if let Some(aa) = annot.get_dict("AA")? {
+                    for key in FIELD_TRIGGER_KEYS {
+                        if let Some(action) = aa.get_dict(key)? {
+                            collect_action(action, aa_trigger, page_ref, &mut actions)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(GetActionTreeResult {
+            has_javascript: actions.iter().any(|a| a.action_type == "JavaScript"),
+            actions,
+        })
+    })
+}
+
+// ============== Get Structural Tree ==============
+
+/// Maximum nesting depth followed in the structure tree.
+const MAX_STRUCT_DEPTH: usize = 64;
+
+/// Maximum number of structure elements returned.
+const MAX_STRUCT_ELEMENTS: usize = 10_000;
+
+/// Parameters for reading the tagged structure tree of a PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetStructuralTreeParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Result of reading the structure tree.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetStructuralTreeResult {
+    /// Whether the document has a `/StructTreeRoot`.
+    pub is_tagged: bool,
+    /// Root node `{ role, alt_text, page, children }`, with one child per
+    /// structure element (H1, P, Table, Figure, ...). Null if untagged.
+    pub tree: Option<Value>,
+}
+
+/// State shared while walking the structure tree.
+struct StructWalk {
+    /// Page object number -> page index.
+    page_numbers: HashMap<i32, i32>,
+    /// Object numbers already emitted, to break reference cycles.
+    visited: HashSet<i32>,
+    /// Number of elements emitted so far.
+    elements: usize,
+}
+
+impl StructWalk {
+    /// Page index of the page an element's `/Pg` entry refers to.
+    fn page(&self, element: &PdfObject) -> Result<Option<i32>> {
+        match element.get_dict("Pg")? {
+            Some(page) if page.is_indirect()? => {
+                Ok(self.page_numbers.get(&page.as_indirect()?).copied())
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Convert the structure elements in a `/K` entry to JSON nodes.
+    ///
+    /// Marked-content and object references are leaves of their element
+    /// and are not listed.
+    fn kids(&mut self, kids: PdfObject, page: Option<i32>, depth: usize) -> Result<Vec<Value>> {
+        let mut pending = vec![kids];
+        let mut nodes = Vec::new();
+        while let Some(kid) = pending.pop() {
+            if kid.is_array()? {
+                for i in (0..kid.len()? as i32).rev() {
+                    pending.extend(kid.get_array(i)?);
+                }
+                continue;
+            }
+            if !kid.is_dict()?
+                || matches!(dict_name(&kid, "Type")?.as_deref(), Some("MCR" | "OBJR"))
+            {
+                continue;
+            }
+            if let Some(node) = self.element(&kid, page, depth)? {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Convert a structure element and its descendants to a JSON node.
+    fn element(
+        &mut self,
+        element: &PdfObject,
+        parent_page: Option<i32>,
+        depth: usize,
+    ) -> Result<Option<Value>> {
+        if depth > MAX_STRUCT_DEPTH || self.elements >= MAX_STRUCT_ELEMENTS {
+            return Ok(None);
+        }
+        if element.is_indirect()? && !self.visited.insert(element.as_indirect()?) {
+            return Ok(None);
+        }
+        self.elements += 1;
+
+        // Elements without /Pg are on their parent's page
+        let page = self.page(element)?.or(parent_page);
+        let children = match element.get_dict("K")? {
+            Some(kids) => self.kids(kids, page, depth + 1)?,
+            None => Vec::new(),
+        };
+
+        Ok(Some(json!({
+            "role": dict_name(element, "S")?,
+            "alt_text": dict_string(element, "Alt")?,
+            "page": page,
+            "children": children,
+        })))
+    }
+}
+
+/// Read the structure tree of a PDF as JSON, or `None` if it is untagged.
+fn read_structure_tree(pdf: &PdfDocument) -> Result<Option<Value>> {
+    let Some(root) = pdf.catalog()?.get_dict("StructTreeRoot")? else {
+        return Ok(None);
+    };
+
+    let mut page_numbers = HashMap::new();
+    for page_number in 0..pdf.page_count()? {
+        let page = pdf.find_page(page_number)?;
+        if page.is_indirect()? {
+            page_numbers.insert(page.as_indirect()?, page_number);
+        }
+    }
+    let mut walk = StructWalk {
+        page_numbers,
+        visited: HashSet::new(),
+        elements: 0,
+    };
+
+    let children = match root.get_dict("K")? {
+        Some(kids) => walk.kids(kids, None, 0)?,
+        None => Vec::new(),
+    };
+    Ok(Some(json!({
+        "role": "StructTreeRoot",
+        "alt_text": null,
+        "page": null,
+        "children": children,
+    })))
+}
+
+/// Read the tagged structure tree (`/StructTreeRoot`) of a PDF.
+///
+/// Each node reports its role (`/S`), alternate text, page and child
+/// elements. The tree is capped at 64 levels and 10,000 elements.
+pub fn get_structural_tree(
+    store: &DocumentStore,
+    params: GetStructuralTreeParams,
+) -> Result<GetStructuralTreeResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let tree = read_structure_tree(pdf)?;
+        Ok(GetStructuralTreeResult {
+            is_tagged: tree.is_some(),
+            tree,
+        })
+    })
+}
+
+// ============== Check Accessibility ==============
+
+/// Parameters for checking the accessibility of a PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckAccessibilityParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// An accessibility problem found in a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccessibilityIssue {
+    /// Severity: "error" or "warning".
+    pub severity: String,
+    /// Description of the problem.
+    pub description: String,
+}
+
+/// Result of an accessibility check.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccessibilityCheckResult {
+    /// Whether the document has a structure tree.
+    pub is_tagged: bool,
+    /// Whether the document metadata has a title.
+    pub has_title: bool,
+    /// Whether the catalog declares a natural language (`/Lang`).
+    pub has_language: bool,
+    /// Number of images covered by a tagged Figure with alternate text.
+    pub images_with_alt_text: usize,
+    /// Number of images without alternate text.
+    pub images_without_alt_text: usize,
+    /// Number of form fields with a label (`/TU`).
+    pub form_fields_with_labels: usize,
+    /// Number of form fields without a label.
+    pub form_fields_without_labels: usize,
+    /// Whether the structure tree defines a reading order.
+    pub reading_order_defined: bool,
+    /// Problems found, most severe first.
+    pub issues: Vec<AccessibilityIssue>,
+}
+
+/// Count the Figure elements of a structure tree node, split by whether
+/// they carry alternate text.
+fn count_figures(node: &Value, with_alt: &mut usize, without_alt: &mut usize) {
+    if node["role"] == "Figure" {
+        if node["alt_text"].is_null() {
+            *without_alt += 1;
+        } else {
+            *with_alt += 1;
+        }
+    }
+    if let Some(children) = node["children"].as_array() {
+        for child in children {
+            count_figures(child, with_alt, without_alt);
+        }
+    }
+}
+
+/// Check a PDF for basic accessibility (PDF/UA) problems.
+///
+/// Reports tagging, title, language, image alternate text, form field
+/// labels and reading order. Images are matched to tagged Figure elements
+/// by count, since figures are not linked to individual images.
+pub fn check_accessibility(
+    store: &DocumentStore,
+    params: CheckAccessibilityParams,
+) -> Result<AccessibilityCheckResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let has_title = read_metadata(pdf).title.is_some();
+        let has_language = dict_string(&pdf.catalog()?, "Lang")?.is_some();
+
+        let tree = read_structure_tree(pdf)?;
+        let (mut figures_with_alt, mut figures_without_alt) = (0, 0);
+        if let Some(tree) = &tree {
+            count_figures(tree, &mut figures_with_alt, &mut figures_without_alt);
+        }
+        let reading_order_defined = tree
+            .as_ref()
+            .and_then(|tree| tree["children"].as_array())
+            .is_some_and(|children| !children.is_empty());
+
+        let mut image_count = 0;
+        let mut form_fields_with_labels = 0;
+        let mut form_fields_without_labels = 0;
+        for page_number in 0..pdf.page_count()? {
+            let page = pdf.load_page(page_number)?;
+            image_count += collect_page_images(&page, false)?.len();
+
+            let Some(annots) = pdf.find_page(page_number)?.get_dict("Annots")? else {
+                continue;
+            };
+            for i in 0..annots.len()? as i32 {
+                let Some(annot) = annots.get_array(i)? else {
+                    continue;
+                };
+                if !annot.is_dict()? || dict_name(&annot, "Subtype")?.as_deref() != Some("Widget") {
+                    continue;
+                }
+                // Widgets inherit the field's /TU from their parent field
+                let labelled = match inherited_entry(annot, "TU")? {
+                    Some(label) => !label.as_string()?.trim().is_empty(),
+                    None => false,
+                };
+                if labelled {
+                    form_fields_with_labels += 1;
+                } else {
+                    form_fields_without_labels += 1;
+                }
+            }
+        }
+        let images_with_alt_text = figures_with_alt.min(image_count);
+        let images_without_alt_text = image_count - images_with_alt_text;
+
+        let mut issues = Vec::new();
+        let mut issue = |severity: &str, description: String| {
+            issues.push(AccessibilityIssue {
+                severity: severity.to_string(),
+                description,
+            })
+        };
+        if tree.is_none() {
+            issue(
+                "error",
+                "Document is not tagged (no /StructTreeRoot)".to_string(),
+            );
+        }
+        if images_without_alt_text > 0 {
+            issue(
+                "error",
+                format!(
+                    "{} image(s) have no alternate text",
+                    images_without_alt_text
+                ),
+            );
+        }
+        if form_fields_without_labels > 0 {
+            issue(
+                "error",
+                format!(
+                    "{} form field(s) have no label (/TU)",
+                    form_fields_without_labels
+                ),
+            );
+        }
+        if tree.is_some() && !reading_order_defined {
+            issue(
+                "warning",
+                "Structure tree is empty, so no reading order is defined".to_string(),
+            );
+        }
+        if !has_title {
+            issue("warning", "Document has no title".to_string());
+        }
+        if !has_language {
+            issue(
+                "warning",
+                "Document does not declare a language (/Lang)".to_string(),
+            );
+        }
+
+        Ok(AccessibilityCheckResult {
+            is_tagged: tree.is_some(),
+            has_title,
+            has_language,
+            images_with_alt_text,
+            images_without_alt_text,
+            form_fields_with_labels,
+            form_fields_without_labels,
+            reading_order_defined,
+            issues,
+        })
+    })
+}
+
+// ============== Page Labels ==============
+
+/// Parameters for getting page labels.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageLabelsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// A page label range, applying from `start_page` up to the next range.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PageLabelEntry {
+    /// First physical page of the range (0-indexed).
+    pub start_page: i32,
+    /// Numbering style: "arabic", "roman_lower", "roman_upper", "alpha_lower", "alpha_upper" or "none".
+    pub style: String,
+    /// Prefix prepended to every label in the range.
+    pub prefix: Option<String>,
+    /// Logical number of the first page of the range.
+    pub first_logical_page: i32,
+}
+
+/// Result of getting page labels.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageLabelsResult {
+    /// Label ranges sorted by start page (empty if the document defines none).
+    pub labels: Vec<PageLabelEntry>,
+}
+
+fn label_style_name(style: &str) -> &'static str {
+    match style {
+        "D" => "arabic",
+        "r" => "roman_lower",
+        "R" => "roman_upper",
+        "a" => "alpha_lower",
+        "A" => "alpha_upper",
+        _ => "none",
+    }
+}
+
+fn to_roman(mut n: i32) -> String {
+    const NUMERALS: [(i32, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+/// Alphabetic numbering as defined by PDF: a..z, then aa..zz, aaa..zzz, ...
+fn to_alpha(n: i32) -> String {
+    if n < 1 {
+        return String::new();
+    }
+    let letter = (b'a' + ((n - 1) % 26) as u8) as char;
+    std::iter::repeat(letter)
+        .take(((n - 1) / 26 + 1) as usize)
+        .collect()
+}
+
+/// Format the label of `page` according to a label range.
+fn format_page_label(range: &PageLabelEntry, page: i32) -> String {
+    let n = range.first_logical_page + (page - range.start_page);
+    let number = match range.style.as_str() {
+        "arabic" => n.to_string(),
+        "roman_lower" => to_roman(n),
+        "roman_upper" => to_roman(n).to_uppercase(),
+        "alpha_lower" => to_alpha(n),
+        "alpha_upper" => to_alpha(n).to_uppercase(),
+        _ => String::new(),
+    };
+    format!("{}{}", range.prefix.as_deref().unwrap_or(""), number)
+}
+
+/// Read the `/PageLabels` ranges of a PDF document.
+fn read_page_labels(pdf: &PdfDocument) -> Result<Vec<PageLabelEntry>> {
+    let Some(tree) = pdf.catalog()?.get_dict("PageLabels")? else {
+        return Ok(Vec::new());
+    };
+
+    let mut labels = number_tree_entries(&tree)?
+        .into_iter()
+        .map(|(start_page, dict)| {
+            let style = dict_name(&dict, "S")?;
+            Ok(PageLabelEntry {
+                start_page,
+                style: label_style_name(style.as_deref().unwrap_or("")).to_string(),
+                prefix: dict_string(&dict, "P")?,
+                first_logical_page: dict_int(&dict, "St")?.unwrap_or(1),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    labels.sort_by_key(|l| l.start_page);
+    Ok(labels)
+}
+
+/// Page label ranges of a stored document (empty for non-PDF documents).
+fn page_labels(store: &DocumentStore, document_id: &str) -> Result<Vec<PageLabelEntry>> {
+    match store.with_pdf_document(document_id, read_page_labels) {
+        Err(MupdfServerError::NotAPdf) => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+/// Get the page label ranges of a document.
+pub fn get_page_labels(
+    store: &DocumentStore,
+    params: GetPageLabelsParams,
+) -> Result<GetPageLabelsResult> {
+    Ok(GetPageLabelsResult {
+        labels: page_labels(store, &params.document_id)?,
+    })
+}
+
+// ============== Resolve Page Label ==============
+
+/// Parameters for resolving a page label.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolvePageLabelParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page label as displayed by viewers (e.g. "iii", "A-2", "17").
+    pub label: String,
+}
+
+/// Result of resolving a page label.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolvePageLabelResult {
+    /// Physical page number (0-indexed), or null if no page has this label.
+    pub page: Option<i32>,
+}
+
+/// Resolve a human-readable page label to its physical page number.
+///
+/// Documents without page labels use 1-based arabic numbering.
+pub fn resolve_page_label(
+    store: &DocumentStore,
+    params: ResolvePageLabelParams,
+) -> Result<ResolvePageLabelResult> {
+    let labels = page_labels(store, &params.document_id)?;
+    let page_count = store.with_document(&params.document_id, |doc| Ok(doc.page_count()?))?;
+
+    let page = (0..page_count).find(|&page| {
+        let label = match labels.iter().rev().find(|l| l.start_page <= page) {
+            Some(range) => format_page_label(range, page),
+            None => (page + 1).to_string(),
+        };
+        label == params.label
+    });
+
+    Ok(ResolvePageLabelResult { page })
+}
+
+// ============== Embedded Files ==============
+
+/// Parameters for listing embedded files.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetEmbeddedFilesParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// A file attached to the document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EmbeddedFileEntry {
+    /// Name of the file in the document's embedded files tree.
+    pub name: String,
+    /// Description of the file.
+    pub description: Option<String>,
+    /// MIME type of the file, if recorded.
+    pub mime_type: Option<String>,
+    /// Uncompressed size in bytes, if recorded.
+    pub size: Option<i64>,
+    /// Creation date (PDF date string).
+    pub creation_date: Option<String>,
+    /// Modification date (PDF date string).
+    pub modification_date: Option<String>,
+}
+
+/// Result of listing embedded files.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetEmbeddedFilesResult {
+    /// Embedded files in name tree order (empty if there are none).
+    pub files: Vec<EmbeddedFileEntry>,
+}
+
+/// Entries of the `/EmbeddedFiles` name tree: name and file specification.
+fn embedded_file_specs(pdf: &PdfDocument) -> Result<Vec<(String, PdfObject)>> {
+    let tree = match pdf.catalog()?.get_dict("Names")? {
+        Some(names) => names.get_dict("EmbeddedFiles")?,
+        None => None,
+    };
+    match tree {
+        Some(tree) => name_tree_entries(&tree),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The embedded file stream of a file specification.
+fn embedded_file_stream(spec: &PdfObject) -> Result<Option<PdfObject>> {
+    match spec.get_dict("EF")? {
+        Some(ef) => match ef.get_dict("UF")? {
+            Some(stream) => Ok(Some(stream)),
+            None => ef.get_dict("F"),
+        },
+        None => Ok(None),
+    }
+}
+
+fn embedded_file_entry(name: String, spec: &PdfObject) -> Result<EmbeddedFileEntry> {
+    let stream = embedded_file_stream(spec)?;
+    let params = match &stream {
+        Some(stream) => stream.get_dict("Params")?,
+        None => None,
+    };
+
+    Ok(EmbeddedFileEntry {
+        name,
+        description: dict_string(spec, "Desc")?,
+        mime_type: match &stream {
+            Some(stream) => dict_name(stream, "Subtype")?,
+            None => None,
+        },
+        size: match &params {
+            Some(params) => dict_int(params, "Size")?.map(i64::from),
+            None => None,
+        },
+        creation_date: match &params {
+            Some(params) => dict_string(params, "CreationDate")?,
+            None => None,
+        },
+        modification_date: match &params {
+            Some(params) => dict_string(params, "ModDate")?,
+            None => None,
+        },
+    })
+}
+
+/// List the files embedded in a document.
+///
+/// Returns an empty list for documents without attachments and for non-PDF documents.
+pub fn get_embedded_files(
+    store: &DocumentStore,
+    params: GetEmbeddedFilesParams,
+) -> Result<GetEmbeddedFilesResult> {
+    let files = store.with_pdf_document(&params.document_id, |pdf| {
+        embedded_file_specs(pdf)?
+            .into_iter()
+            .map(|(name, spec)| embedded_file_entry(name, &spec))
+            .collect::<Result<Vec<_>>>()
+    });
+
+    match files {
+        Ok(files) => Ok(GetEmbeddedFilesResult { files }),
+        Err(MupdfServerError::NotAPdf) => Ok(GetEmbeddedFilesResult { files: Vec::new() }),
+        Err(e) => Err(e),
+    }
+}
+
+// ============== Extract Embedded File ==============
+
+/// Parameters for extracting an embedded file.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExtractEmbeddedFileParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Name of the file as returned by `get_embedded_files`.
+    pub name: String,
+}
+
+/// Result of extracting an embedded file.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExtractEmbeddedFileResult {
+    /// Name of the file.
+    pub name: String,
+    /// Base64-encoded file content.
+    pub content_base64: String,
+    /// MIME type of the file, if recorded.
+    pub mime_type: Option<String>,
+}
+
+/// Extract the content of an embedded file.
+pub fn extract_embedded_file(
+    store: &DocumentStore,
+    params: ExtractEmbeddedFileParams,
+) -> Result<ExtractEmbeddedFileResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let stream = embedded_file_specs(pdf)?
+            .into_iter()
+            .find(|(name, _)| *name == params.name)
+            .map(|(_, spec)| embedded_file_stream(&spec))
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| MupdfServerError::EmbeddedFileNotFound(params.name.clone()))?;
+
+        let content = stream.read_stream()?;
+        Ok(ExtractEmbeddedFileResult {
+            mime_type: dict_name(&stream, "Subtype")?,
+            content_base64: base64::engine::general_purpose::STANDARD.encode(&content),
+            name: params.name,
+        })
+    })
+}
+
+// ============== Named Destinations ==============
+
+/// Parameters for listing named destinations.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetNamedDestinationsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// A named destination.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct NamedDestEntry {
+    /// Destination name.
+    pub name: String,
+    /// Target page number (0-indexed).
+    pub page: i32,
+    /// Destination type: "XYZ", "Fit", "FitH", "FitV", "FitR", "FitB", "FitBH" or "FitBV".
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// Result of listing named destinations.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetNamedDestinationsResult {
+    /// Named destinations in name tree order (empty if there are none).
+    pub destinations: Vec<NamedDestEntry>,
+}
+
+/// Type name and `(x, y, zoom)` of a destination.
+fn destination_details(
+    kind: &DestinationKind,
+) -> (&'static str, Option<f32>, Option<f32>, Option<f32>) {
+    match *kind {
+        DestinationKind::XYZ { left, top, zoom } => ("XYZ", left, top, zoom),
+        DestinationKind::Fit => ("Fit", None, None, None),
+        DestinationKind::FitH { top } => ("FitH", None, top, None),
+        DestinationKind::FitV { left } => ("FitV", left, None, None),
+        DestinationKind::FitR { left, top, .. } => ("FitR", Some(left), Some(top), None),
+        DestinationKind::FitB => ("FitB", None, None, None),
+        DestinationKind::FitBH { top } => ("FitBH", None, top, None),
+        DestinationKind::FitBV { left } => ("FitBV", left, None, None),
+    }
+}
+
+/// Resolve a named destination through MuPDF's link resolution.
+fn resolve_destination(
+    doc: &mupdf::Document,
+    name: &str,
+) -> Result<Option<mupdf::LinkDestination>> {
+    Ok(doc.resolve_link(&format!("#nameddest={}", name))?)
+}
+
+/// Names in the document's `/Dests` name tree.
+fn destination_names(pdf: &PdfDocument) -> Result<Vec<String>> {
+    let tree = match pdf.catalog()?.get_dict("Names")? {
+        Some(names) => names.get_dict("Dests")?,
+        None => None,
+    };
+    match tree {
+        Some(tree) => Ok(name_tree_entries(&tree)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// List the named destinations of a document.
+///
+/// Destinations that do not resolve to a page are skipped. Returns an
+/// empty list for non-PDF documents.
+pub fn get_named_destinations(
+    store: &DocumentStore,
+    params: GetNamedDestinationsParams,
+) -> Result<GetNamedDestinationsResult> {
+    let destinations = store.with_pdf_document(&params.document_id, |pdf| {
+        let mut destinations = Vec::new();
+        for name in destination_names(pdf)? {
+            if let Some(dest) = resolve_destination(pdf, &name)? {
+                destinations.push(NamedDestEntry {
+                    page: dest.loc.page_number as i32,
+                    type_: destination_details(&dest.kind).0.to_string(),
+                    name,
+                });
+            }
+        }
+        Ok(destinations)
+    });
+
+    match destinations {
+        Ok(destinations) => Ok(GetNamedDestinationsResult { destinations }),
+        Err(MupdfServerError::NotAPdf) => Ok(GetNamedDestinationsResult {
+            destinations: Vec::new(),
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+// ============== Resolve Named Destination ==============
+
+/// Parameters for resolving a named destination.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveNamedDestinationParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Destination name.
+    pub name: String,
+}
+
+/// Result of resolving a named destination.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolveNamedDestinationResult {
+    /// Target page number (0-indexed), or null if the name is unknown.
+    pub page: Option<i32>,
+    /// Destination type (e.g. "XYZ", "Fit").
+    pub dest_type: Option<String>,
+    /// X coordinate on the target page.
+    pub x: Option<f32>,
+    /// Y coordinate on the target page.
+    pub y: Option<f32>,
+    /// Zoom factor (XYZ destinations only).
+    pub zoom: Option<f32>,
+}
+
+/// Resolve a named destination to a page and position.
+pub fn resolve_named_destination(
+    store: &DocumentStore,
+    params: ResolveNamedDestinationParams,
+) -> Result<ResolveNamedDestinationResult> {
+    store.with_document(&params.document_id, |doc| {
+        match resolve_destination(doc, &params.name)? {
+            Some(dest) => {
+                let (dest_type, x, y, zoom) = destination_details(&dest.kind);
+                Ok(ResolveNamedDestinationResult {
+                    page: Some(dest.loc.page_number as i32),
+                    dest_type: Some(dest_type.to_string()),
+                    x,
+                    y,
+                    zoom,
+                })
+            }
+            None => Ok(ResolveNamedDestinationResult {
+                page: None,
+                dest_type: None,
+                x: None,
+                y: None,
+                zoom: None,
+            }),
+        }
+    })
+}
+
+// ============== Document Statistics ==============
+
+/// Average silent reading speed used for reading-time estimates.
+const WORDS_PER_MINUTE: f32 = 200.0;
+
+/// Parameters for getting document statistics.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDocumentStatsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Text statistics for a whole document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetDocumentStatsResult {
+    /// Total number of pages.
+    pub page_count: i32,
+    /// Number of non-whitespace characters.
+    pub total_characters: usize,
+    /// Number of whitespace-separated words.
+    pub total_words: usize,
+    /// Number of text lines.
+    pub total_lines: usize,
+    /// Number of text blocks.
+    pub total_blocks: usize,
+    /// Pages containing at least one non-whitespace character.
+    pub pages_with_text: i32,
+    /// Pages without any text (e.g. scanned images).
+    pub pages_without_text: i32,
+    /// Estimated reading time at 200 words per minute.
+    pub estimated_reading_minutes: f32,
+}
+
+/// Compute text statistics for a document in a single pass over its pages.
+pub fn get_document_statistics(
+    store: &DocumentStore,
+    params: GetDocumentStatsParams,
+) -> Result<GetDocumentStatsResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_count = doc.page_count()?;
+        let mut total_characters = 0;
+        let mut total_words = 0;
+        let mut total_lines = 0;
+        let mut total_blocks = 0;
+        let mut pages_with_text = 0;
+
+        for page_number in 0..page_count {
+            let page = doc.load_page(page_number)?;
+            let text_page = page.to_text_page(TextPageFlags::empty())?;
+            let mut page_characters = 0;
+
+            for block in text_page.blocks() {
+                total_blocks += 1;
+                for line in block.lines() {
+                    total_lines += 1;
+                    let text: String = line.chars().filter_map(|ch| ch.char()).collect();
+                    page_characters += text.chars().filter(|c| !c.is_whitespace()).count();
+                    total_words += text.split_whitespace().count();
+                }
+            }
+
+            total_characters += page_characters;
+            if page_characters > 0 {
+                pages_with_text += 1;
+            }
+        }
+
+        Ok(GetDocumentStatsResult {
+            page_count,
+            total_characters,
+            total_words,
+            total_lines,
+            total_blocks,
+            pages_with_text,
+            pages_without_text: page_count - pages_with_text,
+            estimated_reading_minutes: total_words as f32 / WORDS_PER_MINUTE,
+        })
+    })
+}
@@ -1,20 +1,28 @@
 //! Oneshot tools for stateless one-shot operations.
 //!
 //! These tools don't require document_id - they open, process, and close
-//! the document in a single call. Convenient for one-off operations.
+//! the document in a single call. Convenient for one-off operations. The
+//! store is only consulted for server configuration (e.g. allowed paths).
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::error::Result;
+use crate::state::DocumentStore;
+use crate::tools::document::{read_metadata, GetMetadataResult};
+use crate::tools::page::{
+    render_page_inner, resolve_scale, validate_page_number, RenderColorspace, SearchOptions,
+};
+use crate::tools::search::{search_document_pages, DocumentSearchHit};
 use crate::tools::session::DocumentSource;
+use crate::tools::text::extract_text_from_page;
 
 // ============== Oneshot Get Bookmarks ==============
 
 /// Parameters for extracting bookmarks with page numbers (oneshot).
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct OneshotGetBookmarksParams {
-    /// Document source (file path or base64 content).
+    /// Document source (file path, file paths, base64 content or URL).
     pub source: DocumentSource,
     /// Password for encrypted documents (optional).
     #[serde(default)]
@@ -66,9 +74,14 @@ fn collect_bookmarks(outline: &mupdf::Outline, level: i32, result: &mut Vec<Book
 /// This is a oneshot (stateless) operation - it opens the document,
 /// extracts bookmarks, and closes it in a single call.
 pub fn oneshot_get_bookmarks(
+    store: &DocumentStore,
     params: OneshotGetBookmarksParams,
 ) -> Result<OneshotGetBookmarksResult> {
-    let doc = params.source.open(params.password.as_deref())?;
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
     let page_count = doc.page_count()?;
 
     let mut bookmarks = Vec::new();
@@ -83,3 +96,348 @@ pub fn oneshot_get_bookmarks(
         page_count,
     })
 }
+
+// ============== Oneshot Extract Full Text ==============
+
+/// Parameters for extracting the text of every page (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotExtractFullTextParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Output format: "plain", "html", "json", "xml" (default "plain").
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Text extracted from a single page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PageTextEntry {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Extracted text in the requested format.
+    pub text: String,
+}
+
+/// Result of extracting the full document text.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotExtractFullTextResult {
+    /// Text of each page, in page order.
+    pub pages: Vec<PageTextEntry>,
+    /// Total number of pages in the document.
+    pub total_pages: i32,
+}
+
+/// Extract the text of every page in a document.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// extracts all page text, and closes it in a single call.
+pub fn oneshot_extract_full_text(
+    store: &DocumentStore,
+    params: OneshotExtractFullTextParams,
+) -> Result<OneshotExtractFullTextResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+    let total_pages = doc.page_count()?;
+    let format = params.format.as_deref().unwrap_or("plain");
+
+    let mut pages = Vec::with_capacity(total_pages.max(0) as usize);
+    for page_number in 0..total_pages {
+        let page = doc.load_page(page_number)?;
+        pages.push(PageTextEntry {
+            page: page_number,
+            text: extract_text_from_page(&page, format)?,
+        });
+    }
+
+    Ok(OneshotExtractFullTextResult { pages, total_pages })
+}
+
+// ============== Oneshot Get Page Text ==============
+
+/// Parameters for extracting the text of a single page (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotGetPageTextParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Output format: "plain", "html", "json", "xml" (default "plain").
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Result of extracting the text of a single page (oneshot).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotGetPageTextResult {
+    /// Extracted text in the requested format.
+    pub text: String,
+    /// Format of the text.
+    pub format: String,
+    /// Total number of pages in the document.
+    pub page_count: i32,
+}
+
+/// Extract the text of a single page.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// extracts the page text, and closes it in a single call.
+pub fn oneshot_get_page_text(
+    store: &DocumentStore,
+    params: OneshotGetPageTextParams,
+) -> Result<OneshotGetPageTextResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+    let page_count = doc.page_count()?;
+    let page_number = validate_page_number(&doc, params.page)?;
+    let page = doc.load_page(page_number)?;
+
+    let format = params.format.unwrap_or_else(|| "plain".to_string());
+    let text = extract_text_from_page(&page, &format)?;
+
+    Ok(OneshotGetPageTextResult {
+        text,
+        format,
+        page_count,
+    })
+}
+
+// ============== Oneshot Render Page ==============
+
+/// Parameters for rendering a single page (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotRenderPageParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Scale factor (default 1.0 = 72 DPI). Mutually exclusive with `dpi`.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Resolution in dots per inch. Mutually exclusive with `scale`.
+    #[serde(default)]
+    pub dpi: Option<f32>,
+    /// Output format: "png" or "svg" (default "png").
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Result of rendering a page (oneshot).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotRenderPageResult {
+    /// Base64-encoded PNG image data (PNG format only).
+    pub image: Option<String>,
+    /// SVG document (SVG format only).
+    pub svg: Option<String>,
+    /// Image width in pixels (points for SVG).
+    pub width: u32,
+    /// Image height in pixels (points for SVG).
+    pub height: u32,
+    /// Image format.
+    pub format: String,
+    /// Total number of pages in the document.
+    pub page_count: i32,
+}
+
+/// Render a single page to PNG or SVG.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// renders the page, and closes it in a single call.
+pub fn oneshot_render_page(
+    store: &DocumentStore,
+    params: OneshotRenderPageParams,
+) -> Result<OneshotRenderPageResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+    let page_count = doc.page_count()?;
+    let page_number = validate_page_number(&doc, params.page)?;
+    let page = doc.load_page(page_number)?;
+
+    let scale = resolve_scale(params.scale, params.dpi, store.render_scale_limit())?;
+    let format = params.format.unwrap_or_else(|| "png".to_string());
+    let (image, svg, width, height) = render_page_inner(
+        &page,
+        scale,
+        0,
+        &format,
+        RenderColorspace::Rgb,
+        false,
+        store.render_pixel_limit(),
+    )?;
+
+    Ok(OneshotRenderPageResult {
+        image,
+        svg,
+        width,
+        height,
+        format,
+        page_count,
+    })
+}
+
+// ============== Oneshot Get Page Count ==============
+
+/// Parameters for counting pages (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotGetPageCountParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Result of counting pages (oneshot).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotGetPageCountResult {
+    /// Total number of pages in the document.
+    pub page_count: i32,
+}
+
+/// Count the pages of a document.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// counts its pages, and closes it in a single call.
+pub fn oneshot_get_page_count(
+    store: &DocumentStore,
+    params: OneshotGetPageCountParams,
+) -> Result<OneshotGetPageCountResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+
+    Ok(OneshotGetPageCountResult {
+        page_count: doc.page_count()?,
+    })
+}
+
+// ============== Oneshot Get Metadata ==============
+
+/// Parameters for getting document metadata (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotGetMetadataParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Result of getting document metadata (oneshot).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotGetMetadataResult {
+    /// Document metadata.
+    #[serde(flatten)]
+    pub metadata: GetMetadataResult,
+    /// Total number of pages in the document.
+    pub page_count: i32,
+}
+
+/// Get document metadata (title, author, dates, ...) and page count.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// reads its metadata, and closes it in a single call.
+pub fn oneshot_get_metadata(
+    store: &DocumentStore,
+    params: OneshotGetMetadataParams,
+) -> Result<OneshotGetMetadataResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+
+    Ok(OneshotGetMetadataResult {
+        metadata: read_metadata(&doc),
+        page_count: doc.page_count()?,
+    })
+}
+
+// ============== Oneshot Search ==============
+
+/// Parameters for searching a document (oneshot).
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OneshotSearchParams {
+    /// Document source (file path, file paths, base64 content or URL).
+    pub source: DocumentSource,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Text to search for.
+    pub query: String,
+    /// Maximum hits per page (default 100).
+    #[serde(default)]
+    pub max_hits_per_page: Option<usize>,
+    /// Inclusive `[start, end]` page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub page_range: Option<[i32; 2]>,
+    /// Match letter case exactly (default true).
+    #[serde(default)]
+    pub case_sensitive: Option<bool>,
+    /// Only match whole words (default false).
+    #[serde(default)]
+    pub whole_word: Option<bool>,
+}
+
+/// Result of searching a document (oneshot).
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OneshotSearchResult {
+    /// Hits in page order.
+    pub hits: Vec<DocumentSearchHit>,
+    /// Total number of hits.
+    pub total_hits: usize,
+    /// Total number of pages in the document.
+    pub page_count: i32,
+}
+
+/// Search for text across all pages of a document.
+///
+/// This is a oneshot (stateless) operation - it opens the document,
+/// searches it, and closes it in a single call.
+pub fn oneshot_search(
+    store: &DocumentStore,
+    params: OneshotSearchParams,
+) -> Result<OneshotSearchResult> {
+    let doc = params.source.open(
+        params.password.as_deref(),
+        store.allowed_path_roots(),
+        store.url_sources_allowed(),
+    )?;
+    let page_count = doc.page_count()?;
+
+    let hits = search_document_pages(
+        &doc,
+        &params.query,
+        params.max_hits_per_page,
+        params.page_range.map(|[start, end]| (start, end)),
+        SearchOptions {
+            case_sensitive: params.case_sensitive.unwrap_or(true),
+            whole_word: params.whole_word.unwrap_or(false),
+        },
+    )?;
+
+    Ok(OneshotSearchResult {
+        total_hits: hits.len(),
+        hits,
+        page_count,
+    })
+}
@@ -0,0 +1,210 @@
+//! Image tools: enumerate and export raster images embedded in pages.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use base64::Engine;
+use mupdf::{ColorParams, Device, Image, Matrix, NativeDevice};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MupdfServerError, Result};
+use crate::state::DocumentStore;
+use crate::tools::page::validate_page_number;
+
+/// Bounding box of the unit square mapped through an image transform.
+fn image_bounds(ctm: &Matrix) -> [f32; 4] {
+    let xs = [ctm.e, ctm.a + ctm.e, ctm.c + ctm.e, ctm.a + ctm.c + ctm.e];
+    let ys = [ctm.f, ctm.b + ctm.f, ctm.d + ctm.f, ctm.b + ctm.d + ctm.f];
+    [
+        xs.iter().copied().fold(f32::INFINITY, f32::min),
+        ys.iter().copied().fold(f32::INFINITY, f32::min),
+        xs.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        ys.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+    ]
+}
+
+// ============== Extract Page Images ==============
+
+/// Parameters for extracting the images on a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExtractPageImagesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Whether to include base64-encoded PNG data for each image.
+    #[serde(default)]
+    pub include_data: bool,
+}
+
+/// An image drawn on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PageImageEntry {
+    /// Bounding box on the page as `[x0, y0, x1, y1]`.
+    pub bounds: [f32; 4],
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Colorspace name (e.g. "DeviceRGB"), if the image has one.
+    pub colorspace: Option<String>,
+    /// Bits per color component.
+    pub bits_per_component: u8,
+    /// Base64-encoded PNG data (only when `include_data` is set).
+    pub data: Option<String>,
+}
+
+/// Result of extracting page images.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExtractPageImagesResult {
+    /// Images in drawing order.
+    pub images: Vec<PageImageEntry>,
+}
+
+/// Device that records every image drawn while running a page, together
+/// with its image-to-page transform.
+struct ImageCollector {
+    include_data: bool,
+    images: Rc<RefCell<Vec<Result<(PageImageEntry, Matrix)>>>>,
+}
+
+impl ImageCollector {
+    fn entry(&self, img: &Image, ctm: &Matrix) -> Result<PageImageEntry> {
+        let data = if self.include_data {
+            let mut png_buffer = Vec::new();
+            img.to_pixmap()?
+                .write_to(&mut png_buffer, mupdf::ImageFormat::PNG)?;
+            Some(base64::engine::general_purpose::STANDARD.encode(&png_buffer))
+        } else {
+            None
+        };
+
+        Ok(PageImageEntry {
+            bounds: image_bounds(ctm),
+            width: img.width(),
+            height: img.height(),
+            colorspace: img.colorspace().map(|cs| cs.name().to_string()),
+            bits_per_component: img.bpc(),
+            data,
+        })
+    }
+}
+
+impl NativeDevice for ImageCollector {
+    fn fill_image(&mut self, img: &Image, ctm: Matrix, _alpha: f32, _cp: ColorParams) {
+        let entry = self.entry(img, &ctm).map(|entry| (entry, ctm));
+        self.images.borrow_mut().push(entry);
+    }
+}
+
+/// Run a page and collect the images it draws, in drawing order.
+pub(crate) fn collect_page_images(
+    page: &mupdf::Page,
+    include_data: bool,
+) -> Result<Vec<(PageImageEntry, Matrix)>> {
+    let images = Rc::new(RefCell::new(Vec::new()));
+    let device = Device::from_native(ImageCollector {
+        include_data,
+        images: Rc::clone(&images),
+    })?;
+    page.run(&device, &Matrix::IDENTITY)?;
+    drop(device);
+
+    Rc::try_unwrap(images)
+        .map_err(|_| MupdfServerError::internal("Image collector still in use"))?
+        .into_inner()
+        .into_iter()
+        .collect()
+}
+
+/// List (and optionally export) the raster images drawn on a page.
+pub fn extract_page_images(
+    store: &DocumentStore,
+    params: ExtractPageImagesParams,
+) -> Result<ExtractPageImagesResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        let images = collect_page_images(&page, params.include_data)?
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect();
+
+        Ok(ExtractPageImagesResult { images })
+    })
+}
+
+// ============== Get Page Image Blocks ==============
+
+/// Parameters for locating the image regions of a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageImageBlocksParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A region of a page covered by a raster image.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ImageBlockEntry {
+    /// Left edge of the image on the page.
+    pub x0: f32,
+    /// Top edge of the image on the page.
+    pub y0: f32,
+    /// Right edge of the image on the page.
+    pub x1: f32,
+    /// Bottom edge of the image on the page.
+    pub y1: f32,
+    /// Image-to-page matrix `[a, b, c, d, e, f]` mapping the unit square
+    /// onto the page.
+    pub transform: [f32; 6],
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Colorspace name (empty for stencil masks).
+    pub colorspace: String,
+    /// Bits per color component.
+    pub bpc: u8,
+}
+
+/// Result of locating image regions.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageImageBlocksResult {
+    /// Image regions in drawing order.
+    pub image_blocks: Vec<ImageBlockEntry>,
+}
+
+/// Locate the image regions of a page, as opposed to its text.
+pub fn get_page_image_blocks(
+    store: &DocumentStore,
+    params: GetPageImageBlocksParams,
+) -> Result<GetPageImageBlocksResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        let image_blocks = collect_page_images(&page, false)?
+            .into_iter()
+            .map(|(entry, ctm)| {
+                let [x0, y0, x1, y1] = entry.bounds;
+                ImageBlockEntry {
+                    x0,
+                    y0,
+                    x1,
+                    y1,
+                    transform: [ctm.a, ctm.b, ctm.c, ctm.d, ctm.e, ctm.f],
+                    width: entry.width,
+                    height: entry.height,
+                    colorspace: entry.colorspace.unwrap_or_default(),
+                    bpc: entry.bits_per_component,
+                }
+            })
+            .collect();
+
+        Ok(GetPageImageBlocksResult { image_blocks })
+    })
+}
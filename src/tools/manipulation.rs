@@ -0,0 +1,843 @@
+//! Document manipulation tools: split, merge and edit the pages of PDFs.
+
+use std::io::Read;
+
+use base64::Engine;
+use mupdf::pdf::{Encryption, PdfDocument, PdfObject, PdfWriteOptions, Permission};
+use mupdf::{Buffer, Document};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MupdfServerError, Result};
+use crate::state::DocumentStore;
+use crate::tools::document::PermissionsFlags;
+use crate::tools::page::{default_true, pdf_page_rotation, validate_page_number};
+use crate::tools::pdf_object::inherited_entry;
+
+/// Serialize a PDF document to bytes.
+pub(crate) fn write_pdf(doc: &PdfDocument) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    doc.write_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Serialize a PDF document to bytes with its encryption removed.
+///
+/// Used for working copies that are reopened from the bytes: the password
+/// of an unlocked document is not stored, so an encrypted copy would come
+/// back locked.
+pub(crate) fn write_pdf_decrypted(doc: &PdfDocument) -> Result<Vec<u8>> {
+    let mut options = PdfWriteOptions::default();
+    options.set_decrypt(true).set_encryption(Encryption::None);
+    let mut buffer = doc.write_to_buffer_with_options(options)?;
+    let mut bytes = Vec::new();
+    buffer.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Serialize a PDF document, returning its base64 encoding and size in bytes.
+fn encode_pdf(doc: &PdfDocument) -> Result<(String, usize)> {
+    let buffer = write_pdf(doc)?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&buffer);
+    Ok((encoded, buffer.len()))
+}
+
+// ============== Split Document ==============
+
+/// Parameters for extracting a page range into a new PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SplitDocumentParams {
+    /// Document ID.
+    pub document_id: String,
+    /// First page to extract (0-indexed; negative counts from the end, -1 = last page).
+    pub start_page: i32,
+    /// Last page to extract, inclusive (0-indexed; negative counts from the end, -1 = last page).
+    pub end_page: i32,
+}
+
+/// Result of splitting a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SplitDocumentResult {
+    /// Base64-encoded PDF containing the extracted pages.
+    pub base64: String,
+    /// Number of pages in the new PDF.
+    pub page_count: i32,
+    /// Size of the new PDF in bytes.
+    pub size_bytes: usize,
+}
+
+/// Copy an inclusive page range of a PDF into a new PDF.
+pub(crate) fn split_page_range(
+    pdf: &PdfDocument,
+    start_page: i32,
+    end_page: i32,
+) -> Result<PdfDocument> {
+    let start = validate_page_number(pdf, start_page)?;
+    let end = validate_page_number(pdf, end_page)?;
+    if start > end {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "start_page ({}) is after end_page ({})",
+            start_page, end_page
+        )));
+    }
+
+    let mut output = PdfDocument::new();
+    for page_number in start..=end {
+        output.graft_page(-1, pdf, page_number)?;
+    }
+    Ok(output)
+}
+
+/// Extract an inclusive page range of a PDF into a new base64 PDF.
+pub fn split_document(
+    store: &DocumentStore,
+    params: SplitDocumentParams,
+) -> Result<SplitDocumentResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let output = split_page_range(pdf, params.start_page, params.end_page)?;
+        let (base64, size_bytes) = encode_pdf(&output)?;
+
+        Ok(SplitDocumentResult {
+            base64,
+            page_count: output.page_count()?,
+            size_bytes,
+        })
+    })
+}
+
+// ============== Merge Documents ==============
+
+/// Parameters for merging pages of two documents into a new PDF.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MergeDocumentsParams {
+    /// ID of the document whose pages come first.
+    pub document_id_a: String,
+    /// Pages of the first document (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub pages_a: Option<Vec<i32>>,
+    /// ID of the document whose pages are appended.
+    pub document_id_b: String,
+    /// Pages of the second document (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub pages_b: Option<Vec<i32>>,
+}
+
+/// Result of merging documents.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MergeDocumentsResult {
+    /// Base64-encoded merged PDF.
+    pub base64: String,
+    /// Number of pages in the merged PDF.
+    pub page_count: i32,
+    /// Size of the merged PDF in bytes.
+    pub size_bytes: usize,
+}
+
+/// Resolve a page selection against a stored PDF, defaulting to every page.
+fn selected_pages(
+    store: &DocumentStore,
+    document_id: &str,
+    pages: Option<&[i32]>,
+) -> Result<Vec<i32>> {
+    store.with_pdf_document(document_id, |pdf| match pages {
+        Some(pages) => pages
+            .iter()
+            .map(|&page| validate_page_number(pdf, page))
+            .collect(),
+        None => Ok((0..pdf.page_count()?).collect()),
+    })
+}
+
+/// Merge pages of two open documents into a new base64 PDF.
+///
+/// The merged document is not added to the store; pass the result to
+/// `import_document` to work with it further.
+pub fn merge_documents(
+    store: &DocumentStore,
+    params: MergeDocumentsParams,
+) -> Result<MergeDocumentsResult> {
+    // Validate both selections before copying anything
+    let pages_a = selected_pages(store, &params.document_id_a, params.pages_a.as_deref())?;
+    let pages_b = selected_pages(store, &params.document_id_b, params.pages_b.as_deref())?;
+
+    let mut output = PdfDocument::new();
+    for (document_id, pages) in [
+        (&params.document_id_a, pages_a),
+        (&params.document_id_b, pages_b),
+    ] {
+        store.with_pdf_document(document_id, |pdf| {
+            for page_number in pages {
+                output.graft_page(-1, pdf, page_number)?;
+            }
+            Ok(())
+        })?;
+    }
+    let (base64, size_bytes) = encode_pdf(&output)?;
+
+    Ok(MergeDocumentsResult {
+        base64,
+        page_count: output.page_count()?,
+        size_bytes,
+    })
+}
+
+// ============== Create Document From Pages ==============
+
+/// A single page of an open document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PageSelection {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Parameters for assembling a new PDF from pages of open documents.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateDocumentFromPagesParams {
+    /// Pages to copy, in output order. A page may be selected more than once.
+    pub selections: Vec<PageSelection>,
+    /// Add the new PDF to the store and return its ID instead of base64
+    /// (default false).
+    #[serde(default)]
+    pub store_result: bool,
+}
+
+/// Result of assembling a new PDF.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CreateDocumentFromPagesResult {
+    /// ID of the stored document (when `store_result` is true).
+    pub document_id: Option<String>,
+    /// Base64-encoded PDF (when `store_result` is false).
+    pub base64: Option<String>,
+    /// Number of pages in the new PDF.
+    pub page_count: i32,
+}
+
+/// Assemble a new PDF from pages picked across open documents.
+pub fn create_document_from_pages(
+    store: &DocumentStore,
+    params: CreateDocumentFromPagesParams,
+) -> Result<CreateDocumentFromPagesResult> {
+    if params.selections.is_empty() {
+        return Err(MupdfServerError::InvalidParams(
+            "selections must not be empty".to_string(),
+        ));
+    }
+
+    // Validate every selection before copying anything
+    let pages = params
+        .selections
+        .iter()
+        .map(|selection| {
+            store.with_pdf_document(&selection.document_id, |pdf| {
+                validate_page_number(pdf, selection.page)
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut output = PdfDocument::new();
+    for (selection, page_number) in params.selections.iter().zip(pages) {
+        store.with_pdf_document(&selection.document_id, |pdf| {
+            output.graft_page(-1, pdf, page_number)?;
+            Ok(())
+        })?;
+    }
+    let page_count = output.page_count()?;
+
+    if params.store_result {
+        let bytes = write_pdf(&output)?;
+        let document = Document::from_bytes(&bytes, "application/pdf")?;
+        let inserted = store.insert_with_size(document, bytes.len())?;
+        Ok(CreateDocumentFromPagesResult {
+            document_id: Some(inserted.document_id),
+            base64: None,
+            page_count,
+        })
+    } else {
+        let (base64, _) = encode_pdf(&output)?;
+        Ok(CreateDocumentFromPagesResult {
+            document_id: None,
+            base64: Some(base64),
+            page_count,
+        })
+    }
+}
+
+// ============== Set Document Password ==============
+
+/// Parameters for re-encrypting a PDF with new passwords.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetDocumentPasswordParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Password required to open the PDF.
+    #[serde(default)]
+    pub user_password: Option<String>,
+    /// Password granting full access, including changing permissions.
+    #[serde(default)]
+    pub owner_password: Option<String>,
+    /// Operations allowed without the owner password (default: all).
+    #[serde(default)]
+    pub permissions: Option<PermissionsFlags>,
+}
+
+/// Result of re-encrypting a PDF.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SetDocumentPasswordResult {
+    /// Base64-encoded encrypted PDF.
+    pub base64: String,
+    /// Size of the encrypted PDF in bytes.
+    pub size_bytes: usize,
+}
+
+/// Convert permission flags to the PDF `/P` bits.
+fn pdf_permissions(flags: &PermissionsFlags) -> Permission {
+    let mut permissions = Permission::empty();
+    if flags.can_print {
+        permissions |= Permission::PRINT | Permission::PRINT_HQ;
+    }
+    if flags.can_modify {
+        permissions |= Permission::MODIFY | Permission::ASSEMBLE;
+    }
+    if flags.can_copy {
+        permissions |= Permission::COPY | Permission::ACCESSIBILITY;
+    }
+    if flags.can_annotate {
+        permissions |= Permission::ANNOTATE | Permission::FORM;
+    }
+    permissions
+}
+
+/// Write a copy of a PDF encrypted with AES-256 and the given passwords.
+///
+/// The stored document is left unchanged.
+pub fn set_document_password(
+    store: &DocumentStore,
+    params: SetDocumentPasswordParams,
+) -> Result<SetDocumentPasswordResult> {
+    let user_password = params.user_password.unwrap_or_default();
+    let owner_password = params.owner_password.unwrap_or_default();
+    if user_password.is_empty() && owner_password.is_empty() {
+        return Err(MupdfServerError::InvalidParams(
+            "user_password or owner_password is required".to_string(),
+        ));
+    }
+
+    let bytes = store.with_pdf_document(&params.document_id, |pdf| {
+        let mut options = PdfWriteOptions::default();
+        options
+            .set_encryption(Encryption::Aes256)
+            .set_user_password(&user_password)
+            .set_owner_password(&owner_password)
+            .set_permissions(
+                params
+                    .permissions
+                    .as_ref()
+                    .map_or_else(Permission::all, pdf_permissions),
+            );
+
+        let mut buffer = pdf
+            .write_to_buffer_with_options(options)
+            .map_err(|e| MupdfServerError::EncryptionError(e.to_string()))?;
+        let mut bytes = Vec::new();
+        buffer.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    })?;
+
+    Ok(SetDocumentPasswordResult {
+        base64: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        size_bytes: bytes.len(),
+    })
+}
+
+// ============== Optimize Document ==============
+
+/// Garbage collection level used by `optimize_document`: remove unused
+/// objects, compact the xref table and merge duplicate objects.
+const OPTIMIZE_GARBAGE_LEVEL: i32 = 3;
+
+/// Parameters for re-saving a PDF with compression.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OptimizeDocumentParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Write a linearized ("fast web view") PDF (default false).
+    #[serde(default)]
+    pub linearize: bool,
+    /// Drop unused objects and merge duplicates (default true).
+    #[serde(default = "default_true")]
+    pub garbage_collect: bool,
+    /// Compress embedded font streams (default true).
+    #[serde(default = "default_true")]
+    pub compress_fonts: bool,
+    /// Losslessly compress uncompressed image streams (default true).
+    #[serde(default = "default_true")]
+    pub compress_images: bool,
+}
+
+/// Result of optimizing a PDF.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OptimizeDocumentResult {
+    /// Base64-encoded optimized PDF.
+    pub base64: String,
+    /// Size of the document as imported, in bytes.
+    pub original_size_bytes: usize,
+    /// Size of the optimized PDF in bytes.
+    pub optimized_size_bytes: usize,
+    /// Size reduction relative to the original, in percent (0 if the
+    /// optimized PDF is not smaller).
+    pub reduction_percent: f32,
+}
+
+/// Re-save a PDF with compression and garbage collection, returning the
+/// smaller copy.
+///
+/// The stored document is left unchanged.
+pub fn optimize_document(
+    store: &DocumentStore,
+    params: OptimizeDocumentParams,
+) -> Result<OptimizeDocumentResult> {
+    let info = store.get_info(&params.document_id)?;
+    let (original_size_bytes, bytes) = store.with_pdf_document(&params.document_id, |pdf| {
+        // Documents inserted without a source size are measured as saved
+        let original_size_bytes = match info.size_bytes {
+            0 => write_pdf(pdf)?.len(),
+            size => size,
+        };
+
+        let mut options = PdfWriteOptions::default();
+        options
+            .set_compress(true)
+            .set_compress_fonts(params.compress_fonts)
+            .set_compress_images(params.compress_images)
+            .set_linear(params.linearize);
+        if params.garbage_collect {
+            options.set_garbage_level(OPTIMIZE_GARBAGE_LEVEL);
+        }
+
+        let mut buffer = pdf.write_to_buffer_with_options(options)?;
+        let mut bytes = Vec::new();
+        buffer.read_to_end(&mut bytes)?;
+        Ok((original_size_bytes, bytes))
+    })?;
+
+    let reduction_percent = if original_size_bytes > bytes.len() {
+        (original_size_bytes - bytes.len()) as f32 / original_size_bytes as f32 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(OptimizeDocumentResult {
+        base64: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        original_size_bytes,
+        optimized_size_bytes: bytes.len(),
+        reduction_percent,
+    })
+}
+
+// ============== Flatten Document ==============
+
+/// Parameters for flattening form fields.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FlattenDocumentParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// Result of flattening form fields.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FlattenDocumentResult {
+    /// Base64-encoded flattened PDF.
+    pub base64: String,
+    /// Size of the flattened PDF in bytes.
+    pub size_bytes: usize,
+    /// Number of form field widgets baked into page content.
+    pub fields_flattened: usize,
+}
+
+/// Bake form field appearances into page content, returning a new PDF
+/// without interactive fields.
+///
+/// The work is done on a copy; the stored document is left unchanged.
+pub fn flatten_document(
+    store: &DocumentStore,
+    params: FlattenDocumentParams,
+) -> Result<FlattenDocumentResult> {
+    let bytes = store.with_pdf_document(&params.document_id, write_pdf_decrypted)?;
+    let document = Document::from_bytes(&bytes, "application/pdf")?;
+    let mut pdf = PdfDocument::try_from(document)?;
+
+    let mut fields_flattened = 0;
+    for page_number in 0..pdf.page_count()? {
+        fields_flattened += pdf.load_page(page_number)?.widgets()?.count();
+    }
+
+    // Bake widgets only; other annotations stay interactive
+    pdf.bake(false, true)?;
+    let (base64, size_bytes) = encode_pdf(&pdf)?;
+
+    Ok(FlattenDocumentResult {
+        base64,
+        size_bytes,
+        fields_flattened,
+    })
+}
+
+// ============== Add Text Watermark ==============
+
+/// Default watermark font size in points.
+const DEFAULT_WATERMARK_FONT_SIZE: f32 = 48.0;
+
+/// Default watermark opacity.
+const DEFAULT_WATERMARK_OPACITY: f32 = 0.3;
+
+/// Default watermark color (mid gray).
+const DEFAULT_WATERMARK_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+
+/// Distance in points between a corner watermark and the page edges.
+const WATERMARK_MARGIN: f32 = 36.0;
+
+/// Approximate Helvetica glyph width as a fraction of the font size, used
+/// to place the text.
+const HELVETICA_AVERAGE_WIDTH: f32 = 0.55;
+
+/// Resource names of the watermark font and graphics state.
+const WATERMARK_FONT: &str = "MupdfMcpWatermarkFont";
+const WATERMARK_GSTATE: &str = "MupdfMcpWatermarkGs";
+
+/// Watermark positions accepted by `add_text_watermark`.
+const WATERMARK_POSITIONS: [&str; 5] = [
+    "center",
+    "top-left",
+    "top-right",
+    "bottom-left",
+    "bottom-right",
+];
+
+/// Parameters for stamping a text watermark onto pages.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddTextWatermarkParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Watermark text. Characters outside Latin-1 are replaced by `?`.
+    pub text: String,
+    /// Pages to stamp (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub pages: Option<Vec<i32>>,
+    /// Opacity from 0 (invisible) to 1 (opaque) (default 0.3).
+    #[serde(default)]
+    pub opacity: Option<f32>,
+    /// Font size in points (default 48).
+    #[serde(default)]
+    pub font_size: Option<f32>,
+    /// Text color as RGB components in the range 0-1 (default mid gray).
+    #[serde(default)]
+    pub color: Option<[f32; 3]>,
+    /// "center" (default), "top-left", "top-right", "bottom-left" or
+    /// "bottom-right", relative to the unrotated page.
+    #[serde(default)]
+    pub position: Option<String>,
+}
+
+/// Result of watermarking a PDF.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddTextWatermarkResult {
+    /// Base64-encoded watermarked PDF.
+    pub base64: String,
+    /// Number of pages stamped.
+    pub pages_modified: usize,
+}
+
+/// Encode text as a PDF literal string in WinAnsi (Latin-1) encoding.
+fn pdf_literal_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        let byte = match c as u32 {
+            // 0x80-0x9F map to other characters in WinAnsi
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        };
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+/// Get a sub-dictionary of `dict`, creating an empty one if it is missing.
+fn ensure_dict(pdf: &PdfDocument, dict: &mut PdfObject, key: &str) -> Result<PdfObject> {
+    if let Some(existing) = dict.get_dict(key)? {
+        return Ok(existing);
+    }
+    dict.dict_put(key, pdf.new_dict()?)?;
+    dict.get_dict(key)?
+        .ok_or_else(|| MupdfServerError::internal(format!("missing /{} after insert", key)))
+}
+
+/// Add a content stream to the document, returning a reference to it.
+fn add_content_stream(pdf: &mut PdfDocument, content: &[u8]) -> Result<PdfObject> {
+    let dict = pdf.new_dict()?;
+    let mut stream = pdf.add_object(&dict)?;
+    stream.write_stream_buffer(&Buffer::from_bytes(content)?)?;
+    Ok(stream)
+}
+
+/// Text origin for a watermark of `width` x `font_size` on a page box.
+fn watermark_origin(position: &str, page_box: [f32; 4], width: f32, font_size: f32) -> (f32, f32) {
+    let [x0, y0, x1, y1] = page_box;
+    let (left, right) = (x0 + WATERMARK_MARGIN, x1 - WATERMARK_MARGIN - width);
+    let (bottom, top) = (y0 + WATERMARK_MARGIN, y1 - WATERMARK_MARGIN - font_size);
+    match position {
+        "top-left" => (left, top),
+        "top-right" => (right, top),
+        "bottom-left" => (left, bottom),
+        "bottom-right" => (right, bottom),
+        _ => ((x0 + x1 - width) / 2.0, (y0 + y1 - font_size) / 2.0),
+    }
+}
+
+/// `[x0, y0, x1, y1]` of a page's crop box, falling back to its media box.
+fn page_box(pdf: &PdfDocument, page_number: i32) -> Result<[f32; 4]> {
+    let page = pdf.find_page(page_number)?;
+    let rect = match inherited_entry(page, "CropBox")? {
+        Some(rect) => Some(rect),
+        None => inherited_entry(pdf.find_page(page_number)?, "MediaBox")?,
+    };
+    let mut values = [0.0, 0.0, 612.0, 792.0];
+    if let Some(rect) = rect.filter(|r| r.len().is_ok_and(|len| len == 4)) {
+        for (i, value) in values.iter_mut().enumerate() {
+            if let Some(number) = rect.get_array(i as i32)? {
+                *value = number.as_float()?;
+            }
+        }
+    }
+    Ok([
+        values[0].min(values[2]),
+        values[1].min(values[3]),
+        values[0].max(values[2]),
+        values[1].max(values[3]),
+    ])
+}
+
+/// Stamp text onto the page content of a copy of a PDF.
+///
+/// The existing content is wrapped in `q`/`Q` so its graphics state does
+/// not leak into the watermark. The stored document is left unchanged.
+pub fn add_text_watermark(
+    store: &DocumentStore,
+    params: AddTextWatermarkParams,
+) -> Result<AddTextWatermarkResult> {
+    if params.text.is_empty() {
+        return Err(MupdfServerError::InvalidParams(
+            "text must not be empty".to_string(),
+        ));
+    }
+    let position = params.position.as_deref().unwrap_or("center");
+    if !WATERMARK_POSITIONS.contains(&position) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "invalid position: {} (valid positions: {})",
+            position,
+            WATERMARK_POSITIONS.join(", ")
+        )));
+    }
+    let font_size = params.font_size.unwrap_or(DEFAULT_WATERMARK_FONT_SIZE);
+    if font_size.is_nan() || font_size <= 0.0 {
+        return Err(MupdfServerError::InvalidParams(
+            "font_size must be positive".to_string(),
+        ));
+    }
+    let opacity = params
+        .opacity
+        .unwrap_or(DEFAULT_WATERMARK_OPACITY)
+        .clamp(0.0, 1.0);
+    let [r, g, b] = params
+        .color
+        .unwrap_or(DEFAULT_WATERMARK_COLOR)
+        .map(|c| c.clamp(0.0, 1.0));
+
+    let bytes = store.with_pdf_document(&params.document_id, write_pdf_decrypted)?;
+    let document = Document::from_bytes(&bytes, "application/pdf")?;
+    let mut pdf = PdfDocument::try_from(document)?;
+
+    let mut pages = match &params.pages {
+        Some(pages) => pages
+            .iter()
+            .map(|&page| validate_page_number(&pdf, page))
+            .collect::<Result<Vec<_>>>()?,
+        None => (0..pdf.page_count()?).collect(),
+    };
+    pages.sort_unstable();
+    pages.dedup();
+
+    // Shared by every stamped page, referenced by object number
+    let mut font = pdf.new_dict()?;
+    font.dict_put("Type", PdfObject::new_name("Font")?)?;
+    font.dict_put("Subtype", PdfObject::new_name("Type1")?)?;
+    font.dict_put("BaseFont", PdfObject::new_name("Helvetica")?)?;
+    font.dict_put("Encoding", PdfObject::new_name("WinAnsiEncoding")?)?;
+    let font = pdf.add_object(&font)?.as_indirect()?;
+    let mut gstate = pdf.new_dict()?;
+    gstate.dict_put("Type", PdfObject::new_name("ExtGState")?)?;
+    gstate.dict_put("ca", PdfObject::new_real(opacity)?)?;
+    let gstate = pdf.add_object(&gstate)?.as_indirect()?;
+    let save = add_content_stream(&mut pdf, b"q\n")?.as_indirect()?;
+
+    let text = pdf_literal_string(&params.text);
+    let width = params.text.chars().count() as f32 * font_size * HELVETICA_AVERAGE_WIDTH;
+    for &page_number in &pages {
+        let (x, y) = watermark_origin(position, page_box(&pdf, page_number)?, width, font_size);
+        let mut content = format!(
+            "Q\nq /{} gs {:.3} {:.3} {:.3} rg BT /{} {:.2} Tf {:.2} {:.2} Td ",
+            WATERMARK_GSTATE, r, g, b, WATERMARK_FONT, font_size, x, y
+        )
+        .into_bytes();
+        content.extend_from_slice(&text);
+        content.extend_from_slice(b" Tj ET Q\n");
+        let stamp = add_content_stream(&mut pdf, &content)?;
+
+        let mut page = pdf.find_page(page_number)?;
+        // Inherited resources are copied onto the page before changing them
+        let mut resources = match page.get_dict("Resources")? {
+            Some(resources) => resources,
+            None => {
+                let inherited = inherited_entry(pdf.find_page(page_number)?, "Resources")?;
+                page.dict_put("Resources", inherited.unwrap_or(pdf.new_dict()?))?;
+                ensure_dict(&pdf, &mut page, "Resources")?
+            }
+        };
+        ensure_dict(&pdf, &mut resources, "Font")?
+            .dict_put(WATERMARK_FONT, pdf.new_indirect(font, 0)?)?;
+        ensure_dict(&pdf, &mut resources, "ExtGState")?
+            .dict_put(WATERMARK_GSTATE, pdf.new_indirect(gstate, 0)?)?;
+
+        let mut contents = pdf.new_array()?;
+        contents.array_push(pdf.new_indirect(save, 0)?)?;
+        match page.get_dict("Contents")? {
+            Some(existing) if existing.is_array()? => {
+                for i in 0..existing.len()? as i32 {
+                    if let Some(stream) = existing.get_array(i)? {
+                        contents.array_push(stream)?;
+                    }
+                }
+            }
+            Some(existing) => contents.array_push(existing)?,
+            None => {}
+        }
+        contents.array_push(stamp)?;
+        page.dict_put("Contents", contents)?;
+    }
+
+    Ok(AddTextWatermarkResult {
+        base64: encode_pdf(&pdf)?.0,
+        pages_modified: pages.len(),
+    })
+}
+
+// ============== Delete Pages ==============
+
+/// Parameters for deleting pages from a stored document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeletePagesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Pages to delete (0-indexed; negative counts from the end, -1 = last page). Duplicates are ignored.
+    pub pages: Vec<i32>,
+}
+
+/// Result of deleting pages.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DeletePagesResult {
+    /// Number of pages left in the document.
+    pub new_page_count: i32,
+    /// Number of pages deleted.
+    pub deleted_count: usize,
+}
+
+/// Delete pages from a stored PDF in place.
+///
+/// All page numbers refer to the document before any deletion. At least
+/// one page must remain.
+pub fn delete_pages(store: &DocumentStore, params: DeletePagesParams) -> Result<DeletePagesResult> {
+    store.with_pdf_document_mut(&params.document_id, |pdf| {
+        let mut pages = params
+            .pages
+            .iter()
+            .map(|&page| validate_page_number(pdf, page))
+            .collect::<Result<Vec<_>>>()?;
+        pages.sort_unstable();
+        pages.dedup();
+        if pages.len() as i32 >= pdf.page_count()? {
+            return Err(MupdfServerError::InvalidParams(
+                "cannot delete every page of a document".to_string(),
+            ));
+        }
+
+        // Delete from the end so earlier page numbers stay valid
+        for &page_number in pages.iter().rev() {
+            pdf.delete_page(page_number)?;
+        }
+
+        Ok(DeletePagesResult {
+            new_page_count: pdf.page_count()?,
+            deleted_count: pages.len(),
+        })
+    })
+}
+
+// ============== Rotate Pages ==============
+
+/// Parameters for rotating pages of a stored document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RotatePagesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Pages to rotate (0-indexed; negative counts from the end, -1 = last page). Duplicates are ignored.
+    pub pages: Vec<i32>,
+    /// Clockwise rotation added to each page: 0, 90, 180 or 270.
+    pub degrees: i32,
+}
+
+/// Result of rotating pages.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RotatePagesResult {
+    /// Number of pages rotated.
+    pub rotated_count: usize,
+}
+
+/// Rotate pages of a stored PDF clockwise in place.
+///
+/// The rotation is added to each page's current `/Rotate`, so rotating a
+/// page by 90 twice turns it upside down.
+pub fn rotate_pages(store: &DocumentStore, params: RotatePagesParams) -> Result<RotatePagesResult> {
+    if ![0, 90, 180, 270].contains(&params.degrees) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "degrees must be 0, 90, 180 or 270, got {}",
+            params.degrees
+        )));
+    }
+
+    store.with_pdf_document_mut(&params.document_id, |pdf| {
+        // Validate every page before changing any
+        let mut pages = params
+            .pages
+            .iter()
+            .map(|&page| validate_page_number(pdf, page))
+            .collect::<Result<Vec<_>>>()?;
+        pages.sort_unstable();
+        pages.dedup();
+
+        for &page_number in &pages {
+            let rotation = (pdf_page_rotation(pdf, page_number)? + params.degrees) % 360;
+            // Set on the page itself, overriding any inherited /Rotate
+            let mut page = pdf.find_page(page_number)?;
+            page.dict_put("Rotate", PdfObject::new_int(rotation)?)?;
+        }
+
+        Ok(RotatePagesResult {
+            rotated_count: pages.len(),
+        })
+    })
+}
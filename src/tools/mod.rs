@@ -1,14 +1,26 @@
 //! MCP tool implementations for PDF operations.
 
+pub mod annotations;
 pub mod document;
 pub mod highlevel;
+pub mod images;
+pub mod manipulation;
 pub mod page;
+pub(crate) mod pdf_object;
+pub mod search;
 pub mod session;
+pub mod tables;
 pub mod text;
+pub(crate) mod util;
 
 // Re-export common types
+pub use annotations::*;
 pub use document::*;
 pub use highlevel::*;
+pub use images::*;
+pub use manipulation::*;
 pub use page::*;
+pub use search::*;
 pub use session::*;
+pub use tables::*;
 pub use text::*;
@@ -1,24 +1,61 @@
 //! Page-level operations: bounds, links, search, render.
 
 use base64::Engine;
-use mupdf::{Colorspace, Matrix};
+use std::collections::BTreeSet;
+
+use mupdf::pdf::{PdfDocument, PdfObject};
+use mupdf::{Colorspace, Device, Matrix, Pixmap, TextPageFlags};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{MupdfServerError, Result};
-use crate::state::DocumentStore;
+use crate::state::{DocumentHandle, DocumentStore};
+use crate::tools::images::collect_page_images;
+use crate::tools::pdf_object::{dict_int, dict_name, inherited_entry};
+use crate::tools::text::{quad_bounds, region_text, DEFAULT_REGION_OVERLAP};
+use crate::tools::util::resolve_page_index;
 
-/// Validate page number and return the page.
-fn validate_page_number(doc: &mupdf::Document, page: i32) -> Result<()> {
+/// Validate a page number, returning the resolved 0-indexed page.
+///
+/// Negative page numbers count from the end (`-1` is the last page).
+pub(crate) fn validate_page_number(doc: &mupdf::Document, page: i32) -> Result<i32> {
     let page_count = doc.page_count()?;
-    if page < 0 || page >= page_count {
+    let index = resolve_page_index(doc, page)?;
+    if index < 0 || index >= page_count {
         return Err(MupdfServerError::InvalidPageNumber {
             page,
             total: page_count,
             max: page_count - 1,
         });
     }
-    Ok(())
+    Ok(index)
+}
+
+/// Maximum number of `/Parent` levels searched for an inherited `/Rotate`.
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+
+/// Page rotation in degrees, normalized to 0, 90, 180 or 270.
+///
+/// Read from the (possibly inherited) `/Rotate` entry; non-PDF documents
+/// have no rotation.
+pub(crate) fn page_rotation(doc: &DocumentHandle, page: i32) -> Result<i32> {
+    match doc.as_pdf() {
+        Some(pdf) => pdf_page_rotation(pdf, page),
+        None => Ok(0),
+    }
+}
+
+/// Rotation in degrees of a PDF page, normalized to 0, 90, 180 or 270.
+pub(crate) fn pdf_page_rotation(pdf: &PdfDocument, page: i32) -> Result<i32> {
+    let mut node = Some(pdf.find_page(page)?);
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let Some(current) = node else { break };
+        if let Some(rotate) = dict_int(&current, "Rotate")? {
+            return Ok((rotate / 90).rem_euclid(4) * 90);
+        }
+        node = current.get_dict("Parent")?;
+    }
+    Ok(0)
 }
 
 // ============== Get Page Bounds ==============
@@ -28,7 +65,7 @@ fn validate_page_number(doc: &mupdf::Document, page: i32) -> Result<()> {
 pub struct GetPageBoundsParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
 }
 
@@ -43,6 +80,23 @@ pub struct GetPageBoundsResult {
     pub x0: f32,
     /// Y origin (usually 0).
     pub y0: f32,
+    /// Page rotation in degrees (0, 90, 180 or 270).
+    pub rotation: i32,
+}
+
+/// Dimensions and rotation of a single page.
+fn page_bounds(doc: &DocumentHandle, page_number: i32) -> Result<GetPageBoundsResult> {
+    let page_number = validate_page_number(doc, page_number)?;
+    let page = doc.load_page(page_number)?;
+    let bounds = page.bounds()?;
+
+    Ok(GetPageBoundsResult {
+        width: bounds.width(),
+        height: bounds.height(),
+        x0: bounds.x0,
+        y0: bounds.y0,
+        rotation: page_rotation(doc, page_number)?,
+    })
 }
 
 /// Get the dimensions of a page.
@@ -50,16 +104,269 @@ pub fn get_page_bounds(
     store: &DocumentStore,
     params: GetPageBoundsParams,
 ) -> Result<GetPageBoundsResult> {
-    store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
-        let bounds = page.bounds()?;
+    store.with_document_handle(&params.document_id, |doc| page_bounds(doc, params.page))
+}
+
+// ============== Get Pages Bounds ==============
+
+/// Parameters for getting the bounds of several pages.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPagesBoundsParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page numbers (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub pages: Option<Vec<i32>>,
+}
+
+/// Dimensions of one page in a batch query.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PageBoundsEntry {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Page dimensions and rotation.
+    #[serde(flatten)]
+    pub bounds: GetPageBoundsResult,
+}
+
+/// Result of getting the bounds of several pages.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPagesBoundsResult {
+    /// Bounds of each requested page, in request order.
+    pub pages: Vec<PageBoundsEntry>,
+}
+
+/// Get the dimensions of several pages (or all pages) in one call.
+pub fn get_pages_bounds(
+    store: &DocumentStore,
+    params: GetPagesBoundsParams,
+) -> Result<GetPagesBoundsResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let pages = match params.pages {
+            Some(pages) => pages,
+            None => (0..doc.page_count()?).collect(),
+        };
+
+        // Validate up front so an out-of-range page fails before any work is done
+        let pages = pages
+            .into_iter()
+            .map(|page| validate_page_number(doc, page))
+            .collect::<Result<Vec<_>>>()?;
+
+        let pages = pages
+            .into_iter()
+            .map(|page| {
+                Ok(PageBoundsEntry {
+                    page,
+                    bounds: page_bounds(doc, page)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(GetPagesBoundsResult { pages })
+    })
+}
+
+// ============== Get Page Display Bounds ==============
+
+/// Parameters for getting page dimensions before and after rotation.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageDisplayBoundsParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Page dimensions as displayed and as stored.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageDisplayBoundsResult {
+    /// Width in points after applying the page rotation.
+    pub display_width: f32,
+    /// Height in points after applying the page rotation.
+    pub display_height: f32,
+    /// Width in points of the unrotated page.
+    pub unrotated_width: f32,
+    /// Height in points of the unrotated page.
+    pub unrotated_height: f32,
+    /// Page rotation in degrees (0, 90, 180 or 270).
+    pub rotation: i32,
+}
+
+/// Get the dimensions of a page with and without its rotation applied.
+pub fn get_page_display_bounds(
+    store: &DocumentStore,
+    params: GetPageDisplayBoundsParams,
+) -> Result<GetPageDisplayBoundsResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let bounds = page_bounds(doc, params.page)?;
+        // MuPDF reports bounds with the rotation already applied
+        let (unrotated_width, unrotated_height) = if bounds.rotation % 180 == 0 {
+            (bounds.width, bounds.height)
+        } else {
+            (bounds.height, bounds.width)
+        };
 
-        Ok(GetPageBoundsResult {
-            width: bounds.width(),
-            height: bounds.height(),
-            x0: bounds.x0,
-            y0: bounds.y0,
+        Ok(GetPageDisplayBoundsResult {
+            display_width: bounds.width,
+            display_height: bounds.height,
+            unrotated_width,
+            unrotated_height,
+            rotation: bounds.rotation,
+        })
+    })
+}
+
+// ============== Get Page Size Preset ==============
+
+/// Points per inch.
+const POINTS_PER_INCH: f32 = 72.0;
+
+/// Millimetres per inch.
+const MM_PER_INCH: f32 = 25.4;
+
+/// Maximum difference in points between a page and a matched paper size.
+const PAPER_SIZE_TOLERANCE: f32 = 2.0;
+
+/// ISO 216 A and B series paper sizes (portrait width x height in mm).
+const ISO_216_SIZES_MM: [(&str, f32, f32); 22] = [
+    ("A0", 841.0, 1189.0),
+    ("A1", 594.0, 841.0),
+    ("A2", 420.0, 594.0),
+    ("A3", 297.0, 420.0),
+    ("A4", 210.0, 297.0),
+    ("A5", 148.0, 210.0),
+    ("A6", 105.0, 148.0),
+    ("A7", 74.0, 105.0),
+    ("A8", 52.0, 74.0),
+    ("A9", 37.0, 52.0),
+    ("A10", 26.0, 37.0),
+    ("B0", 1000.0, 1414.0),
+    ("B1", 707.0, 1000.0),
+    ("B2", 500.0, 707.0),
+    ("B3", 353.0, 500.0),
+    ("B4", 250.0, 353.0),
+    ("B5", 176.0, 250.0),
+    ("B6", 125.0, 176.0),
+    ("B7", 88.0, 125.0),
+    ("B8", 62.0, 88.0),
+    ("B9", 44.0, 62.0),
+    ("B10", 31.0, 44.0),
+];
+
+/// ANSI and ARCH paper sizes (portrait width x height in inches).
+const US_SIZES_INCHES: [(&str, f32, f32); 12] = [
+    ("Letter", 8.5, 11.0),
+    ("Legal", 8.5, 14.0),
+    ("Tabloid", 11.0, 17.0),
+    ("ANSI C", 17.0, 22.0),
+    ("ANSI D", 22.0, 34.0),
+    ("ANSI E", 34.0, 44.0),
+    ("ARCH A", 9.0, 12.0),
+    ("ARCH B", 12.0, 18.0),
+    ("ARCH C", 18.0, 24.0),
+    ("ARCH D", 24.0, 36.0),
+    ("ARCH E", 36.0, 48.0),
+    ("ARCH E1", 30.0, 42.0),
+];
+
+/// Parameters for matching a page against known paper sizes.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageSizePresetParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Page size with the matching paper size, if any.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageSizePresetResult {
+    /// Page width in points.
+    pub width_pt: f32,
+    /// Page height in points.
+    pub height_pt: f32,
+    /// Page width in millimetres.
+    pub width_mm: f32,
+    /// Page height in millimetres.
+    pub height_mm: f32,
+    /// Matching paper size (e.g. "A4", "Letter", "ARCH D"), or null.
+    pub preset: Option<String>,
+    /// "portrait" or "landscape".
+    pub orientation: String,
+}
+
+/// Name of the paper size matching a page in either orientation.
+fn paper_size_preset(width: f32, height: f32) -> Option<&'static str> {
+    let (short, long) = (width.min(height), width.max(height));
+    let iso = ISO_216_SIZES_MM
+        .iter()
+        .map(|&(name, w, h)| (name, w / MM_PER_INCH, h / MM_PER_INCH));
+    let us = US_SIZES_INCHES.iter().copied();
+
+    iso.chain(us)
+        .find(|&(_, w, h)| {
+            (w * POINTS_PER_INCH - short).abs() <= PAPER_SIZE_TOLERANCE
+                && (h * POINTS_PER_INCH - long).abs() <= PAPER_SIZE_TOLERANCE
+        })
+        .map(|(name, _, _)| name)
+}
+
+/// Match a page against ISO 216, ANSI and ARCH paper sizes.
+///
+/// Dimensions are as displayed, with the page rotation applied. Sizes match
+/// within 2 points in either orientation.
+pub fn get_page_size_preset(
+    store: &DocumentStore,
+    params: GetPageSizePresetParams,
+) -> Result<GetPageSizePresetResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let bounds = page_bounds(doc, params.page)?;
+        let to_mm = |points: f32| points / POINTS_PER_INCH * MM_PER_INCH;
+
+        Ok(GetPageSizePresetResult {
+            width_pt: bounds.width,
+            height_pt: bounds.height,
+            width_mm: to_mm(bounds.width),
+            height_mm: to_mm(bounds.height),
+            preset: paper_size_preset(bounds.width, bounds.height).map(str::to_string),
+            orientation: if bounds.width > bounds.height {
+                "landscape"
+            } else {
+                "portrait"
+            }
+            .to_string(),
+        })
+    })
+}
+
+// ============== Get Page Rotation ==============
+
+/// Parameters for getting page rotation.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageRotationParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Page rotation.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageRotationResult {
+    /// Clockwise rotation in degrees (0, 90, 180 or 270).
+    pub rotation_degrees: i32,
+}
+
+/// Get the display rotation of a page.
+pub fn get_page_rotation(
+    store: &DocumentStore,
+    params: GetPageRotationParams,
+) -> Result<GetPageRotationResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        Ok(GetPageRotationResult {
+            rotation_degrees: page_rotation(doc, page_number)?,
         })
     })
 }
@@ -71,8 +378,11 @@ pub fn get_page_bounds(
 pub struct GetPageLinksParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
+    /// Include the text under each link as `link_text` (default false).
+    #[serde(default)]
+    pub include_text: bool,
 }
 
 /// A hyperlink on a page.
@@ -84,6 +394,8 @@ pub struct PageLink {
     pub uri: Option<String>,
     /// Target page number (for internal links).
     pub target_page: Option<i32>,
+    /// Text under the link (only when requested with `include_text`).
+    pub link_text: Option<String>,
 }
 
 /// Bounding box for a link.
@@ -102,36 +414,135 @@ pub struct GetPageLinksResult {
     pub links: Vec<PageLink>,
 }
 
+/// Collect the hyperlinks of a page, optionally with the text under each.
+fn page_links(
+    doc: &mupdf::Document,
+    page_number: i32,
+    include_text: bool,
+) -> Result<Vec<PageLink>> {
+    let page = doc.load_page(page_number)?;
+    let text_page = if include_text {
+        Some(page.to_text_page(TextPageFlags::empty())?)
+    } else {
+        None
+    };
+
+    let mut links = Vec::new();
+    for link in page.links()? {
+        let target_page = doc
+            .resolve_link(&link.uri)
+            .ok()
+            .flatten()
+            .map(|dest| dest.loc.page_number as i32);
+
+        links.push(PageLink {
+            bounds: LinkBounds {
+                x0: link.bounds.x0,
+                y0: link.bounds.y0,
+                x1: link.bounds.x1,
+                y1: link.bounds.y1,
+            },
+            uri: Some(link.uri.clone()),
+            target_page,
+            link_text: text_page.as_ref().map(|text_page| {
+                let bounds = [
+                    link.bounds.x0,
+                    link.bounds.y0,
+                    link.bounds.x1,
+                    link.bounds.y1,
+                ];
+                region_text(text_page, bounds, DEFAULT_REGION_OVERLAP).0
+            }),
+        });
+    }
+
+    Ok(links)
+}
+
 /// Get all hyperlinks on a page.
 pub fn get_page_links(
     store: &DocumentStore,
     params: GetPageLinksParams,
 ) -> Result<GetPageLinksResult> {
     store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
+        let page_number = validate_page_number(doc, params.page)?;
+
+        Ok(GetPageLinksResult {
+            links: page_links(doc, page_number, params.include_text)?,
+        })
+    })
+}
+
+// ============== Get Page Links (All Pages) ==============
+
+/// Parameters for getting the hyperlinks of every page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageLinksAllParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Include links to locations inside the document (default true).
+    #[serde(default = "default_true")]
+    pub include_internal: bool,
+    /// Include links to external URIs (default true).
+    #[serde(default = "default_true")]
+    pub include_external: bool,
+}
+
+/// A hyperlink with the page it is on.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PageLinkWithPage {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Link details.
+    #[serde(flatten)]
+    pub link: PageLink,
+}
+
+/// Result of getting the hyperlinks of every page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageLinksAllResult {
+    /// Links in page order.
+    pub links: Vec<PageLinkWithPage>,
+    /// Number of links returned.
+    pub total: usize,
+}
+
+impl PageLink {
+    /// Whether the link points inside the document.
+    fn is_internal(&self) -> bool {
+        self.target_page.is_some() || self.uri.as_deref().is_some_and(|u| u.starts_with('#'))
+    }
+}
 
+/// Get the hyperlinks on every page of a document.
+pub fn get_page_links_all(
+    store: &DocumentStore,
+    params: GetPageLinksAllParams,
+) -> Result<GetPageLinksAllResult> {
+    store.with_document(&params.document_id, |doc| {
         let mut links = Vec::new();
-        for link in page.links()? {
-            let target_page = doc
-                .resolve_link(&link.uri)
-                .ok()
-                .flatten()
-                .map(|dest| dest.loc.page_number as i32);
-
-            links.push(PageLink {
-                bounds: LinkBounds {
-                    x0: link.bounds.x0,
-                    y0: link.bounds.y0,
-                    x1: link.bounds.x1,
-                    y1: link.bounds.y1,
-                },
-                uri: Some(link.uri.clone()),
-                target_page,
-            });
+        for page_number in 0..doc.page_count()? {
+            links.extend(
+                page_links(doc, page_number, false)?
+                    .into_iter()
+                    .filter(|link| {
+                        if link.is_internal() {
+                            params.include_internal
+                        } else {
+                            params.include_external
+                        }
+                    })
+                    .map(|link| PageLinkWithPage {
+                        page: page_number,
+                        link,
+                    }),
+            );
         }
 
-        Ok(GetPageLinksResult { links })
+        Ok(GetPageLinksAllResult {
+            total: links.len(),
+            links,
+        })
     })
 }
 
@@ -142,10 +553,29 @@ pub fn get_page_links(
 pub struct SearchPageParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
     /// Text to search for.
     pub query: String,
+    /// Match letter case exactly (default true).
+    #[serde(default = "default_true")]
+    pub case_sensitive: bool,
+    /// Only match whole words (default false).
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// How a search query is matched against page text.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SearchOptions {
+    /// Match letter case exactly.
+    pub case_sensitive: bool,
+    /// Only match whole words.
+    pub whole_word: bool,
 }
 
 /// A search hit with its bounding quad.
@@ -175,35 +605,152 @@ pub struct SearchPageResult {
     pub hits: Vec<SearchHit>,
 }
 
+/// Default maximum number of hits returned per page.
+pub(crate) const DEFAULT_MAX_HITS: u32 = 100;
+
+/// Convert a MuPDF quad to a search hit.
+pub(crate) fn quad_to_hit(quad: &mupdf::Quad) -> SearchHit {
+    SearchHit {
+        ul: Point {
+            x: quad.ul.x,
+            y: quad.ul.y,
+        },
+        ur: Point {
+            x: quad.ur.x,
+            y: quad.ur.y,
+        },
+        ll: Point {
+            x: quad.ll.x,
+            y: quad.ll.y,
+        },
+        lr: Point {
+            x: quad.lr.x,
+            y: quad.lr.y,
+        },
+    }
+}
+
+/// Convert an axis-aligned `[x0, y0, x1, y1]` box to a search hit.
+fn bounds_to_hit([x0, y0, x1, y1]: [f32; 4]) -> SearchHit {
+    SearchHit {
+        ul: Point { x: x0, y: y0 },
+        ur: Point { x: x1, y: y0 },
+        ll: Point { x: x0, y: y1 },
+        lr: Point { x: x1, y: y1 },
+    }
+}
+
+/// Search a loaded page, returning at most `max_hits` hits.
+///
+/// MuPDF's built-in search always ignores case and matches substrings, so
+/// it is used only when those are the requested semantics. Otherwise the
+/// page's characters are matched directly, yielding one hit per line a
+/// match spans.
+pub(crate) fn search_hits(
+    page: &mupdf::Page,
+    query: &str,
+    max_hits: u32,
+    options: SearchOptions,
+) -> Result<Vec<SearchHit>> {
+    if !options.case_sensitive && !options.whole_word {
+        let hits = page
+            .search(query, max_hits)?
+            .iter()
+            .map(quad_to_hit)
+            .collect();
+        return Ok(hits);
+    }
+
+    let needle: Vec<char> = query.chars().collect();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Flatten the page into (char, line, bounds); lines are separated by a
+    // space without bounds so matches can span line breaks.
+    let text_page = page.to_text_page(TextPageFlags::empty())?;
+    let mut chars: Vec<(char, usize, Option<[f32; 4]>)> = Vec::new();
+    let mut line_number = 0;
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            for ch in line.chars() {
+                if let Some(c) = ch.char() {
+                    chars.push((c, line_number, Some(quad_bounds(&ch.quad()))));
+                }
+            }
+            chars.push((' ', line_number, None));
+            line_number += 1;
+        }
+    }
+
+    let chars_equal = |a: char, b: char| {
+        if options.case_sensitive {
+            a == b
+        } else {
+            a.to_lowercase().eq(b.to_lowercase())
+        }
+    };
+    let is_word_char = |i: usize| chars.get(i).is_some_and(|(c, _, _)| c.is_alphanumeric());
+
+    let mut hits = Vec::new();
+    let mut start = 0;
+    while start + needle.len() <= chars.len() && hits.len() < max_hits as usize {
+        let end = start + needle.len();
+        let matched = chars[start..end]
+            .iter()
+            .zip(&needle)
+            .all(|((c, _, _), q)| chars_equal(*c, *q));
+        let at_word_boundary =
+            !options.whole_word || ((start == 0 || !is_word_char(start - 1)) && !is_word_char(end));
+
+        if !(matched && at_word_boundary) {
+            start += 1;
+            continue;
+        }
+
+        // Merge the matched characters into one box per line
+        let mut current: Option<(usize, [f32; 4])> = None;
+        for (_, line, bounds) in &chars[start..end] {
+            let Some(b) = bounds else {
+                continue;
+            };
+            match current.as_mut() {
+                Some((l, acc)) if l == line => {
+                    acc[0] = acc[0].min(b[0]);
+                    acc[1] = acc[1].min(b[1]);
+                    acc[2] = acc[2].max(b[2]);
+                    acc[3] = acc[3].max(b[3]);
+                }
+                _ => {
+                    if let Some((_, acc)) = current.replace((*line, *b)) {
+                        hits.push(bounds_to_hit(acc));
+                    }
+                }
+            }
+        }
+        if let Some((_, acc)) = current {
+            hits.push(bounds_to_hit(acc));
+        }
+
+        start = end;
+    }
+    hits.truncate(max_hits as usize);
+
+    Ok(hits)
+}
+
 /// Search for text on a page.
 pub fn search_page(store: &DocumentStore, params: SearchPageParams) -> Result<SearchPageResult> {
     store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
 
         // Search with a reasonable hit limit
-        let hits: Vec<SearchHit> = page
-            .search(&params.query, 100)?
-            .iter()
-            .map(|quad| SearchHit {
-                ul: Point {
-                    x: quad.ul.x,
-                    y: quad.ul.y,
-                },
-                ur: Point {
-                    x: quad.ur.x,
-                    y: quad.ur.y,
-                },
-                ll: Point {
-                    x: quad.ll.x,
-                    y: quad.ll.y,
-                },
-                lr: Point {
-                    x: quad.lr.x,
-                    y: quad.lr.y,
-                },
-            })
-            .collect();
+        let options = SearchOptions {
+            case_sensitive: params.case_sensitive,
+            whole_word: params.whole_word,
+        };
+        let hits = search_hits(&page, &params.query, DEFAULT_MAX_HITS, options)?;
 
         Ok(SearchPageResult { hits })
     })
@@ -216,52 +763,841 @@ pub fn search_page(store: &DocumentStore, params: SearchPageParams) -> Result<Se
 pub struct RenderPageParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
-    /// Scale factor (default 1.0 = 72 DPI).
-    #[serde(default = "default_scale")]
-    pub scale: f32,
+    /// Scale factor (default 1.0 = 72 DPI). Mutually exclusive with `dpi`.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Resolution in dots per inch. Mutually exclusive with `scale`.
+    #[serde(default)]
+    pub dpi: Option<f32>,
+    /// Output format: "png" or "svg".
+    #[serde(default = "default_image_format")]
+    pub format: String,
+    /// Raster colorspace: "rgb" (default), "gray" or "cmyk". CMYK renders
+    /// are encoded as PSD since PNG has no CMYK mode.
+    #[serde(default)]
+    pub colorspace: Option<String>,
+    /// Render onto a transparent instead of a white background, adding an
+    /// alpha channel to raster output (default false).
+    #[serde(default)]
+    pub transparent_background: Option<bool>,
+    /// Apply the page's `/Rotate` so the image is in display orientation
+    /// (default true). With false the page is rendered unrotated, in its
+    /// stored coordinate system.
+    #[serde(default)]
+    pub rotate_to_upright: Option<bool>,
+}
+
+/// Colorspace of raster page renders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum RenderColorspace {
+    #[default]
+    Rgb,
+    Gray,
+    Cmyk,
+}
+
+impl RenderColorspace {
+    /// Parse a colorspace name, defaulting to RGB.
+    pub(crate) fn parse(name: Option<&str>) -> Result<Self> {
+        match name.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("rgb") => Ok(Self::Rgb),
+            Some("gray") | Some("grey") => Ok(Self::Gray),
+            Some("cmyk") => Ok(Self::Cmyk),
+            Some(other) => Err(MupdfServerError::InvalidParams(format!(
+                "unsupported colorspace {:?}; expected \"rgb\", \"gray\" or \"cmyk\"",
+                other
+            ))),
+        }
+    }
+
+    /// Name reported in render results.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Rgb => "rgb",
+            Self::Gray => "gray",
+            Self::Cmyk => "cmyk",
+        }
+    }
+
+    fn colorspace(self) -> Colorspace {
+        match self {
+            Self::Rgb => Colorspace::device_rgb(),
+            Self::Gray => Colorspace::device_gray(),
+            Self::Cmyk => Colorspace::device_cmyk(),
+        }
+    }
+
+    /// Raster encoding used for this colorspace and its format name.
+    fn raster_format(self) -> (mupdf::ImageFormat, &'static str) {
+        match self {
+            Self::Cmyk => (mupdf::ImageFormat::PSD, "psd"),
+            Self::Rgb | Self::Gray => (mupdf::ImageFormat::PNG, "png"),
+        }
+    }
+
+    /// Format actually produced when `format` is requested.
+    pub(crate) fn output_format(self, format: &str) -> String {
+        match format {
+            "png" => self.raster_format().1.to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Base resolution of PDF user space (1 point = 1/72 inch).
+const BASE_DPI: f32 = 72.0;
+
+/// Resolve the effective scale factor from optional `scale` and `dpi` values,
+/// rejecting scales above `max_scale`.
+pub(crate) fn resolve_scale(
+    scale: Option<f32>,
+    dpi: Option<f32>,
+    max_scale: Option<f32>,
+) -> Result<f32> {
+    let effective_scale = match (scale, dpi) {
+        (Some(_), Some(_)) => {
+            return Err(MupdfServerError::InvalidParams(
+                "specify either scale or dpi, not both".to_string(),
+            ))
+        }
+        (Some(scale), None) => scale,
+        (None, Some(dpi)) => dpi / BASE_DPI,
+        (None, None) => 1.0,
+    };
+
+    if !(effective_scale.is_finite() && effective_scale > 0.0) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "scale must be positive, got {}",
+            effective_scale
+        )));
+    }
+
+    if let Some(max) = max_scale.filter(|&max| effective_scale > max) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "scale {} exceeds the maximum of {}",
+            effective_scale, max
+        )));
+    }
+
+    Ok(effective_scale)
 }
 
-fn default_scale() -> f32 {
-    1.0
+fn default_image_format() -> String {
+    "png".to_string()
 }
 
 /// Result of rendering a page.
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct RenderPageResult {
+    /// Base64-encoded PNG image data (PNG format only).
+    pub image: Option<String>,
+    /// SVG document (SVG format only).
+    pub svg: Option<String>,
+    /// Image width in pixels (points for SVG).
+    pub width: u32,
+    /// Image height in pixels (points for SVG).
+    pub height: u32,
+    /// Image format ("png", "svg", or "psd" for CMYK renders).
+    pub format: String,
+    /// Resolution actually used for rendering.
+    pub effective_dpi: f32,
+    /// Raster colorspace ("rgb", "gray" or "cmyk").
+    pub colorspace: String,
+    /// Whether the raster image has an alpha channel.
+    pub has_alpha: bool,
+}
+
+/// Reject a `width` x `height` pixmap larger than `max_pixels`.
+fn check_render_size(width: f32, height: f32, max_pixels: u64) -> Result<()> {
+    let width = width.ceil().max(0.0) as u64;
+    let height = height.ceil().max(0.0) as u64;
+    if width.saturating_mul(height) > max_pixels {
+        return Err(MupdfServerError::RenderTooLarge {
+            width,
+            height,
+            max_pixels,
+        });
+    }
+    Ok(())
+}
+
+/// Render transform that scales a page and turns it clockwise by `degrees`
+/// (a multiple of 90), moving the turned page back to the origin.
+///
+/// Also returns the unscaled width and height of the turned page.
+fn render_matrix(bounds: &mupdf::Rect, scale: f32, degrees: i32) -> (Matrix, f32, f32) {
+    let (width, height) = (bounds.width(), bounds.height());
+    match degrees.rem_euclid(360) {
+        90 => (
+            Matrix::new(
+                0.0,
+                scale,
+                -scale,
+                0.0,
+                bounds.y1 * scale,
+                -bounds.x0 * scale,
+            ),
+            height,
+            width,
+        ),
+        180 => (
+            Matrix::new(
+                -scale,
+                0.0,
+                0.0,
+                -scale,
+                bounds.x1 * scale,
+                bounds.y1 * scale,
+            ),
+            width,
+            height,
+        ),
+        270 => (
+            Matrix::new(
+                0.0,
+                -scale,
+                scale,
+                0.0,
+                -bounds.y0 * scale,
+                bounds.x1 * scale,
+            ),
+            height,
+            width,
+        ),
+        _ => (Matrix::new_scale(scale, scale), width, height),
+    }
+}
+
+/// Render a loaded page as a base64 raster image or an SVG document.
+///
+/// Returns `(image, svg, width, height)`; exactly one of `image` and `svg`
+/// is set depending on `format`. Raster images are PNG, or PSD for CMYK.
+/// With `alpha`, raster images get an alpha channel and the page is drawn
+/// onto a transparent background rather than white. `turn_degrees` turns
+/// the rendered page clockwise by a further multiple of 90 degrees. Raster
+/// renders larger than `max_pixels` are rejected before any pixmap is
+/// allocated.
+pub(crate) fn render_page_inner(
+    page: &mupdf::Page,
+    scale: f32,
+    turn_degrees: i32,
+    format: &str,
+    colorspace: RenderColorspace,
+    alpha: bool,
+    max_pixels: u64,
+) -> Result<(Option<String>, Option<String>, u32, u32)> {
+    let (matrix, width, height) = render_matrix(&page.bounds()?, scale, turn_degrees);
+
+    match format {
+        "png" => {
+            check_render_size(width * scale, height * scale, max_pixels)?;
+            let pixmap = page.to_pixmap(&matrix, &colorspace.colorspace(), alpha, true)?;
+
+            // Write to image bytes using the pixmap's write method
+            let mut png_buffer = Vec::new();
+            pixmap.write_to(&mut png_buffer, colorspace.raster_format().0)?;
+            let image = base64::engine::general_purpose::STANDARD.encode(&png_buffer);
+
+            Ok((Some(image), None, pixmap.width(), pixmap.height()))
+        }
+        "svg" => {
+            let svg = page.to_svg(&matrix)?;
+            let width = (width * scale).round() as u32;
+            let height = (height * scale).round() as u32;
+
+            Ok((None, Some(svg), width, height))
+        }
+        other => Err(MupdfServerError::InvalidImageFormat(other.to_string())),
+    }
+}
+
+/// Render a page to a PNG image or SVG document.
+pub fn render_page(store: &DocumentStore, params: RenderPageParams) -> Result<RenderPageResult> {
+    store.with_document_handle(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        // MuPDF applies /Rotate when rendering; turning the page back by
+        // the same angle gives the stored orientation
+        let turn_degrees = if params.rotate_to_upright.unwrap_or(true) {
+            0
+        } else {
+            360 - page_rotation(doc, page_number)?
+        };
+
+        let scale = resolve_scale(params.scale, params.dpi, store.render_scale_limit())?;
+        let colorspace = RenderColorspace::parse(params.colorspace.as_deref())?;
+        let alpha = params.transparent_background.unwrap_or(false);
+        let (image, svg, width, height) = render_page_inner(
+            &page,
+            scale,
+            turn_degrees,
+            &params.format,
+            colorspace,
+            alpha,
+            store.render_pixel_limit(),
+        )?;
+
+        Ok(RenderPageResult {
+            has_alpha: alpha && image.is_some(),
+            image,
+            svg,
+            width,
+            height,
+            format: colorspace.output_format(&params.format),
+            effective_dpi: scale * BASE_DPI,
+            colorspace: colorspace.name().to_string(),
+        })
+    })
+}
+
+// ============== Render Page Region ==============
+
+/// Parameters for rendering a rectangular region of a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenderPageRegionParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Left edge of the region in page coordinates.
+    pub x0: f32,
+    /// Top edge of the region in page coordinates.
+    pub y0: f32,
+    /// Right edge of the region in page coordinates.
+    pub x1: f32,
+    /// Bottom edge of the region in page coordinates.
+    pub y1: f32,
+    /// Scale factor (default 1.0 = 72 DPI).
+    #[serde(default)]
+    pub scale: Option<f32>,
+}
+
+/// Result of rendering a page region.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RenderPageRegionResult {
     /// Base64-encoded PNG image data.
     pub image: String,
     /// Image width in pixels.
     pub width: u32,
     /// Image height in pixels.
     pub height: u32,
-    /// Image format.
+    /// Image format (always "png").
     pub format: String,
 }
 
-/// Render a page to a PNG image.
-pub fn render_page(store: &DocumentStore, params: RenderPageParams) -> Result<RenderPageResult> {
+/// Render a rectangular region of a page to a PNG image.
+pub fn render_page_region(
+    store: &DocumentStore,
+    params: RenderPageRegionParams,
+) -> Result<RenderPageRegionResult> {
+    let scale = resolve_scale(params.scale, None, store.render_scale_limit())?;
+    let RenderPageRegionParams { x0, y0, x1, y1, .. } = params;
+    if !(x0 < x1 && y0 < y1) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "region [{}, {}, {}, {}] is empty",
+            x0, y0, x1, y1
+        )));
+    }
+
     store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        let bounds = page.bounds()?;
+        if x0 < bounds.x0 || y0 < bounds.y0 || x1 > bounds.x1 || y1 > bounds.y1 {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "region [{}, {}, {}, {}] is outside the page bounds [{}, {}, {}, {}]",
+                x0, y0, x1, y1, bounds.x0, bounds.y0, bounds.x1, bounds.y1
+            )));
+        }
 
-        let matrix = Matrix::new_scale(params.scale, params.scale);
-        let pixmap = page.to_pixmap(&matrix, &Colorspace::device_rgb(), false, true)?;
+        let width = ((x1 - x0) * scale).round().max(1.0) as i32;
+        let height = ((y1 - y0) * scale).round().max(1.0) as i32;
+        check_render_size(width as f32, height as f32, store.render_pixel_limit())?;
 
-        let width = pixmap.width();
-        let height = pixmap.height();
+        // Map the region's top-left corner to the pixmap origin, then scale
+        let matrix = Matrix::new(scale, 0.0, 0.0, scale, -x0 * scale, -y0 * scale);
+        let mut pixmap = Pixmap::new_with_w_h(&Colorspace::device_rgb(), width, height, false)?;
+        pixmap.clear_with(255)?;
+        {
+            let device = Device::from_pixmap(&pixmap)?;
+            page.run(&device, &matrix)?;
+        }
 
-        // Write to PNG bytes using the pixmap's write method
         let mut png_buffer = Vec::new();
         pixmap.write_to(&mut png_buffer, mupdf::ImageFormat::PNG)?;
-        let image = base64::engine::general_purpose::STANDARD.encode(&png_buffer);
 
-        Ok(RenderPageResult {
+        Ok(RenderPageRegionResult {
+            image: base64::engine::general_purpose::STANDARD.encode(&png_buffer),
+            width: pixmap.width(),
+            height: pixmap.height(),
+            format: "png".to_string(),
+        })
+    })
+}
+
+// ============== Get Page Color Info ==============
+
+/// Scale used for the low-resolution color analysis renders.
+const COLOR_INFO_SCALE: f32 = 0.1;
+
+/// Gray level at or above which a pixel counts as paper rather than ink.
+const NEAR_WHITE: u8 = 240;
+
+/// Maximum channel deviation from the gray value for a pixel to count as
+/// neutral (anti-aliasing produces small deviations).
+const NEUTRAL_TOLERANCE: i32 = 24;
+
+/// Fraction of chromatic pixels above which a page counts as color.
+const COLOR_PIXEL_FRACTION: f32 = 0.01;
+
+/// Parameters for classifying the colors on a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageColorInfoParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Result of classifying the colors on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageColorInfoResult {
+    /// Whether the page contains noticeable color (otherwise grayscale).
+    pub is_color: bool,
+    /// Whether the page draws any raster images.
+    pub has_images: bool,
+    /// Fraction of the page (0-1) covered by pixels that are not near-white.
+    pub estimated_ink_coverage: f32,
+}
+
+/// Classify a page as color or grayscale from low-resolution renders.
+pub fn get_page_color_info(
+    store: &DocumentStore,
+    params: GetPageColorInfoParams,
+) -> Result<GetPageColorInfoResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        let matrix = Matrix::new_scale(COLOR_INFO_SCALE, COLOR_INFO_SCALE);
+        let rgb = page.to_pixmap(&matrix, &Colorspace::device_rgb(), false, true)?;
+        let gray = page.to_pixmap(&matrix, &Colorspace::device_gray(), false, true)?;
+
+        let rgb_pixels = rgb.samples().chunks_exact(rgb.n() as usize);
+        let gray_pixels = gray.samples().chunks_exact(gray.n() as usize);
+        let mut total = 0usize;
+        let mut chromatic = 0usize;
+        let mut inked = 0usize;
+        for (color, level) in rgb_pixels.zip(gray_pixels) {
+            let level = level[0];
+            total += 1;
+            if level < NEAR_WHITE {
+                inked += 1;
+            }
+            // A neutral pixel has all channels close to its gray value
+            let deviation = color[..3]
+                .iter()
+                .map(|&c| (c as i32 - level as i32).abs())
+                .max()
+                .unwrap_or(0);
+            if deviation > NEUTRAL_TOLERANCE {
+                chromatic += 1;
+            }
+        }
+
+        let fraction = |count: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f32 / total as f32
+            }
+        };
+
+        Ok(GetPageColorInfoResult {
+            is_color: fraction(chromatic) > COLOR_PIXEL_FRACTION,
+            has_images: !collect_page_images(&page, false)?.is_empty(),
+            estimated_ink_coverage: fraction(inked),
+        })
+    })
+}
+
+// ============== Get Page Ink Coverage ==============
+
+/// Default scale of the ink coverage render (about 3.6 DPI).
+const DEFAULT_INK_SAMPLE_SCALE: f32 = 0.05;
+
+/// Largest accepted ink coverage sample scale (72 DPI).
+const MAX_INK_SAMPLE_SCALE: f32 = 1.0;
+
+/// Parameters for estimating the ink coverage of a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageInkCoverageParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Render scale of the sample, greater than 0 and at most 1 (default 0.05).
+    #[serde(default)]
+    pub sample_scale: Option<f32>,
+}
+
+/// Estimated ink coverage of a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageInkCoverageResult {
+    /// Percentage (0-100) of the page covered by pixels that are not near-white.
+    pub coverage_percent: f32,
+    /// Whether no pixel of the sample is inked.
+    pub is_blank: bool,
+    /// Width of the sample render in pixels.
+    pub render_width: u32,
+    /// Height of the sample render in pixels.
+    pub render_height: u32,
+}
+
+/// Estimate the ink coverage of a page from a low-resolution gray render.
+pub fn get_page_ink_coverage(
+    store: &DocumentStore,
+    params: GetPageInkCoverageParams,
+) -> Result<GetPageInkCoverageResult> {
+    let scale = params.sample_scale.unwrap_or(DEFAULT_INK_SAMPLE_SCALE);
+    if scale.is_nan() || scale <= 0.0 || scale > MAX_INK_SAMPLE_SCALE {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "sample_scale must be greater than 0 and at most {}, got {}",
+            MAX_INK_SAMPLE_SCALE, scale
+        )));
+    }
+
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+
+        let matrix = Matrix::new_scale(scale, scale);
+        let gray = page.to_pixmap(&matrix, &Colorspace::device_gray(), false, true)?;
+        let pixels = gray.samples().chunks_exact(gray.n() as usize);
+        let total = pixels.len();
+        let inked = pixels.filter(|level| level[0] < NEAR_WHITE).count();
+
+        Ok(GetPageInkCoverageResult {
+            coverage_percent: if total == 0 {
+                0.0
+            } else {
+                inked as f32 / total as f32 * 100.0
+            },
+            is_blank: inked == 0,
+            render_width: gray.width(),
+            render_height: gray.height(),
+        })
+    })
+}
+
+// ============== Get Page Color Space Usage ==============
+
+/// Maximum nesting of base color spaces (Indexed, Pattern) followed.
+const MAX_COLORSPACE_DEPTH: usize = 4;
+
+/// DeviceN colorant names that are process colors or special values
+/// rather than spot colors.
+const NON_SPOT_COLORANTS: [&str; 6] = ["Cyan", "Magenta", "Yellow", "Black", "All", "None"];
+
+/// Parameters for listing the color spaces used by a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageColorSpaceParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Result of listing the color spaces used by a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageColorSpaceResult {
+    /// Color space families used (e.g. "DeviceRGB", "ICCBased", "Separation"), sorted.
+    pub colorspaces: Vec<String>,
+    /// Whether the page uses Separation or DeviceN spot colors.
+    pub has_spot_colors: bool,
+    /// Names of the spot colorants, sorted.
+    pub spot_color_names: Vec<String>,
+    /// Whether the page uses an ICC-based color space.
+    pub has_icc: bool,
+}
+
+/// Color spaces collected from a page's resources.
+#[derive(Default)]
+struct ColorSpaceUsage {
+    colorspaces: BTreeSet<String>,
+    spot_color_names: BTreeSet<String>,
+}
+
+impl ColorSpaceUsage {
+    /// Record a color space given as a name or a `[/Family ...]` array.
+    fn add(&mut self, colorspace: &PdfObject, depth: usize) -> Result<()> {
+        if depth > MAX_COLORSPACE_DEPTH {
+            return Ok(());
+        }
+        if colorspace.is_name()? {
+            self.colorspaces
+                .insert(String::from_utf8_lossy(colorspace.as_name()?).into_owned());
+            return Ok(());
+        }
+        if !colorspace.is_array()? {
+            return Ok(());
+        }
+        let Some(family) = colorspace.get_array(0)? else {
+            return Ok(());
+        };
+        let family = String::from_utf8_lossy(family.as_name()?).into_owned();
+
+        match family.as_str() {
+            "Separation" => {
+                if let Some(name) = colorspace.get_array(1)? {
+                    self.add_colorant(&name)?;
+                }
+            }
+            "DeviceN" => {
+                if let Some(names) = colorspace.get_array(1)? {
+                    for i in 0..names.len()? as i32 {
+                        if let Some(name) = names.get_array(i)? {
+                            self.add_colorant(&name)?;
+                        }
+                    }
+                }
+            }
+            // The base space is what the color values end up in
+            "Indexed" | "Pattern" => {
+                if let Some(base) = colorspace.get_array(1)? {
+                    self.add(&base, depth + 1)?;
+                }
+            }
+            _ => {}
+        }
+        self.colorspaces.insert(family);
+        Ok(())
+    }
+
+    /// Record a Separation or DeviceN colorant if it is a spot color.
+    fn add_colorant(&mut self, name: &PdfObject) -> Result<()> {
+        if !name.is_name()? {
+            return Ok(());
+        }
+        let name = String::from_utf8_lossy(name.as_name()?).into_owned();
+        if !NON_SPOT_COLORANTS.contains(&name.as_str()) {
+            self.spot_color_names.insert(name);
+        }
+        Ok(())
+    }
+}
+
+/// List the color spaces a page declares.
+///
+/// Reads the page's `/Resources /ColorSpace` dictionary, the color spaces
+/// of its image XObjects and its transparency group. Device colors set
+/// directly by content operators without a resource are not listed.
+pub fn get_page_color_space_usage(
+    store: &DocumentStore,
+    params: GetPageColorSpaceParams,
+) -> Result<GetPageColorSpaceResult> {
+    store.with_pdf_document(&params.document_id, |pdf| {
+        let page_number = validate_page_number(pdf, params.page)?;
+        let page = pdf.find_page(page_number)?;
+        let mut usage = ColorSpaceUsage::default();
+
+        if let Some(group) = page.get_dict("Group")? {
+            if let Some(colorspace) = group.get_dict("CS")? {
+                usage.add(&colorspace, 0)?;
+            }
+        }
+        if let Some(resources) = inherited_entry(page, "Resources")? {
+            if let Some(colorspaces) = resources.get_dict("ColorSpace")? {
+                for i in 0..colorspaces.dict_len()? as i32 {
+                    if let Some(colorspace) = colorspaces.get_dict_val(i)? {
+                        usage.add(&colorspace, 0)?;
+                    }
+                }
+            }
+            if let Some(xobjects) = resources.get_dict("XObject")? {
+                for i in 0..xobjects.dict_len()? as i32 {
+                    let Some(xobject) = xobjects.get_dict_val(i)? else {
+                        continue;
+                    };
+                    if dict_name(&xobject, "Subtype")?.as_deref() != Some("Image") {
+                        continue;
+                    }
+                    if let Some(colorspace) = xobject.get_dict("ColorSpace")? {
+                        usage.add(&colorspace, 0)?;
+                    }
+                }
+            }
+        }
+
+        Ok(GetPageColorSpaceResult {
+            has_icc: usage.colorspaces.contains("ICCBased"),
+            has_spot_colors: !usage.spot_color_names.is_empty(),
+            colorspaces: usage.colorspaces.into_iter().collect(),
+            spot_color_names: usage.spot_color_names.into_iter().collect(),
+        })
+    })
+}
+
+// ============== Render Pages ==============
+
+/// Maximum number of pages rendered by a single `render_pages` call.
+pub(crate) const MAX_RENDER_PAGES: usize = 50;
+
+/// Parameters for rendering several pages in one call.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenderPagesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page numbers to render (0-indexed, at most 50; negative counts from the end, -1 = last page).
+    pub pages: Vec<i32>,
+    /// Scale factor (default 1.0 = 72 DPI). Mutually exclusive with `dpi`.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Resolution in dots per inch. Mutually exclusive with `scale`.
+    #[serde(default)]
+    pub dpi: Option<f32>,
+    /// Output format: "png" or "svg" (default "png").
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// A rendered page in a batch render.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RenderPageEntry {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Rendered page.
+    #[serde(flatten)]
+    pub render: RenderPageResult,
+}
+
+/// Result of rendering several pages.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RenderPagesResult {
+    /// Rendered pages, in request order.
+    pub images: Vec<RenderPageEntry>,
+}
+
+/// Render a page at `scale` for a batch render, in RGB without alpha.
+fn render_page_entry(
+    doc: &mupdf::Document,
+    page_number: i32,
+    scale: f32,
+    format: &str,
+    max_pixels: u64,
+) -> Result<RenderPageEntry> {
+    let page = doc.load_page(page_number)?;
+    let (image, svg, width, height) = render_page_inner(
+        &page,
+        scale,
+        0,
+        format,
+        RenderColorspace::Rgb,
+        false,
+        max_pixels,
+    )?;
+    Ok(RenderPageEntry {
+        page: page_number,
+        render: RenderPageResult {
             image,
+            svg,
             width,
             height,
-            format: "png".to_string(),
-        })
+            format: format.to_string(),
+            effective_dpi: scale * BASE_DPI,
+            colorspace: RenderColorspace::Rgb.name().to_string(),
+            has_alpha: false,
+        },
+    })
+}
+
+/// Render several pages to PNG images or SVG documents.
+pub fn render_pages(store: &DocumentStore, params: RenderPagesParams) -> Result<RenderPagesResult> {
+    if params.pages.len() > MAX_RENDER_PAGES {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "at most {} pages can be rendered per call, got {}",
+            MAX_RENDER_PAGES,
+            params.pages.len()
+        )));
+    }
+    let scale = resolve_scale(params.scale, params.dpi, store.render_scale_limit())?;
+    let format = params.format.unwrap_or_else(default_image_format);
+
+    store.with_document(&params.document_id, |doc| {
+        let pages = params
+            .pages
+            .iter()
+            .map(|&page| validate_page_number(doc, page))
+            .collect::<Result<Vec<_>>>()?;
+
+        let images = pages
+            .into_iter()
+            .map(|page_number| {
+                render_page_entry(doc, page_number, scale, &format, store.render_pixel_limit())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RenderPagesResult { images })
+    })
+}
+
+// ============== Render All Pages ==============
+
+/// Parameters for rendering thumbnails of every page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenderAllPagesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Scale factor (default 1.0 = 72 DPI). Mutually exclusive with `dpi`.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Resolution in dots per inch. Mutually exclusive with `scale`.
+    #[serde(default)]
+    pub dpi: Option<f32>,
+    /// Render at most this many pages, starting from the first (default and
+    /// maximum 50).
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+}
+
+/// Result of rendering every page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RenderAllPagesResult {
+    /// Rendered pages, in page order.
+    pub images: Vec<RenderPageEntry>,
+    /// Total number of pages in the document; more than `images.len()` if
+    /// rendering stopped at `max_pages`.
+    pub page_count: i32,
+}
+
+/// Render every page (up to `max_pages`) to PNG images.
+pub fn render_all_pages(
+    store: &DocumentStore,
+    params: RenderAllPagesParams,
+) -> Result<RenderAllPagesResult> {
+    let max_pages = params.max_pages.unwrap_or(MAX_RENDER_PAGES);
+    if max_pages > MAX_RENDER_PAGES {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "max_pages must be at most {}, got {}",
+            MAX_RENDER_PAGES, max_pages
+        )));
+    }
+    let scale = resolve_scale(params.scale, params.dpi, store.render_scale_limit())?;
+    let format = default_image_format();
+
+    store.with_document(&params.document_id, |doc| {
+        let page_count = doc.page_count()?;
+        let images = (0..page_count)
+            .take(max_pages)
+            .map(|page_number| {
+                render_page_entry(doc, page_number, scale, &format, store.render_pixel_limit())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RenderAllPagesResult { images, page_count })
     })
 }
@@ -0,0 +1,96 @@
+//! Helpers for reading raw PDF objects (dictionaries, name and number trees).
+
+use mupdf::pdf::PdfObject;
+
+use crate::error::Result;
+
+/// Maximum nesting depth followed in name and number trees.
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Collect the key/value pairs of a name or number tree node and its kids.
+///
+/// `leaf_key` is `"Names"` for name trees and `"Nums"` for number trees.
+fn collect_tree(
+    node: &PdfObject,
+    leaf_key: &str,
+    depth: usize,
+    out: &mut Vec<(PdfObject, PdfObject)>,
+) -> Result<()> {
+    if depth > MAX_TREE_DEPTH {
+        return Ok(());
+    }
+    if let Some(pairs) = node.get_dict(leaf_key)? {
+        let len = pairs.len()? as i32;
+        for i in (0..len - 1).step_by(2) {
+            if let (Some(key), Some(value)) = (pairs.get_array(i)?, pairs.get_array(i + 1)?) {
+                out.push((key, value));
+            }
+        }
+    }
+    if let Some(kids) = node.get_dict("Kids")? {
+        for i in 0..kids.len()? as i32 {
+            if let Some(kid) = kids.get_array(i)? {
+                collect_tree(&kid, leaf_key, depth + 1, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Flatten a number tree into `(key, value)` pairs in tree order.
+pub(crate) fn number_tree_entries(root: &PdfObject) -> Result<Vec<(i32, PdfObject)>> {
+    let mut entries = Vec::new();
+    collect_tree(root, "Nums", 0, &mut entries)?;
+    entries
+        .into_iter()
+        .map(|(key, value)| Ok((key.as_int()?, value)))
+        .collect()
+}
+
+/// Flatten a name tree into `(key, value)` pairs in tree order.
+pub(crate) fn name_tree_entries(root: &PdfObject) -> Result<Vec<(String, PdfObject)>> {
+    let mut entries = Vec::new();
+    collect_tree(root, "Names", 0, &mut entries)?;
+    entries
+        .into_iter()
+        .map(|(key, value)| Ok((key.as_string()?.to_string(), value)))
+        .collect()
+}
+
+/// Read a text string entry of a dictionary, treating empty strings as absent.
+pub(crate) fn dict_string(dict: &PdfObject, key: &str) -> Result<Option<String>> {
+    match dict.get_dict(key)? {
+        Some(value) => Ok(Some(value.as_string()?.to_string()).filter(|s| !s.is_empty())),
+        None => Ok(None),
+    }
+}
+
+/// Read a name entry of a dictionary (without the leading slash).
+pub(crate) fn dict_name(dict: &PdfObject, key: &str) -> Result<Option<String>> {
+    match dict.get_dict(key)? {
+        Some(value) => Ok(Some(String::from_utf8_lossy(value.as_name()?).into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Read an integer entry of a dictionary.
+pub(crate) fn dict_int(dict: &PdfObject, key: &str) -> Result<Option<i32>> {
+    match dict.get_dict(key)? {
+        Some(value) => Ok(Some(value.as_int()?)),
+        None => Ok(None),
+    }
+}
+
+/// Look up a page attribute that may be inherited from the page tree
+/// (`/Resources`, `/MediaBox`, `/CropBox`, `/Rotate`).
+pub(crate) fn inherited_entry(page: PdfObject, key: &str) -> Result<Option<PdfObject>> {
+    let mut node = Some(page);
+    for _ in 0..MAX_TREE_DEPTH {
+        let Some(current) = node else { break };
+        if let Some(value) = current.get_dict(key)? {
+            return Ok(Some(value));
+        }
+        node = current.get_dict("Parent")?;
+    }
+    Ok(None)
+}
@@ -0,0 +1,228 @@
+//! Document-wide search tools.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MupdfServerError, Result};
+use crate::state::DocumentStore;
+use crate::tools::page::{
+    default_true, search_hits, validate_page_number, SearchHit, SearchOptions, DEFAULT_MAX_HITS,
+};
+use crate::tools::text::extract_text_from_page;
+
+/// Default cap on the number of regex matches returned.
+const DEFAULT_MAX_REGEX_MATCHES: usize = 1000;
+
+/// Resolve an optional inclusive page range to concrete bounds.
+///
+/// `None` means every page of the document.
+pub(crate) fn resolve_page_range(
+    doc: &mupdf::Document,
+    page_range: Option<(i32, i32)>,
+) -> Result<(i32, i32)> {
+    match page_range {
+        Some((start, end)) => {
+            let start = validate_page_number(doc, start)?;
+            let end = validate_page_number(doc, end)?;
+            if start > end {
+                return Err(MupdfServerError::InvalidParams(format!(
+                    "page_range start ({}) is after end ({})",
+                    start, end
+                )));
+            }
+            Ok((start, end))
+        }
+        None => Ok((0, doc.page_count()? - 1)),
+    }
+}
+
+// ============== Search Document ==============
+
+/// Parameters for searching an entire document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchDocumentParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Text to search for.
+    pub query: String,
+    /// Maximum hits per page (default 100).
+    #[serde(default)]
+    pub max_hits_per_page: Option<usize>,
+    /// Inclusive `[start, end]` page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub page_range: Option<(i32, i32)>,
+    /// Match letter case exactly (default true).
+    #[serde(default = "default_true")]
+    pub case_sensitive: bool,
+    /// Only match whole words (default false).
+    #[serde(default)]
+    pub whole_word: bool,
+}
+
+/// A search hit with the page it was found on.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DocumentSearchHit {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Bounding quad of the hit.
+    #[serde(flatten)]
+    pub hit: SearchHit,
+}
+
+/// Result of searching a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchDocumentResult {
+    /// Hits in page order.
+    pub hits: Vec<DocumentSearchHit>,
+    /// Total number of hits.
+    pub total_hits: usize,
+}
+
+/// Search every page of a document (or a page range) for text.
+pub(crate) fn search_document_pages(
+    doc: &mupdf::Document,
+    query: &str,
+    max_hits_per_page: Option<usize>,
+    page_range: Option<(i32, i32)>,
+    options: SearchOptions,
+) -> Result<Vec<DocumentSearchHit>> {
+    let (start, end) = resolve_page_range(doc, page_range)?;
+    let max_hits = max_hits_per_page
+        .map(|n| n.min(u32::MAX as usize) as u32)
+        .unwrap_or(DEFAULT_MAX_HITS);
+
+    let mut hits = Vec::new();
+    for page_number in start..=end {
+        let page = doc.load_page(page_number)?;
+        hits.extend(
+            search_hits(&page, query, max_hits, options)?
+                .into_iter()
+                .map(|hit| DocumentSearchHit {
+                    page: page_number,
+                    hit,
+                }),
+        );
+    }
+
+    Ok(hits)
+}
+
+/// Search for text across all pages of a document.
+pub fn search_document(
+    store: &DocumentStore,
+    params: SearchDocumentParams,
+) -> Result<SearchDocumentResult> {
+    store.with_document(&params.document_id, |doc| {
+        let hits = search_document_pages(
+            doc,
+            &params.query,
+            params.max_hits_per_page,
+            params.page_range,
+            SearchOptions {
+                case_sensitive: params.case_sensitive,
+                whole_word: params.whole_word,
+            },
+        )?;
+
+        Ok(SearchDocumentResult {
+            total_hits: hits.len(),
+            hits,
+        })
+    })
+}
+
+// ============== Search Document Regex ==============
+
+/// Parameters for searching a document with a regular expression.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchDocumentRegexParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Regular expression (Rust `regex` syntax, similar to PCRE without
+    /// look-around or backreferences).
+    pub pattern: String,
+    /// Inclusive `[start, end]` page range (0-indexed; negative counts from the end, -1 = last page). Defaults to all pages.
+    #[serde(default)]
+    pub page_range: Option<(i32, i32)>,
+    /// Maximum number of matches to return (default 1000).
+    #[serde(default)]
+    pub max_matches: Option<usize>,
+}
+
+/// A regular expression match in a page's plain text.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RegexMatch {
+    /// Page number (0-indexed).
+    pub page: i32,
+    /// Matched text.
+    pub text: String,
+    /// Character offset of the match start in the page's plain text.
+    pub start_char: usize,
+    /// Character offset just past the match end in the page's plain text.
+    pub end_char: usize,
+    /// Capture groups in order; `None` for groups that did not participate.
+    pub groups: Vec<Option<String>>,
+}
+
+/// Result of a regular expression search.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RegexSearchResult {
+    /// Matches in page order.
+    pub matches: Vec<RegexMatch>,
+    /// Whether matching stopped at `max_matches`.
+    pub truncated: bool,
+}
+
+/// Search the plain text of every page (or a page range) with a regular
+/// expression.
+///
+/// Lines are separated by `\n` and blocks by a blank line, as in
+/// `get_page_text` with the `plain` format; offsets count characters, not
+/// bytes.
+pub fn search_document_regex(
+    store: &DocumentStore,
+    params: SearchDocumentRegexParams,
+) -> Result<RegexSearchResult> {
+    let regex =
+        Regex::new(&params.pattern).map_err(|e| MupdfServerError::InvalidRegex(e.to_string()))?;
+    let max_matches = params.max_matches.unwrap_or(DEFAULT_MAX_REGEX_MATCHES);
+
+    store.with_document(&params.document_id, |doc| {
+        let (start, end) = resolve_page_range(doc, params.page_range)?;
+
+        let mut matches = Vec::new();
+        for page_number in start..=end {
+            let page = doc.load_page(page_number)?;
+            let text = extract_text_from_page(&page, "plain")?;
+
+            for captures in regex.captures_iter(&text) {
+                if matches.len() == max_matches {
+                    return Ok(RegexSearchResult {
+                        matches,
+                        truncated: true,
+                    });
+                }
+
+                let whole = captures.get(0).expect("group 0 always participates");
+                let start_char = text[..whole.start()].chars().count();
+                matches.push(RegexMatch {
+                    page: page_number,
+                    text: whole.as_str().to_string(),
+                    start_char,
+                    end_char: start_char + whole.as_str().chars().count(),
+                    groups: captures
+                        .iter()
+                        .skip(1)
+                        .map(|group| group.map(|m| m.as_str().to_string()))
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(RegexSearchResult {
+            matches,
+            truncated: false,
+        })
+    })
+}
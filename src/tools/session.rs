@@ -1,14 +1,51 @@
 //! Session management tools: upload, close, list documents.
 
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
 use base64::Engine;
+use mupdf::pdf::PdfDocument;
 use mupdf::Document;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{MupdfServerError, Result};
 use crate::state::DocumentStore;
+use crate::tools::manipulation::{split_page_range, write_pdf, write_pdf_decrypted};
+
+/// Timeout for downloading a document from a URL.
+const DEFAULT_HTTP_TIMEOUT_SECONDS: u64 = 30;
+
+/// Largest document accepted from a URL, in bytes (256 MiB).
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Maximum number of redirects followed when downloading a document.
+const MAX_DOWNLOAD_REDIRECTS: usize = 5;
 
-/// Source for a document: either a file path or base64 content.
+/// Content types passed on to MuPDF as the document format. Anything else
+/// (e.g. `application/octet-stream`) is sniffed from the content instead.
+const DOCUMENT_CONTENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/epub+zip",
+    "application/oxps",
+    "application/vnd.ms-xpsdocument",
+    "application/vnd.comicbook+zip",
+    "application/x-cbz",
+    "application/x-fictionbook+xml",
+    "image/png",
+    "image/jpeg",
+    "image/tiff",
+    "image/gif",
+    "image/bmp",
+];
+
+/// Maximum number of files combined by a single `FilePaths` source.
+const MAX_SOURCE_FILES: usize = 1000;
+
+/// Source for a document: a file path, several file paths combined into
+/// one document, base64 content or an HTTP(S) URL.
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum DocumentSource {
@@ -17,6 +54,12 @@ pub enum DocumentSource {
         /// Path to the PDF file.
         path: String,
     },
+    /// Combine the pages of several files, in order, into one PDF.
+    FilePaths {
+        /// Paths to the files (at most 1000), e.g. the pages of a
+        /// multi-file TIFF.
+        paths: Vec<String>,
+    },
     /// Load document from base64-encoded content.
     Base64 {
         /// Base64-encoded document content.
@@ -25,34 +68,319 @@ pub enum DocumentSource {
         #[serde(default)]
         filename: Option<String>,
     },
+    /// Download document from an HTTP(S) URL.
+    Url {
+        /// URL of the document.
+        url: String,
+        /// Optional filename hint (for format detection).
+        #[serde(default)]
+        filename: Option<String>,
+        /// Download timeout in seconds (default 30).
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+    },
+}
+
+/// Download a document, returning its bytes and content type.
+///
+/// Bodies larger than [`MAX_DOWNLOAD_BYTES`] are rejected.
+fn download(url: &str, timeout: Duration) -> Result<(Vec<u8>, Option<String>)> {
+    let fetch = || -> Result<(Vec<u8>, Option<String>)> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::limited(MAX_DOWNLOAD_REDIRECTS))
+            .build()?;
+        let response = client.get(url).send()?.error_for_status()?;
+        let too_large = || {
+            MupdfServerError::PayloadTooLarge(format!(
+                "download exceeds the limit of {} bytes",
+                MAX_DOWNLOAD_BYTES
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_DOWNLOAD_BYTES)
+        {
+            return Err(too_large());
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_ascii_lowercase());
+
+        // The length header may be missing or wrong, so cap the read too
+        let mut bytes = Vec::new();
+        response
+            .take(MAX_DOWNLOAD_BYTES + 1)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+            return Err(too_large());
+        }
+        Ok((bytes, content_type))
+    };
+
+    // The blocking client must not run on an async runtime thread, and
+    // block_in_place panics on a current-thread runtime, so fetch on a
+    // separate thread
+    std::thread::scope(|scope| {
+        scope
+            .spawn(fetch)
+            .join()
+            .map_err(|_| MupdfServerError::internal("Download thread panicked"))?
+    })
+}
+
+/// Detect the format of a document from its leading bytes.
+///
+/// Returns the magic string passed to MuPDF; unknown content is assumed
+/// to be PDF.
+fn sniff_format(bytes: &[u8]) -> &'static str {
+    // ZIP local file header
+    if bytes.starts_with(b"PK\x03\x04") {
+        // EPUB stores an uncompressed `mimetype` entry first, whose data
+        // follows the 30-byte local header and 8-byte file name
+        if bytes.get(30..58) == Some(b"mimetypeapplication/epub+zip".as_slice()) {
+            return "application/epub+zip";
+        }
+        let head = &bytes[..bytes.len().min(4096)];
+        if head
+            .windows(11)
+            .any(|w| w == b"FixedDocSeq" || w == b"FixedDocume")
+        {
+            return "application/oxps";
+        }
+        return "application/vnd.comicbook+zip";
+    }
+    if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        return "image/tiff";
+    }
+    if bytes.starts_with(b"\x89PNG") {
+        return "image/png";
+    }
+    if bytes.starts_with(b"\xFF\xD8\xFF") {
+        return "image/jpeg";
+    }
+    "application/pdf"
+}
+
+/// Check that `path` resolves to a location inside one of `allowed` directories.
+///
+/// Paths that cannot be resolved are rejected the same way, so the error
+/// does not reveal whether a file exists outside the allowed directories.
+pub(crate) fn check_path_allowed(path: &str, allowed: &[PathBuf]) -> Result<()> {
+    let canonical = Path::new(path)
+        .canonicalize()
+        .map_err(|_| MupdfServerError::PathNotAllowed(path.to_string()))?;
+    if allowed.iter().any(|root| canonical.starts_with(root)) {
+        Ok(())
+    } else {
+        Err(MupdfServerError::PathNotAllowed(path.to_string()))
+    }
+}
+
+/// Resolve a path to write to, checking that it lies inside one of
+/// `allowed` directories.
+///
+/// Writing is refused when no allowed directories are configured. The
+/// parent directory must exist, and the path itself must not be a symlink
+/// (even a dangling one), so the write cannot land outside the allowed
+/// directories.
+fn resolve_output_path(path: &str, allowed: Option<&[PathBuf]>) -> Result<PathBuf> {
+    let allowed = allowed.ok_or_else(|| {
+        MupdfServerError::PathNotAllowed(format!(
+            "{} (writing files requires allowed directories to be configured)",
+            path
+        ))
+    })?;
+    let output = Path::new(path);
+    let file_name = output.file_name().ok_or_else(|| {
+        MupdfServerError::InvalidParams(format!("output_path has no file name: {}", path))
+    })?;
+    let parent = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let resolved = parent
+        .canonicalize()
+        .map_err(|_| MupdfServerError::PathNotAllowed(path.to_string()))?
+        .join(file_name);
+
+    let is_symlink = std::fs::symlink_metadata(&resolved)
+        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+    if is_symlink || !allowed.iter().any(|root| resolved.starts_with(root)) {
+        return Err(MupdfServerError::PathNotAllowed(path.to_string()));
+    }
+    Ok(resolved)
 }
 
 impl DocumentSource {
+    /// File name of the document, taken from the path, the filename hint
+    /// or the last segment of the URL.
+    pub fn filename(&self) -> Option<String> {
+        let last_component = |path: &str| {
+            Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        };
+        match self {
+            DocumentSource::FilePath { path } => last_component(path),
+            DocumentSource::FilePaths { paths } => {
+                paths.first().and_then(|path| last_component(path))
+            }
+            DocumentSource::Base64 { filename, .. } => filename.clone(),
+            DocumentSource::Url { url, filename, .. } => filename.clone().or_else(|| {
+                let path = url.split(['?', '#']).next().unwrap_or(url);
+                path.rsplit_once('/')
+                    .map(|(_, name)| name.to_string())
+                    .filter(|name| !name.is_empty())
+            }),
+        }
+    }
+
+    /// Short description of where the document came from, e.g.
+    /// `"path:/foo.pdf"` or `"base64:foo.pdf"`.
+    pub fn source_hint(&self) -> String {
+        match self {
+            DocumentSource::FilePath { path } => format!("path:{}", path),
+            DocumentSource::FilePaths { paths } => match paths.as_slice() {
+                [first, rest @ ..] if !rest.is_empty() => {
+                    format!("paths:{} (+{} more)", first, rest.len())
+                }
+                _ => format!("paths:{}", paths.join(",")),
+            },
+            DocumentSource::Base64 { filename, .. } => {
+                format!("base64:{}", filename.as_deref().unwrap_or(""))
+            }
+            DocumentSource::Url { url, .. } => format!("url:{}", url),
+        }
+    }
+
     /// Open a document from this source.
-    pub fn open(&self, password: Option<&str>) -> Result<Document> {
-        let mut doc = match self {
-            DocumentSource::FilePath { path } => Document::open(path)?,
+    ///
+    /// When `allowed_paths` is set, file path sources must resolve to a
+    /// location inside one of those directories. URL sources are refused
+    /// unless `allow_urls` is set.
+    pub fn open(
+        &self,
+        password: Option<&str>,
+        allowed_paths: Option<&[PathBuf]>,
+        allow_urls: bool,
+    ) -> Result<Document> {
+        Ok(self.open_with_size(password, allowed_paths, allow_urls)?.0)
+    }
+
+    /// Open a document from this source, also returning its size in bytes.
+    ///
+    /// The size is the raw document length (decoded base64, downloaded
+    /// body or file size) and serves as a memory usage estimate.
+    pub fn open_with_size(
+        &self,
+        password: Option<&str>,
+        allowed_paths: Option<&[PathBuf]>,
+        allow_urls: bool,
+    ) -> Result<(Document, usize)> {
+        let (mut doc, size) = match self {
+            DocumentSource::FilePath { path } => {
+                if let Some(allowed) = allowed_paths {
+                    check_path_allowed(path, allowed)?;
+                }
+                let size = std::fs::metadata(path)?.len() as usize;
+                (Document::open(path)?, size)
+            }
+            DocumentSource::FilePaths { paths } => combine_files(paths, password, allowed_paths)?,
             DocumentSource::Base64 { base64, filename } => {
                 let bytes = base64::engine::general_purpose::STANDARD.decode(base64)?;
-                let magic = filename.as_deref().unwrap_or("application/pdf");
-                Document::from_bytes(&bytes, magic)?
+                let magic = filename.as_deref().unwrap_or_else(|| sniff_format(&bytes));
+                (Document::from_bytes(&bytes, magic)?, bytes.len())
             }
+            DocumentSource::Url {
+                url,
+                filename,
+                timeout_seconds,
+            } => {
+                if !allow_urls {
+                    return Err(MupdfServerError::UrlNotAllowed(url.clone()));
+                }
+                let timeout =
+                    Duration::from_secs(timeout_seconds.unwrap_or(DEFAULT_HTTP_TIMEOUT_SECONDS));
+                let (bytes, content_type) = download(url, timeout)?;
+                let content_type = content_type
+                    .as_deref()
+                    .filter(|content_type| DOCUMENT_CONTENT_TYPES.contains(content_type));
+                let magic = filename
+                    .as_deref()
+                    .or(content_type)
+                    .unwrap_or_else(|| sniff_format(&bytes));
+                (Document::from_bytes(&bytes, magic)?, bytes.len())
+            }
+        };
+
+        unlock(&mut doc, password)?;
+        Ok((doc, size))
+    }
+}
+
+/// Open several files and copy their pages, in order, into a new PDF.
+///
+/// Non-PDF files (images, XPS, ...) are converted to PDF first. `password`
+/// is tried on every encrypted file. Returns the combined document and the
+/// total size of the input files.
+fn combine_files(
+    paths: &[String],
+    password: Option<&str>,
+    allowed_paths: Option<&[PathBuf]>,
+) -> Result<(Document, usize)> {
+    if paths.is_empty() {
+        return Err(MupdfServerError::InvalidParams(
+            "paths must not be empty".to_string(),
+        ));
+    }
+    if paths.len() > MAX_SOURCE_FILES {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "at most {} files can be combined, got {}",
+            MAX_SOURCE_FILES,
+            paths.len()
+        )));
+    }
+
+    let mut output = PdfDocument::new();
+    let mut size = 0;
+    for path in paths {
+        if let Some(allowed) = allowed_paths {
+            check_path_allowed(path, allowed)?;
+        }
+        size += std::fs::metadata(path)?.len() as usize;
+
+        let mut doc = Document::open(path)?;
+        unlock(&mut doc, password)?;
+        let pdf = if doc.is_pdf() {
+            PdfDocument::try_from(doc)?
+        } else {
+            doc.convert_to_pdf(0, -1, 0)?
         };
+        for page_number in 0..pdf.page_count()? {
+            output.graft_page(-1, &pdf, page_number)?;
+        }
+    }
+
+    let bytes = write_pdf(&output)?;
+    Ok((Document::from_bytes(&bytes, "application/pdf")?, size))
+}
 
-        // Handle password protection
-        if doc.needs_password()? {
-            match password {
-                Some(pw) => {
-                    if !doc.authenticate(pw)? {
-                        return Err(MupdfServerError::InvalidPassword);
-                    }
+/// Authenticate a password-protected document.
+fn unlock(doc: &mut Document, password: Option<&str>) -> Result<()> {
+    if doc.needs_password()? {
+        match password {
+            Some(pw) => {
+                if !doc.authenticate(pw)? {
+                    return Err(MupdfServerError::InvalidPassword);
                 }
-                None => return Err(MupdfServerError::PasswordRequired),
             }
+            None => return Err(MupdfServerError::PasswordRequired),
         }
-
-        Ok(doc)
     }
+    Ok(())
 }
 
 // ============== Import Document ==============
@@ -60,7 +388,7 @@ impl DocumentSource {
 /// Parameters for importing a document.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ImportDocumentParams {
-    /// Document source (file path or base64 content).
+    /// Document source (file path, file paths, base64 content or URL).
     pub source: DocumentSource,
     /// Password for encrypted documents (optional).
     #[serde(default)]
@@ -74,20 +402,163 @@ pub struct ImportDocumentResult {
     pub document_id: String,
     /// Number of pages in the document.
     pub page_count: i32,
+    /// ID of a document closed to make room, if the store was full.
+    pub evicted_document_id: Option<String>,
 }
 
 /// Import a document to the server.
+///
+/// Importing the same unencrypted file path again returns the existing
+/// document, so callers share it: closing it closes it for every caller.
+/// Once a document is edited in place (e.g. `delete_pages`,
+/// `rotate_pages`), later imports of its path open the file afresh.
 pub fn import_document(
     store: &DocumentStore,
     params: ImportDocumentParams,
 ) -> Result<ImportDocumentResult> {
-    let doc = params.source.open(params.password.as_deref())?;
-    let page_count = doc.page_count()?;
-    let document_id = store.insert(doc)?;
+    // File paths are imported at most once; repeated imports share a document
+    let inserted = match &params.source {
+        DocumentSource::FilePath { path } => {
+            store.get_or_import_path(path, params.password.as_deref())?
+        }
+        source => {
+            let (doc, size) = source.open_with_size(
+                params.password.as_deref(),
+                store.allowed_path_roots(),
+                store.url_sources_allowed(),
+            )?;
+            store.insert_with_source(doc, size, source.filename(), source.source_hint())?
+        }
+    };
+    let page_count = store.get_info(&inserted.document_id)?.page_count;
+
+    Ok(ImportDocumentResult {
+        document_id: inserted.document_id,
+        page_count,
+        evicted_document_id: inserted.evicted,
+    })
+}
+
+// ============== Chunked Upload ==============
+
+/// Parameters for uploading one chunk of a large document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UploadChunkParams {
+    /// Caller-chosen ID grouping the chunks of one upload.
+    pub stream_id: String,
+    /// Position of this chunk, starting at 0. Chunk 0 starts a new upload.
+    pub chunk_index: u32,
+    /// Base64-encoded chunk data. Each chunk is decoded on its own, so its
+    /// raw size must be a multiple of 3 bytes except for the last chunk.
+    pub data: String,
+}
+
+/// Result of uploading a chunk.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UploadChunkResult {
+    /// Number of chunks received so far.
+    pub chunks_received: u32,
+    /// Number of decoded bytes received so far.
+    pub bytes_received: usize,
+}
+
+/// Append a base64 chunk to a pending upload.
+pub fn upload_chunk(store: &DocumentStore, params: UploadChunkParams) -> Result<UploadChunkResult> {
+    let data = base64::engine::general_purpose::STANDARD.decode(&params.data)?;
+    let (chunks_received, bytes_received) =
+        store.append_upload_chunk(&params.stream_id, params.chunk_index, &data)?;
+
+    Ok(UploadChunkResult {
+        chunks_received,
+        bytes_received,
+    })
+}
+
+/// Parameters for importing a completed chunked upload.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FinalizeStreamParams {
+    /// ID of the upload passed to `upload_chunk`.
+    pub stream_id: String,
+    /// Original filename, used to detect the format (e.g. "report.pdf").
+    #[serde(default)]
+    pub filename: Option<String>,
+    /// Password for encrypted documents (optional).
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Import the bytes of a chunked upload as a document.
+///
+/// The upload is removed from the store whether or not the import succeeds.
+pub fn finalize_stream(
+    store: &DocumentStore,
+    params: FinalizeStreamParams,
+) -> Result<ImportDocumentResult> {
+    let bytes = store.take_upload(&params.stream_id)?;
+    let magic = params
+        .filename
+        .as_deref()
+        .unwrap_or_else(|| sniff_format(&bytes));
+    let mut doc = Document::from_bytes(&bytes, magic)?;
+    unlock(&mut doc, params.password.as_deref())?;
+
+    let inserted = store.insert_with_source(
+        doc,
+        bytes.len(),
+        params.filename,
+        format!("upload:{}", params.stream_id),
+    )?;
+    let page_count = store.get_info(&inserted.document_id)?.page_count;
+
+    Ok(ImportDocumentResult {
+        document_id: inserted.document_id,
+        page_count,
+        evicted_document_id: inserted.evicted,
+    })
+}
+
+// ============== Import Document Subrange ==============
+
+/// Parameters for importing a page range of an open document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImportFromSubrangeParams {
+    /// ID of the document to take the pages from.
+    pub source_document_id: String,
+    /// First page to import (0-indexed; negative counts from the end, -1 = last page).
+    pub start_page: i32,
+    /// Last page to import, inclusive (0-indexed; negative counts from the end, -1 = last page).
+    pub end_page: i32,
+}
+
+/// Import an inclusive page range of an open PDF as a new document.
+///
+/// Like `split_document`, but the new PDF is added to the store instead of
+/// being returned as base64. It is independent of the source document.
+pub fn import_document_subrange(
+    store: &DocumentStore,
+    params: ImportFromSubrangeParams,
+) -> Result<ImportDocumentResult> {
+    let info = store.get_info(&params.source_document_id)?;
+    let bytes = store.with_pdf_document(&params.source_document_id, |pdf| {
+        write_pdf(&split_page_range(pdf, params.start_page, params.end_page)?)
+    })?;
+
+    let doc = Document::from_bytes(&bytes, "application/pdf")?;
+    let inserted = store.insert_with_source(
+        doc,
+        bytes.len(),
+        info.filename,
+        format!(
+            "subrange:{}:{}-{}",
+            params.source_document_id, params.start_page, params.end_page
+        ),
+    )?;
+    let page_count = store.get_info(&inserted.document_id)?.page_count;
 
     Ok(ImportDocumentResult {
-        document_id,
+        document_id: inserted.document_id,
         page_count,
+        evicted_document_id: inserted.evicted,
     })
 }
 
@@ -116,11 +587,169 @@ pub fn close_document(
     Ok(CloseDocumentResult { success: true })
 }
 
-// ============== List Documents ==============
+// ============== Close All Documents ==============
+
+/// Parameters for closing every open document.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CloseAllDocumentsParams {
+    /// Only close documents not accessed for more than this many seconds.
+    /// Closes every document if omitted.
+    #[serde(default)]
+    pub older_than_seconds: Option<u64>,
+}
+
+/// Result of closing documents in bulk.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CloseAllDocumentsResult {
+    /// Number of documents closed.
+    pub closed_count: usize,
+    /// IDs of the closed documents, sorted.
+    pub document_ids: Vec<String>,
+}
+
+/// Close every open document, or only those idle for longer than
+/// `older_than_seconds`.
+///
+/// Pending chunked uploads idle for as long are dropped as well.
+///
+/// Documents in use by another operation are skipped when an age limit is
+/// given.
+pub fn close_all_documents(
+    store: &DocumentStore,
+    params: CloseAllDocumentsParams,
+) -> Result<CloseAllDocumentsResult> {
+    let mut document_ids = store.purge_idle(params.older_than_seconds.map(Duration::from_secs))?;
+    document_ids.sort();
+
+    Ok(CloseAllDocumentsResult {
+        closed_count: document_ids.len(),
+        document_ids,
+    })
+}
 
-/// Parameters for listing documents (none required).
+// ============== Copy Document ==============
+
+/// Parameters for duplicating a document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CopyDocumentParams {
+    /// ID of the document to copy.
+    pub document_id: String,
+}
+
+/// Result of duplicating a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CopyDocumentResult {
+    /// ID of the new copy.
+    pub new_document_id: String,
+    /// Number of pages in the copy.
+    pub page_count: i32,
+}
+
+/// Duplicate an open PDF as a new, independent document in the store.
+///
+/// The document is serialized with its current changes and re-opened, so
+/// later edits to either document do not affect the other.
+pub fn copy_document(
+    store: &DocumentStore,
+    params: CopyDocumentParams,
+) -> Result<CopyDocumentResult> {
+    let info = store.get_info(&params.document_id)?;
+    let bytes = store.with_pdf_document(&params.document_id, write_pdf_decrypted)?;
+
+    let doc = Document::from_bytes(&bytes, "application/pdf")?;
+    let inserted = store.insert_with_source(
+        doc,
+        bytes.len(),
+        info.filename,
+        format!("copy:{}", params.document_id),
+    )?;
+    let page_count = store.get_info(&inserted.document_id)?.page_count;
+
+    Ok(CopyDocumentResult {
+        new_document_id: inserted.document_id,
+        page_count,
+    })
+}
+
+// ============== Export Document ==============
+
+/// Parameters for writing a document to the filesystem.
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct ListDocumentsParams {}
+pub struct ExportDocumentParams {
+    /// Document ID.
+    pub document_id: String,
+    /// File to write. Must be inside the allowed directories, which must be
+    /// configured.
+    pub output_path: String,
+    /// Replace an existing file (default false).
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Result of exporting a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExportDocumentResult {
+    /// Absolute path of the written file.
+    pub path: String,
+    /// Size of the written file in bytes.
+    pub size_bytes: u64,
+}
+
+/// Write an open PDF, including unsaved changes, to a file.
+///
+/// Only available when the store restricts file paths to allowed
+/// directories.
+pub fn export_document(
+    store: &DocumentStore,
+    params: ExportDocumentParams,
+) -> Result<ExportDocumentResult> {
+    let path = resolve_output_path(&params.output_path, store.allowed_path_roots())?;
+    let bytes = store.with_pdf_document(&params.document_id, write_pdf)?;
+
+    // create_new refuses existing files atomically
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if params.overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let mut file = options.open(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => {
+            MupdfServerError::FileAlreadyExists(path.display().to_string())
+        }
+        _ => e.into(),
+    })?;
+    file.write_all(&bytes)?;
+
+    Ok(ExportDocumentResult {
+        path: path.display().to_string(),
+        size_bytes: bytes.len() as u64,
+    })
+}
+
+// ============== List Documents ==============
+
+/// Parameters for listing documents.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ListDocumentsParams {
+    /// Cursor from a previous call's `next_cursor` to continue listing.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Maximum number of documents to return. Defaults to all.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Sort key: "created" (default), "last_accessed", "access_count" or
+    /// "size". Cursors are only supported with the default ordering.
+    #[serde(default)]
+    pub sort_by: Option<String>,
+    /// Sort in descending order (default false).
+    #[serde(default)]
+    pub descending: bool,
+    /// Only list documents carrying all of these tags with these values.
+    #[serde(default)]
+    pub tag_filter: Option<HashMap<String, String>>,
+}
 
 /// Information about a single document.
 #[derive(Debug, Serialize, JsonSchema)]
@@ -131,31 +760,234 @@ pub struct DocumentListEntry {
     pub page_count: i32,
     /// Seconds since the document was uploaded.
     pub age_seconds: u64,
+    /// Size of the imported document in bytes.
+    pub size_bytes: usize,
+    /// Number of times the document has been accessed since import.
+    pub access_count: u64,
+    /// Seconds since the document was last accessed.
+    pub last_accessed_seconds_ago: u64,
+    /// File name of the document, if known.
+    pub filename: Option<String>,
+    /// Where the document came from (e.g. "path:/foo.pdf", "base64:foo.pdf").
+    pub source_hint: String,
+    /// User-supplied labels.
+    pub tags: HashMap<String, String>,
 }
 
 /// Result of listing documents.
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct ListDocumentsResult {
-    /// List of open documents.
+    /// Open documents, oldest first.
     pub documents: Vec<DocumentListEntry>,
+    /// Cursor for the next page, or null if this is the last page.
+    pub next_cursor: Option<String>,
+    /// Total number of open documents matching the tag filter.
+    pub total: usize,
+}
+
+/// Encode a listing cursor from a document's ordering key.
+fn encode_cursor(created_unix_nanos: u128, id: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", created_unix_nanos, id))
+}
+
+/// Decode a listing cursor into its ordering key.
+fn decode_cursor(cursor: &str) -> Result<(u128, String)> {
+    let invalid = || MupdfServerError::InvalidParams(format!("invalid cursor: {}", cursor));
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(cursor)
+        .map_err(|_| invalid())?;
+    let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (nanos, id) = text.split_once(':').ok_or_else(invalid)?;
+    Ok((nanos.parse().map_err(|_| invalid())?, id.to_string()))
 }
 
-/// List all open documents.
+/// Sort keys accepted by `list_documents`.
+const SORT_KEYS: [&str; 4] = ["created", "last_accessed", "access_count", "size"];
+
+/// List open documents, oldest first by default, with optional cursor
+/// pagination.
 pub fn list_documents(
     store: &DocumentStore,
-    _params: ListDocumentsParams,
+    params: ListDocumentsParams,
 ) -> Result<ListDocumentsResult> {
-    let docs = store.list()?;
+    let sort_by = params.sort_by.as_deref().unwrap_or("created");
+    if !SORT_KEYS.contains(&sort_by) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "invalid sort_by: {} (valid values: {})",
+            sort_by,
+            SORT_KEYS.join(", ")
+        )));
+    }
+    let default_order = sort_by == "created" && !params.descending;
+    if !default_order && params.cursor.is_some() {
+        return Err(MupdfServerError::InvalidParams(
+            "cursor is only supported with the default ordering".to_string(),
+        ));
+    }
+
+    let mut docs = store.list()?;
+    if let Some(filter) = &params.tag_filter {
+        docs.retain(|d| filter.iter().all(|(k, v)| d.tags.get(k) == Some(v)));
+    }
+    let total = docs.len();
+    docs.sort_by(|a, b| {
+        let by_key = match sort_by {
+            "last_accessed" => a.last_accessed.cmp(&b.last_accessed),
+            "access_count" => a.access_count.cmp(&b.access_count),
+            "size" => a.size_bytes.cmp(&b.size_bytes),
+            _ => std::cmp::Ordering::Equal,
+        };
+        by_key.then_with(|| (a.created_unix_nanos, &a.id).cmp(&(b.created_unix_nanos, &b.id)))
+    });
+    if params.descending {
+        docs.reverse();
+    }
+
+    if let Some(cursor) = &params.cursor {
+        let (nanos, id) = decode_cursor(cursor)?;
+        docs.retain(|d| (d.created_unix_nanos, &d.id) > (nanos, &id));
+    }
+
+    let limit = params.limit.unwrap_or(usize::MAX);
+    let next_cursor = if docs.len() > limit {
+        docs.truncate(limit);
+        docs.last()
+            .filter(|_| default_order)
+            .map(|d| encode_cursor(d.created_unix_nanos, &d.id))
+    } else {
+        None
+    };
+
     let documents = docs
         .into_iter()
         .map(|info| DocumentListEntry {
             document_id: info.id,
             page_count: info.page_count,
             age_seconds: info.created_at.elapsed().as_secs(),
+            size_bytes: info.size_bytes,
+            access_count: info.access_count,
+            last_accessed_seconds_ago: info.last_accessed.elapsed().as_secs(),
+            filename: info.filename,
+            source_hint: info.source_hint,
+            tags: info.tags,
         })
         .collect();
 
-    Ok(ListDocumentsResult { documents })
+    Ok(ListDocumentsResult {
+        documents,
+        next_cursor,
+        total,
+    })
+}
+
+// ============== Document Tags ==============
+
+/// Parameters for tagging a document.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetDocumentTagParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Tag name.
+    pub key: String,
+    /// Tag value; replaces any previous value.
+    pub value: String,
+}
+
+/// Result of tagging a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SetDocumentTagResult {
+    /// Whether the tag was set.
+    pub success: bool,
+}
+
+/// Attach a `key`/`value` label to a document.
+pub fn set_document_tag(
+    store: &DocumentStore,
+    params: SetDocumentTagParams,
+) -> Result<SetDocumentTagResult> {
+    store.set_tag(&params.document_id, params.key, params.value)?;
+    Ok(SetDocumentTagResult { success: true })
+}
+
+/// Parameters for reading a document tag.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDocumentTagParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Tag name.
+    pub key: String,
+}
+
+/// Value of a document tag.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetDocumentTagResult {
+    /// Tag value, or null if the document has no such tag.
+    pub value: Option<String>,
+}
+
+/// Read a single tag of a document.
+pub fn get_document_tag(
+    store: &DocumentStore,
+    params: GetDocumentTagParams,
+) -> Result<GetDocumentTagResult> {
+    let mut tags = store.get_info(&params.document_id)?.tags;
+    Ok(GetDocumentTagResult {
+        value: tags.remove(&params.key),
+    })
+}
+
+/// Parameters for listing document tags.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListDocumentTagsParams {
+    /// Document ID.
+    pub document_id: String,
+}
+
+/// All tags of a document.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ListDocumentTagsResult {
+    /// Tag names and values.
+    pub tags: HashMap<String, String>,
+}
+
+/// List every tag of a document.
+pub fn list_document_tags(
+    store: &DocumentStore,
+    params: ListDocumentTagsParams,
+) -> Result<ListDocumentTagsResult> {
+    Ok(ListDocumentTagsResult {
+        tags: store.get_info(&params.document_id)?.tags,
+    })
+}
+
+// ============== Get Store Stats ==============
+
+/// Resource usage of the document store.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StoreStatsResult {
+    /// Number of open documents.
+    pub document_count: usize,
+    /// Sum of the imported documents' sizes in bytes.
+    pub total_estimated_memory_bytes: usize,
+    /// Age of the oldest open document, if any.
+    pub oldest_document_age_seconds: Option<u64>,
+    /// Highest access count among open documents, if any.
+    pub most_accessed_count: Option<u64>,
+    /// Seconds since the server started.
+    pub server_uptime_seconds: u64,
+}
+
+/// Report document store usage for a server started at `server_start`.
+pub fn get_store_stats(store: &DocumentStore, server_start: Instant) -> Result<StoreStatsResult> {
+    let docs = store.list()?;
+
+    Ok(StoreStatsResult {
+        document_count: docs.len(),
+        total_estimated_memory_bytes: docs.iter().map(|d| d.size_bytes).sum(),
+        oldest_document_age_seconds: docs.iter().map(|d| d.created_at.elapsed().as_secs()).max(),
+        most_accessed_count: docs.iter().map(|d| d.access_count).max(),
+        server_uptime_seconds: server_start.elapsed().as_secs(),
+    })
 }
 
 #[cfg(test)]
@@ -184,4 +1016,48 @@ mod tests {
             _ => panic!("Expected Base64 variant"),
         }
     }
+
+    #[test]
+    fn test_document_source_filename() {
+        let path = DocumentSource::FilePath {
+            path: "/tmp/docs/report.pdf".to_string(),
+        };
+        assert_eq!(path.filename(), Some("report.pdf".to_string()));
+        assert_eq!(path.source_hint(), "path:/tmp/docs/report.pdf");
+
+        let url = DocumentSource::Url {
+            url: "https://example.com/files/paper.pdf?download=1".to_string(),
+            filename: None,
+            timeout_seconds: None,
+        };
+        assert_eq!(url.filename(), Some("paper.pdf".to_string()));
+    }
+
+    #[test]
+    fn test_document_source_deserialize_paths() {
+        let json = r#"{"paths": ["/tmp/page1.tiff", "/tmp/page2.tiff"]}"#;
+        let source: DocumentSource = serde_json::from_str(json).unwrap();
+        match &source {
+            DocumentSource::FilePaths { paths } => assert_eq!(paths.len(), 2),
+            _ => panic!("Expected FilePaths variant"),
+        }
+        assert_eq!(source.filename(), Some("page1.tiff".to_string()));
+        assert_eq!(source.source_hint(), "paths:/tmp/page1.tiff (+1 more)");
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        // Minimal EPUB: a stored `mimetype` entry as the first ZIP member
+        let mut epub = b"PK\x03\x04".to_vec();
+        epub.extend_from_slice(&[0; 22]);
+        epub.extend_from_slice(&8u16.to_le_bytes());
+        epub.extend_from_slice(&0u16.to_le_bytes());
+        epub.extend_from_slice(b"mimetypeapplication/epub+zip");
+        assert_eq!(sniff_format(&epub), "application/epub+zip");
+
+        assert_eq!(sniff_format(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(sniff_format(b"II*\0\x08\0\0\0"), "image/tiff");
+        assert_eq!(sniff_format(b"PK\x03\x04"), "application/vnd.comicbook+zip");
+        assert_eq!(sniff_format(b""), "application/pdf");
+    }
 }
@@ -0,0 +1,252 @@
+//! Heuristic table detection.
+//!
+//! Tables are found from text layout alone: lines are split into cells at
+//! wide horizontal gaps, lines sharing a baseline form rows, and runs of
+//! consecutive multi-cell rows whose cells start at shared x-positions form
+//! a table. Ruling lines and cell borders are not considered.
+
+use mupdf::{TextPage, TextPageFlags};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::state::DocumentStore;
+use crate::tools::page::validate_page_number;
+use crate::tools::text::quad_bounds;
+
+/// Minimum horizontal gap (points) between two cells of a line.
+const CELL_GAP: f32 = 8.0;
+
+/// Maximum difference (points) between line centers of the same row.
+const ROW_TOLERANCE: f32 = 3.0;
+
+/// Maximum vertical gap (points) between consecutive rows of a table.
+const ROW_GAP_LIMIT: f32 = 30.0;
+
+/// Maximum difference (points) between left edges of cells in one column.
+const COLUMN_TOLERANCE: f32 = 6.0;
+
+/// Rows a table needs before its confidence is no longer discounted.
+const CONFIDENT_ROWS: usize = 4;
+
+// ============== Get Page Tables ==============
+
+/// Parameters for detecting tables on a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTablesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A table detected on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DetectedTable {
+    /// Table bounds as `[x0, y0, x1, y1]`.
+    pub bounds: [f32; 4],
+    /// Cell text, row by row; every row has one entry per column.
+    pub rows: Vec<Vec<String>>,
+    /// How table-like the layout is, from 0 to 1.
+    pub confidence: f32,
+}
+
+/// Result of detecting tables.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTablesResult {
+    /// Detected tables, top to bottom.
+    pub tables: Vec<DetectedTable>,
+}
+
+/// A run of text within a line.
+struct Cell {
+    text: String,
+    bounds: [f32; 4],
+}
+
+/// Cells sharing a vertical position.
+struct Row {
+    /// Vertical center of the row.
+    y: f32,
+    cells: Vec<Cell>,
+}
+
+/// Union of two `[x0, y0, x1, y1]` boxes.
+fn union(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [
+        a[0].min(b[0]),
+        a[1].min(b[1]),
+        a[2].max(b[2]),
+        a[3].max(b[3]),
+    ]
+}
+
+/// Split every line of a text page into cells and group lines into rows.
+fn page_rows(text_page: &TextPage) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::new();
+
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            let mut cells: Vec<Cell> = Vec::new();
+            let mut current: Option<Cell> = None;
+            for ch in line.chars() {
+                let Some(c) = ch.char() else {
+                    continue;
+                };
+                if c.is_whitespace() {
+                    if let Some(cell) = current.as_mut() {
+                        cell.text.push(c);
+                    }
+                    continue;
+                }
+
+                let bounds = quad_bounds(&ch.quad());
+                match current.as_mut() {
+                    Some(cell) if bounds[0] - cell.bounds[2] <= CELL_GAP => {
+                        cell.text.push(c);
+                        cell.bounds = union(cell.bounds, bounds);
+                    }
+                    _ => {
+                        cells.extend(current.replace(Cell {
+                            text: c.to_string(),
+                            bounds,
+                        }));
+                    }
+                }
+            }
+            cells.extend(current);
+            if cells.is_empty() {
+                continue;
+            }
+
+            let bounds = cells
+                .iter()
+                .map(|cell| cell.bounds)
+                .reduce(union)
+                .unwrap_or_default();
+            let y = (bounds[1] + bounds[3]) / 2.0;
+            match rows
+                .iter_mut()
+                .find(|row| (row.y - y).abs() <= ROW_TOLERANCE)
+            {
+                Some(row) => row.cells.extend(cells),
+                None => rows.push(Row { y, cells }),
+            }
+        }
+    }
+
+    for row in &mut rows {
+        for cell in &mut row.cells {
+            cell.text = cell.text.trim().to_string();
+        }
+        row.cells
+            .sort_by(|a, b| a.bounds[0].total_cmp(&b.bounds[0]));
+    }
+    rows.sort_by(|a, b| a.y.total_cmp(&b.y));
+    rows
+}
+
+/// Build a table from a run of multi-cell rows, if enough columns align.
+fn build_table(rows: &[Row]) -> Option<DetectedTable> {
+    // Cluster the left edges of all cells into column anchors
+    let mut edges: Vec<(f32, usize)> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.cells.iter().map(move |cell| (cell.bounds[0], i)))
+        .collect();
+    edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut clusters: Vec<(f32, Vec<usize>)> = Vec::new();
+    for (x, row) in edges {
+        match clusters.last_mut() {
+            Some((start, members)) if x - *start <= COLUMN_TOLERANCE => members.push(row),
+            _ => clusters.push((x, vec![row])),
+        }
+    }
+
+    // A column must be used by at least two rows
+    let columns: Vec<f32> = clusters
+        .into_iter()
+        .filter_map(|(x, mut members)| {
+            members.dedup();
+            (members.len() >= 2).then_some(x)
+        })
+        .collect();
+    if columns.len() < 2 {
+        return None;
+    }
+
+    let mut total_cells = 0;
+    let mut aligned_cells = 0;
+    let mut bounds: Option<[f32; 4]> = None;
+    let mut table_rows = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut texts = vec![String::new(); columns.len()];
+        for cell in &row.cells {
+            let (column, distance) = columns
+                .iter()
+                .map(|x| (cell.bounds[0] - x).abs())
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap_or((0, f32::INFINITY));
+
+            total_cells += 1;
+            if distance <= COLUMN_TOLERANCE {
+                aligned_cells += 1;
+            }
+            let text = &mut texts[column];
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&cell.text);
+            bounds = Some(bounds.map_or(cell.bounds, |b| union(b, cell.bounds)));
+        }
+        table_rows.push(texts);
+    }
+
+    let alignment = aligned_cells as f32 / total_cells as f32;
+    let size = rows.len().min(CONFIDENT_ROWS) as f32 / CONFIDENT_ROWS as f32;
+
+    Some(DetectedTable {
+        bounds: bounds?,
+        rows: table_rows,
+        confidence: (alignment * size).clamp(0.0, 1.0),
+    })
+}
+
+/// Detect tables on a page from the alignment of its text.
+pub fn get_page_tables(
+    store: &DocumentStore,
+    params: GetPageTablesParams,
+) -> Result<GetPageTablesResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let rows = page_rows(&text_page);
+
+        // Split the page into runs of consecutive, closely spaced
+        // multi-cell rows
+        let mut tables = Vec::new();
+        let mut start = 0;
+        while start < rows.len() {
+            if rows[start].cells.len() < 2 {
+                start += 1;
+                continue;
+            }
+            let mut end = start + 1;
+            while end < rows.len()
+                && rows[end].cells.len() >= 2
+                && rows[end].y - rows[end - 1].y <= ROW_GAP_LIMIT
+            {
+                end += 1;
+            }
+            if end - start >= 2 {
+                tables.extend(build_table(&rows[start..end]));
+            }
+            start = end;
+        }
+
+        Ok(GetPageTablesResult { tables })
+    })
+}
@@ -1,23 +1,47 @@
 //! Text extraction tools.
 
-use mupdf::TextPageFlags;
+use std::collections::{HashMap, HashSet};
+
+use mupdf::{TextPage, TextPageFlags};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 
 use crate::error::{MupdfServerError, Result};
 use crate::state::DocumentStore;
+use crate::tools::highlevel::PageTextEntry;
+use crate::tools::page::{quad_to_hit, validate_page_number, SearchHit, DEFAULT_MAX_HITS};
 
-/// Validate page number.
-fn validate_page_number(doc: &mupdf::Document, page: i32) -> Result<()> {
-    let page_count = doc.page_count()?;
-    if page < 0 || page >= page_count {
-        return Err(MupdfServerError::InvalidPageNumber {
-            page,
-            total: page_count,
-            max: page_count - 1,
-        });
-    }
-    Ok(())
+/// Extract text from a loaded page in the specified format.
+pub(crate) fn extract_text_from_page(page: &mupdf::Page, format: &str) -> Result<String> {
+    let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+    let text = match format {
+        "plain" => {
+            // Extract plain text by iterating through blocks
+            let mut result = String::new();
+            for block in text_page.blocks() {
+                for line in block.lines() {
+                    for ch in line.chars() {
+                        if let Some(c) = ch.char() {
+                            result.push(c);
+                        }
+                    }
+                    result.push('\n');
+                }
+                result.push('\n');
+            }
+            result
+        }
+        "html" => text_page.to_html(0, true)?,
+        "json" => text_page.to_json(1.0)?,
+        "xml" => text_page.to_xml(0)?,
+        other => return Err(MupdfServerError::InvalidTextFormat(other.to_string())),
+    };
+
+    Ok(text)
 }
 
 // ============== Get Page Text ==============
@@ -27,7 +51,7 @@ fn validate_page_number(doc: &mupdf::Document, page: i32) -> Result<()> {
 pub struct GetPageTextParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
     /// Output format: "plain", "html", "json", "xml".
     #[serde(default = "default_text_format")]
@@ -53,32 +77,9 @@ pub fn get_page_text(
     params: GetPageTextParams,
 ) -> Result<GetPageTextResult> {
     store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
-        let text_page = page.to_text_page(TextPageFlags::empty())?;
-
-        let text = match params.format.as_str() {
-            "plain" => {
-                // Extract plain text by iterating through blocks
-                let mut result = String::new();
-                for block in text_page.blocks() {
-                    for line in block.lines() {
-                        for ch in line.chars() {
-                            if let Some(c) = ch.char() {
-                                result.push(c);
-                            }
-                        }
-                        result.push('\n');
-                    }
-                    result.push('\n');
-                }
-                result
-            }
-            "html" => text_page.to_html(0, true)?,
-            "json" => text_page.to_json(1.0)?,
-            "xml" => text_page.to_xml(0)?,
-            other => return Err(MupdfServerError::InvalidTextFormat(other.to_string())),
-        };
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text = extract_text_from_page(&page, &params.format)?;
 
         Ok(GetPageTextResult {
             text,
@@ -87,6 +88,82 @@ pub fn get_page_text(
     })
 }
 
+// ============== Extract Text From Page Range ==============
+
+/// Default separator placed between pages in the concatenated text.
+const DEFAULT_PAGE_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Parameters for extracting the text of a page range.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExtractTextRangeParams {
+    /// Document ID.
+    pub document_id: String,
+    /// First page of the range (0-indexed, inclusive; negative counts from the end).
+    pub start_page: i32,
+    /// Last page of the range (0-indexed, inclusive; negative counts from the end).
+    pub end_page: i32,
+    /// Output format: "plain", "html", "json", "xml" (default "plain").
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Separator inserted between pages in `concatenated` (default "\n\n---\n\n").
+    #[serde(default)]
+    pub join_separator: Option<String>,
+}
+
+/// Result of extracting the text of a page range.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExtractTextRangeResult {
+    /// Text of each page in the range, in page order.
+    pub pages: Vec<PageTextEntry>,
+    /// Page texts joined with the separator.
+    pub concatenated: String,
+}
+
+/// Extract the text of an inclusive page range.
+///
+/// The document is locked once for the whole range rather than once per page.
+pub fn extract_text_from_page_range(
+    store: &DocumentStore,
+    params: ExtractTextRangeParams,
+) -> Result<ExtractTextRangeResult> {
+    let format = params.format.as_deref().unwrap_or("plain");
+    let separator = params
+        .join_separator
+        .as_deref()
+        .unwrap_or(DEFAULT_PAGE_SEPARATOR);
+
+    store.with_document(&params.document_id, |doc| {
+        let start = validate_page_number(doc, params.start_page)?;
+        let end = validate_page_number(doc, params.end_page)?;
+        if start > end {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "start_page ({}) is after end_page ({})",
+                params.start_page, params.end_page
+            )));
+        }
+
+        let mut pages = Vec::with_capacity((end - start + 1) as usize);
+        for page_number in start..=end {
+            let page = doc.load_page(page_number)?;
+            pages.push(PageTextEntry {
+                page: page_number,
+                text: extract_text_from_page(&page, format)?,
+            });
+        }
+
+        let concatenated = pages
+            .iter()
+            .map(|entry| entry.text.as_str())
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        Ok(ExtractTextRangeResult {
+            pages,
+            concatenated,
+        })
+    })
+}
+
 // ============== Get Page Text Blocks ==============
 
 /// Parameters for extracting structured text blocks.
@@ -94,7 +171,7 @@ pub fn get_page_text(
 pub struct GetPageTextBlocksParams {
     /// Document ID.
     pub document_id: String,
-    /// Page number (0-indexed).
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
     pub page: i32,
 }
 
@@ -132,48 +209,1712 @@ pub struct GetPageTextBlocksResult {
     pub blocks: Vec<TextBlock>,
 }
 
+/// Collect the text blocks of a text page with their lines.
+fn text_blocks(text_page: &TextPage) -> Vec<TextBlock> {
+    let mut blocks = Vec::new();
+
+    for block in text_page.blocks() {
+        let block_bounds = block.bounds();
+        let mut lines = Vec::new();
+
+        for line in block.lines() {
+            let line_bounds = line.bounds();
+            let text: String = line.chars().filter_map(|c| c.char()).collect();
+
+            lines.push(TextLine {
+                bounds: BlockBounds {
+                    x0: line_bounds.x0,
+                    y0: line_bounds.y0,
+                    x1: line_bounds.x1,
+                    y1: line_bounds.y1,
+                },
+                text,
+            });
+        }
+
+        blocks.push(TextBlock {
+            bounds: BlockBounds {
+                x0: block_bounds.x0,
+                y0: block_bounds.y0,
+                x1: block_bounds.x1,
+                y1: block_bounds.y1,
+            },
+            lines,
+        });
+    }
+
+    blocks
+}
+
 /// Extract structured text blocks from a page.
 pub fn get_page_text_blocks(
     store: &DocumentStore,
     params: GetPageTextBlocksParams,
 ) -> Result<GetPageTextBlocksResult> {
     store.with_document(&params.document_id, |doc| {
-        validate_page_number(doc, params.page)?;
-        let page = doc.load_page(params.page)?;
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
         let text_page = page.to_text_page(TextPageFlags::empty())?;
 
-        let mut blocks = Vec::new();
+        Ok(GetPageTextBlocksResult {
+            blocks: text_blocks(&text_page),
+        })
+    })
+}
 
-        for block in text_page.blocks() {
-            let block_bounds = block.bounds();
-            let mut lines = Vec::new();
+// ============== Get Page Text Lines ==============
+
+/// Parameters for extracting the text lines of a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextLinesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A single line of text.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TextLineEntry {
+    /// Line text.
+    pub text: String,
+    /// Left edge of the line.
+    pub x0: f32,
+    /// Top edge of the line.
+    pub y0: f32,
+    /// Right edge of the line.
+    pub x1: f32,
+    /// Bottom edge of the line.
+    pub y1: f32,
+    /// "horizontal" or "vertical".
+    pub writing_mode: String,
+    /// Index of the containing text block.
+    pub block_index: usize,
+}
+
+/// Result of extracting text lines.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextLinesResult {
+    /// Non-empty lines in text order.
+    pub lines: Vec<TextLineEntry>,
+    /// Number of lines returned.
+    pub total: usize,
+}
+
+/// Writing mode of a line, judged by whether its characters advance
+/// mostly across or down the page.
+fn line_writing_mode(line: &mupdf::TextLine) -> &'static str {
+    let mut centers = line.chars().map(|ch| {
+        let [x0, y0, x1, y1] = quad_bounds(&ch.quad());
+        ((x0 + x1) / 2.0, (y0 + y1) / 2.0)
+    });
+    let Some(first) = centers.next() else {
+        return "horizontal";
+    };
+    let last = centers.last().unwrap_or(first);
+    if (last.1 - first.1).abs() > (last.0 - first.0).abs() {
+        "vertical"
+    } else {
+        "horizontal"
+    }
+}
 
+/// Extract every non-empty line of a page with its bounding box.
+pub fn get_page_text_lines(
+    store: &DocumentStore,
+    params: GetPageTextLinesParams,
+) -> Result<GetPageTextLinesResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        let mut lines = Vec::new();
+        for (block_index, block) in text_page.blocks().enumerate() {
             for line in block.lines() {
-                let line_bounds = line.bounds();
                 let text: String = line.chars().filter_map(|c| c.char()).collect();
+                if text.trim().is_empty() {
+                    continue;
+                }
 
-                lines.push(TextLine {
-                    bounds: BlockBounds {
-                        x0: line_bounds.x0,
-                        y0: line_bounds.y0,
-                        x1: line_bounds.x1,
-                        y1: line_bounds.y1,
-                    },
+                let bounds = line.bounds();
+                lines.push(TextLineEntry {
                     text,
+                    x0: bounds.x0,
+                    y0: bounds.y0,
+                    x1: bounds.x1,
+                    y1: bounds.y1,
+                    writing_mode: line_writing_mode(&line).to_string(),
+                    block_index,
                 });
             }
+        }
 
-            blocks.push(TextBlock {
-                bounds: BlockBounds {
-                    x0: block_bounds.x0,
-                    y0: block_bounds.y0,
-                    x1: block_bounds.x1,
-                    y1: block_bounds.y1,
-                },
-                lines,
-            });
+        Ok(GetPageTextLinesResult {
+            total: lines.len(),
+            lines,
+        })
+    })
+}
+
+// ============== Get Page Text Reading Order ==============
+
+/// Default horizontal gap (in points) separating two columns.
+const DEFAULT_COLUMN_GAP: f32 = 20.0;
+
+/// Group horizontal extents `(x0, x1)` into columns.
+///
+/// Extents are swept from left to right, starting a new column whenever the
+/// gap to the current column exceeds `gap`. Returns the `(x_min, x_max)` of
+/// each column, left to right, and the column index of each extent.
+fn cluster_columns(extents: &[(f32, f32)], gap: f32) -> (Vec<(f32, f32)>, Vec<usize>) {
+    let mut order: Vec<usize> = (0..extents.len()).collect();
+    order.sort_by(|&a, &b| extents[a].0.total_cmp(&extents[b].0));
+
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+    let mut assignment = vec![0; extents.len()];
+    for index in order {
+        let (x0, x1) = extents[index];
+        match columns.last_mut() {
+            Some((_, right)) if x0 - *right <= gap => *right = right.max(x1),
+            _ => columns.push((x0, x1)),
+        }
+        assignment[index] = columns.len() - 1;
+    }
+    (columns, assignment)
+}
+
+/// Fraction of the page width above which a block is treated as spanning
+/// columns (titles, headers, footers) rather than belonging to one.
+const SPANNING_BLOCK_WIDTH: f32 = 0.5;
+
+/// Whether a block from `x0` to `x1` spans columns on a page `page_width`
+/// points wide.
+fn is_spanning_block(x0: f32, x1: f32, page_width: f32) -> bool {
+    x1 - x0 > page_width * SPANNING_BLOCK_WIDTH
+}
+
+/// Validate a column gap threshold, returning it or the default.
+fn column_gap(gap: Option<f32>, name: &str) -> Result<f32> {
+    let gap = gap.unwrap_or(DEFAULT_COLUMN_GAP);
+    if gap.is_nan() || gap < 0.0 {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "{} must be non-negative, got {}",
+            name, gap
+        )));
+    }
+    Ok(gap)
+}
+
+/// Parameters for extracting page text in visual reading order.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextReadingOrderParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Minimum horizontal gap in points between two columns (default 20).
+    /// Blocks closer than this are merged into one column.
+    #[serde(default)]
+    pub column_gap_threshold: Option<f32>,
+}
+
+/// Page text in visual reading order.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextReadingOrderResult {
+    /// Text of the page, column by column, top to bottom within a column.
+    /// Blocks spanning the columns are placed between them by position.
+    pub text: String,
+    /// Largest number of columns detected between two spanning blocks.
+    pub column_count: usize,
+}
+
+/// Extract page text in reading order: columns left to right, blocks top
+/// to bottom within each column.
+///
+/// Blocks wider than half the page (titles, headers, footers) span the
+/// columns; they are read in vertical order and split the page into bands.
+/// Within each band, columns are found by sweeping blocks from left to
+/// right and starting a new column whenever the gap to the current column
+/// exceeds the threshold.
+pub fn get_page_text_reading_order(
+    store: &DocumentStore,
+    params: GetPageTextReadingOrderParams,
+) -> Result<GetPageTextReadingOrderResult> {
+    let gap = column_gap(params.column_gap_threshold, "column_gap_threshold")?;
+
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let page_bounds = page.bounds()?;
+        let page_width = page_bounds.x1 - page_bounds.x0;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        // (bounds, text) of every block with text
+        let blocks: Vec<(mupdf::Rect, String)> = text_page
+            .blocks()
+            .filter_map(|block| {
+                let mut text = String::new();
+                for line in block.lines() {
+                    text.extend(line.chars().filter_map(|c| c.char()));
+                    text.push('\n');
+                }
+                Some((block.bounds(), text)).filter(|(_, text)| !text.trim().is_empty())
+            })
+            .collect();
+
+        // Spanning blocks stay out of the column clustering and split the
+        // page into bands, each with its own columns
+        let (mut spanning, columned): (Vec<_>, Vec<_>) = blocks
+            .into_iter()
+            .partition(|(bounds, _)| is_spanning_block(bounds.x0, bounds.x1, page_width));
+        spanning.sort_by(|(a, _), (b, _)| a.y0.total_cmp(&b.y0));
+        let mut bands: Vec<Vec<(mupdf::Rect, String)>> =
+            (0..=spanning.len()).map(|_| Vec::new()).collect();
+        for block in columned {
+            let band = spanning.partition_point(|(bounds, _)| bounds.y0 <= block.0.y0);
+            bands[band].push(block);
+        }
+
+        let mut text = String::new();
+        let mut column_count = 1;
+        for (band, members) in bands.into_iter().enumerate() {
+            // Assign blocks to columns by horizontal extent
+            let extents: Vec<(f32, f32)> = members.iter().map(|(b, _)| (b.x0, b.x1)).collect();
+            let (zones, assignment) = cluster_columns(&extents, gap);
+            column_count = column_count.max(zones.len());
+            let mut columns: Vec<Vec<(mupdf::Rect, String)>> =
+                zones.iter().map(|_| Vec::new()).collect();
+            for (block, column) in members.into_iter().zip(assignment) {
+                columns[column].push(block);
+            }
+
+            for members in &mut columns {
+                members.sort_by(|(a, _), (b, _)| a.y0.total_cmp(&b.y0).then(a.x0.total_cmp(&b.x0)));
+                for (_, block_text) in members.iter() {
+                    text.push_str(block_text);
+                    text.push('\n');
+                }
+            }
+            if let Some((_, block_text)) = spanning.get(band) {
+                text.push_str(block_text);
+                text.push('\n');
+            }
+        }
+
+        Ok(GetPageTextReadingOrderResult { text, column_count })
+    })
+}
+
+// ============== Get Text Blocks With Reading Zones ==============
+
+/// Parameters for grouping the text blocks of a page into columns.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTextBlocksWithZonesParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Minimum horizontal gap in points between two columns (default 20).
+    #[serde(default)]
+    pub column_gap_pts: Option<f32>,
+}
+
+/// A column of text blocks.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ColumnZone {
+    /// Column number, left to right from 0.
+    pub column_index: usize,
+    /// Left edge of the column.
+    pub x_min: f32,
+    /// Right edge of the column.
+    pub x_max: f32,
+}
+
+/// A text block with the column it belongs to.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TextBlockWithZone {
+    /// The text block.
+    #[serde(flatten)]
+    pub block: TextBlock,
+    /// Index of the containing column, or `None` for a block spanning the
+    /// columns (wider than half the page, such as a title or footer).
+    pub column: Option<usize>,
+}
+
+/// Result of grouping text blocks into columns.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetTextBlocksWithZonesResult {
+    /// Detected columns, left to right.
+    pub columns: Vec<ColumnZone>,
+    /// Text blocks in page order, each with its column.
+    pub blocks: Vec<TextBlockWithZone>,
+}
+
+/// Get the text blocks of a page grouped into columns by x-position.
+///
+/// Uses the same column sweep as `get_page_text_reading_order`, leaving
+/// blocks wider than half the page out of the columns. A page whose blocks
+/// are all that wide is a single column.
+pub fn get_text_blocks_with_reading_zones(
+    store: &DocumentStore,
+    params: GetTextBlocksWithZonesParams,
+) -> Result<GetTextBlocksWithZonesResult> {
+    let gap = column_gap(params.column_gap_pts, "column_gap_pts")?;
+
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let page_bounds = page.bounds()?;
+        let page_width = page_bounds.x1 - page_bounds.x0;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let blocks = text_blocks(&text_page);
+
+        let mut spanning: Vec<bool> = blocks
+            .iter()
+            .map(|block| is_spanning_block(block.bounds.x0, block.bounds.x1, page_width))
+            .collect();
+        if spanning.iter().all(|&spanning| spanning) {
+            spanning.fill(false);
+        }
+        let columned: Vec<usize> = (0..blocks.len()).filter(|&i| !spanning[i]).collect();
+        let extents: Vec<(f32, f32)> = columned
+            .iter()
+            .map(|&i| (blocks[i].bounds.x0, blocks[i].bounds.x1))
+            .collect();
+        let (zones, assignment) = cluster_columns(&extents, gap);
+        let mut block_columns = vec![None; blocks.len()];
+        for (index, column) in columned.into_iter().zip(assignment) {
+            block_columns[index] = Some(column);
+        }
+
+        Ok(GetTextBlocksWithZonesResult {
+            columns: zones
+                .into_iter()
+                .enumerate()
+                .map(|(column_index, (x_min, x_max))| ColumnZone {
+                    column_index,
+                    x_min,
+                    x_max,
+                })
+                .collect(),
+            blocks: blocks
+                .into_iter()
+                .zip(block_columns)
+                .map(|(block, column)| TextBlockWithZone { block, column })
+                .collect(),
+        })
+    })
+}
+
+// ============== Get Page Words ==============
+
+/// Parameters for extracting words with their bounding boxes.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageWordsParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A single word on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WordEntry {
+    /// Word text.
+    pub text: String,
+    /// Left edge of the word.
+    pub x0: f32,
+    /// Top edge of the word.
+    pub y0: f32,
+    /// Right edge of the word.
+    pub x1: f32,
+    /// Bottom edge of the word.
+    pub y1: f32,
+    /// Index of the text block containing the word.
+    pub block_index: usize,
+}
+
+/// Result of extracting words.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageWordsResult {
+    /// Words on the page, in text order.
+    pub words: Vec<WordEntry>,
+}
+
+/// Axis-aligned bounding box of a character quad as `[x0, y0, x1, y1]`.
+pub(crate) fn quad_bounds(quad: &mupdf::Quad) -> [f32; 4] {
+    let xs = [quad.ul.x, quad.ur.x, quad.ll.x, quad.lr.x];
+    let ys = [quad.ul.y, quad.ur.y, quad.ll.y, quad.lr.y];
+    [
+        xs.iter().copied().fold(f32::INFINITY, f32::min),
+        ys.iter().copied().fold(f32::INFINITY, f32::min),
+        xs.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        ys.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+    ]
+}
+
+/// Group the characters of a text page into whitespace-separated words.
+///
+/// Words never span lines; each word's bounds are the union of its
+/// character quads.
+fn collect_words(text_page: &TextPage) -> Vec<WordEntry> {
+    collect_words_with_lines(text_page)
+        .into_iter()
+        .map(|(_, word)| word)
+        .collect()
+}
+
+/// Like [`collect_words`], pairing each word with the index of its line
+/// within its block.
+fn collect_words_with_lines(text_page: &TextPage) -> Vec<(usize, WordEntry)> {
+    let mut words = Vec::new();
+
+    for (block_index, block) in text_page.blocks().enumerate() {
+        for (line_index, line) in block.lines().enumerate() {
+            let mut current: Option<WordEntry> = None;
+
+            for ch in line.chars() {
+                let Some(c) = ch.char() else {
+                    continue;
+                };
+
+                if c.is_whitespace() {
+                    if let Some(word) = current.take() {
+                        words.push((line_index, word));
+                    }
+                    continue;
+                }
+
+                let [x0, y0, x1, y1] = quad_bounds(&ch.quad());
+                match current.as_mut() {
+                    Some(word) => {
+                        word.text.push(c);
+                        word.x0 = word.x0.min(x0);
+                        word.y0 = word.y0.min(y0);
+                        word.x1 = word.x1.max(x1);
+                        word.y1 = word.y1.max(y1);
+                    }
+                    None => {
+                        current = Some(WordEntry {
+                            text: c.to_string(),
+                            x0,
+                            y0,
+                            x1,
+                            y1,
+                            block_index,
+                        });
+                    }
+                }
+            }
+
+            if let Some(word) = current.take() {
+                words.push((line_index, word));
+            }
+        }
+    }
+
+    words
+}
+
+/// Extract all words on a page with their bounding boxes.
+pub fn get_page_words(
+    store: &DocumentStore,
+    params: GetPageWordsParams,
+) -> Result<GetPageWordsResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        Ok(GetPageWordsResult {
+            words: collect_words(&text_page),
+        })
+    })
+}
+
+// ============== Get Page Words At Point ==============
+
+/// Parameters for finding the word under a point.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageWordsAtPointParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// X coordinate in page points.
+    pub x: f32,
+    /// Y coordinate in page points.
+    pub y: f32,
+}
+
+/// The word under a point, if any.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageWordsAtPointResult {
+    /// Word text, or null if no word contains the point.
+    pub word: Option<String>,
+    /// Word bounds as `[x0, y0, x1, y1]`.
+    pub bounds: Option<[f32; 4]>,
+    /// Index of the text block containing the word.
+    pub block_index: Option<usize>,
+    /// Index of the line within its block.
+    pub line_index: Option<usize>,
+}
+
+/// Find the first word whose bounding box contains a point.
+pub fn get_page_words_at_point(
+    store: &DocumentStore,
+    params: GetPageWordsAtPointParams,
+) -> Result<GetPageWordsAtPointResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        let (x, y) = (params.x, params.y);
+        let hit = collect_words_with_lines(&text_page)
+            .into_iter()
+            .find(|(_, w)| x >= w.x0 && x <= w.x1 && y >= w.y0 && y <= w.y1);
+
+        Ok(match hit {
+            Some((line_index, word)) => GetPageWordsAtPointResult {
+                bounds: Some([word.x0, word.y0, word.x1, word.y1]),
+                block_index: Some(word.block_index),
+                line_index: Some(line_index),
+                word: Some(word.text),
+            },
+            None => GetPageWordsAtPointResult {
+                word: None,
+                bounds: None,
+                block_index: None,
+                line_index: None,
+            },
+        })
+    })
+}
+
+// ============== Get Page Chars ==============
+
+/// Font of a run of characters, as reported by the `<font>` elements of
+/// MuPDF's stext XML output.
+#[derive(Debug, Clone, Default)]
+struct FontRun {
+    name: String,
+}
+
+impl FontRun {
+    fn is_bold(&self) -> bool {
+        self.name.contains("Bold")
+    }
+
+    fn is_italic(&self) -> bool {
+        self.name.contains("Italic") || self.name.contains("Oblique")
+    }
+}
+
+/// Font and color of every character of a text page.
+///
+/// The stext character API exposes neither, but the XML output wraps the
+/// characters of each line in a `<font>` element at every font change and
+/// gives each `<char>` a `color="#rrggbb"` attribute. Its `<char>` elements
+/// follow the same order as the character iterators.
+struct PageStyles {
+    /// Font runs in text order.
+    runs: Vec<FontRun>,
+    /// Per character in text order: index into `runs` (if inside a run)
+    /// and color as `0xRRGGBB` (black if missing).
+    chars: Vec<(Option<usize>, u32)>,
+}
+
+impl PageStyles {
+    fn new(text_page: &TextPage) -> Result<Self> {
+        let xml = text_page.to_xml(0)?;
+        let mut reader = Reader::from_str(&xml);
+        let mut runs = Vec::new();
+        let mut chars = Vec::new();
+        let mut current = None;
+
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| MupdfServerError::internal(format!("Invalid stext XML: {}", e)))?;
+            match event {
+                Event::Start(element) if element.name().as_ref() == b"font" => {
+                    let name = xml_attributes(&element)?.remove("name").unwrap_or_default();
+                    current = Some(runs.len());
+                    runs.push(FontRun { name });
+                }
+                Event::End(element) if element.name().as_ref() == b"font" => current = None,
+                Event::Empty(element) if element.name().as_ref() == b"char" => {
+                    let color = xml_attributes(&element)?
+                        .get("color")
+                        .and_then(|color| color.strip_prefix('#'))
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .unwrap_or(0);
+                    chars.push((current, color));
+                }
+                Event::Eof => break,
+                _ => {}
+            }
         }
 
-        Ok(GetPageTextBlocksResult { blocks })
+        Ok(Self { runs, chars })
+    }
+
+    /// Font of the `index`-th character of the page.
+    fn font(&self, index: usize) -> Option<&FontRun> {
+        self.runs.get(self.chars.get(index)?.0?)
+    }
+
+    /// Color of the `index`-th character of the page as `0xRRGGBB`.
+    fn color(&self, index: usize) -> u32 {
+        self.chars.get(index).map_or(0, |(_, color)| *color)
+    }
+}
+
+/// Names of all fonts used by the characters of a text page.
+pub(crate) fn page_font_names(text_page: &TextPage) -> Result<HashSet<String>> {
+    let styles = PageStyles::new(text_page)?;
+    Ok(styles
+        .runs
+        .into_iter()
+        .map(|font| font.name)
+        .filter(|name| !name.is_empty())
+        .collect())
+}
+
+/// Parameters for extracting characters with their bounding boxes.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageCharsParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A single character on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CharEntry {
+    /// The character.
+    pub ch: char,
+    /// Left edge of the character.
+    pub x0: f32,
+    /// Top edge of the character.
+    pub y0: f32,
+    /// Right edge of the character.
+    pub x1: f32,
+    /// Bottom edge of the character.
+    pub y1: f32,
+    /// Font name (empty if unknown).
+    pub font_name: String,
+    /// Font size in points.
+    pub font_size: f32,
+    /// Index of the containing text block.
+    pub block: usize,
+    /// Index of the containing line within its block.
+    pub line: usize,
+}
+
+/// Result of extracting characters.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageCharsResult {
+    /// Characters on the page, in text order.
+    pub chars: Vec<CharEntry>,
+}
+
+/// Extract every character on a page with its position and font.
+pub fn get_page_chars(
+    store: &DocumentStore,
+    params: GetPageCharsParams,
+) -> Result<GetPageCharsResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let styles = PageStyles::new(&text_page)?;
+
+        let mut chars = Vec::new();
+        let mut char_index = 0;
+        for (block_index, block) in text_page.blocks().enumerate() {
+            for (line_index, line) in block.lines().enumerate() {
+                for ch in line.chars() {
+                    let font = styles.font(char_index);
+                    char_index += 1;
+
+                    // Skip characters without a Unicode mapping
+                    let Some(c) = ch.char() else {
+                        continue;
+                    };
+
+                    let [x0, y0, x1, y1] = quad_bounds(&ch.quad());
+                    chars.push(CharEntry {
+                        ch: c,
+                        x0,
+                        y0,
+                        x1,
+                        y1,
+                        font_name: font.map(|f| f.name.clone()).unwrap_or_default(),
+                        font_size: ch.size(),
+                        block: block_index,
+                        line: line_index,
+                    });
+                }
+            }
+        }
+
+        Ok(GetPageCharsResult { chars })
+    })
+}
+
+// ============== Get Page Text Spans ==============
+
+/// Parameters for extracting styled text spans.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextSpansParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A run of consecutive characters on one line sharing the same style.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TextSpanEntry {
+    /// Text of the span.
+    pub text: String,
+    /// Font name (empty if unknown).
+    pub font_name: String,
+    /// Font size in points.
+    pub font_size: f32,
+    /// Whether the font is bold.
+    pub is_bold: bool,
+    /// Whether the font is italic.
+    pub is_italic: bool,
+    /// Text color as `0xRRGGBB`.
+    pub color: u32,
+    /// Left edge of the span.
+    pub x0: f32,
+    /// Top edge of the span.
+    pub y0: f32,
+    /// Right edge of the span.
+    pub x1: f32,
+    /// Bottom edge of the span.
+    pub y1: f32,
+}
+
+/// Result of extracting styled text spans.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextSpansResult {
+    /// Spans in text order.
+    pub spans: Vec<TextSpanEntry>,
+}
+
+/// Extract text spans with font and style information from a page.
+pub fn get_page_text_spans(
+    store: &DocumentStore,
+    params: GetPageTextSpansParams,
+) -> Result<GetPageTextSpansResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let styles = PageStyles::new(&text_page)?;
+
+        let mut spans: Vec<TextSpanEntry> = Vec::new();
+        let mut char_index = 0;
+        for block in text_page.blocks() {
+            for line in block.lines() {
+                // Spans never continue across lines
+                let mut current: Option<TextSpanEntry> = None;
+                for ch in line.chars() {
+                    let font = styles.font(char_index);
+                    let color = styles.color(char_index);
+                    char_index += 1;
+
+                    let Some(c) = ch.char() else {
+                        continue;
+                    };
+                    let [x0, y0, x1, y1] = quad_bounds(&ch.quad());
+                    let font_name = font.map(|f| f.name.clone()).unwrap_or_default();
+                    let is_bold = font.is_some_and(FontRun::is_bold);
+                    let is_italic = font.is_some_and(FontRun::is_italic);
+
+                    match &mut current {
+                        Some(span)
+                            if span.font_name == font_name
+                                && span.font_size == ch.size()
+                                && span.is_bold == is_bold
+                                && span.is_italic == is_italic
+                                && span.color == color =>
+                        {
+                            span.text.push(c);
+                            span.x0 = span.x0.min(x0);
+                            span.y0 = span.y0.min(y0);
+                            span.x1 = span.x1.max(x1);
+                            span.y1 = span.y1.max(y1);
+                        }
+                        _ => {
+                            spans.extend(current.take());
+                            current = Some(TextSpanEntry {
+                                text: c.to_string(),
+                                font_name,
+                                font_size: ch.size(),
+                                is_bold,
+                                is_italic,
+                                color,
+                                x0,
+                                y0,
+                                x1,
+                                y1,
+                            });
+                        }
+                    }
+                }
+                spans.extend(current);
+            }
+        }
+
+        Ok(GetPageTextSpansResult { spans })
+    })
+}
+
+// ============== Get Fonts On Page ==============
+
+/// Parameters for listing the fonts used on a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetFontsOnPageParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// A font and size combination used on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PageFontEntry {
+    /// Font name (empty if unknown).
+    pub name: String,
+    /// Font size in points.
+    pub size: f32,
+    /// Number of characters set in this font and size.
+    pub char_count: usize,
+}
+
+/// Result of listing the fonts used on a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetFontsOnPageResult {
+    /// Fonts in order of first use.
+    pub fonts: Vec<PageFontEntry>,
+}
+
+/// Count the characters on a page per font name and size.
+pub fn get_fonts_on_page(
+    store: &DocumentStore,
+    params: GetFontsOnPageParams,
+) -> Result<GetFontsOnPageResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let styles = PageStyles::new(&text_page)?;
+
+        let mut entries: Vec<PageFontEntry> = Vec::new();
+        let mut char_index = 0;
+        for block in text_page.blocks() {
+            for line in block.lines() {
+                for ch in line.chars() {
+                    let font = styles.font(char_index);
+                    char_index += 1;
+
+                    let name = font.map(|f| f.name.as_str()).unwrap_or_default();
+                    let size = ch.size();
+                    match entries
+                        .iter_mut()
+                        .find(|e| e.name == name && e.size == size)
+                    {
+                        Some(entry) => entry.char_count += 1,
+                        None => entries.push(PageFontEntry {
+                            name: name.to_string(),
+                            size,
+                            char_count: 1,
+                        }),
+                    }
+                }
+            }
+        }
+
+        Ok(GetFontsOnPageResult { fonts: entries })
+    })
+}
+
+// ============== Get Page Text Language ==============
+
+/// Share of letters that must belong to a script for it to be detected.
+const SCRIPT_THRESHOLD: f32 = 0.8;
+
+/// Parameters for detecting the script of a page's text.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextLanguageParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Coarse script and language detection result.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextLanguageResult {
+    /// "CJK", "RTL" (Arabic or Hebrew) or "Latin" (anything else).
+    pub detected_script: String,
+    /// Likely language codes for the script (e.g. "zh/ja/ko"), if any.
+    pub language_hint: Option<String>,
+    /// Share of letters on the page that belong to the detected script (0-1).
+    pub confidence: f32,
+    /// Number of letters considered (whitespace, digits and punctuation are
+    /// ignored).
+    pub char_count: usize,
+}
+
+/// Whether a character is in a Chinese, Japanese or Korean Unicode block.
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x11FF // Hangul Jamo
+        | 0x3040..=0x30FF // Hiragana, Katakana
+        | 0x3130..=0x318F // Hangul Compatibility Jamo
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul Syllables
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0xFF66..=0xFF9F // Halfwidth Katakana
+        | 0x20000..=0x2FA1F // CJK Extensions B-F, Compatibility Supplement
+    )
+}
+
+/// Whether a character is in an Arabic or Hebrew Unicode block.
+fn is_rtl(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x05FF // Hebrew
+        | 0x0600..=0x06FF // Arabic
+        | 0x0750..=0x077F // Arabic Supplement
+        | 0x08A0..=0x08FF // Arabic Extended-A
+        | 0xFB1D..=0xFB4F // Hebrew presentation forms
+        | 0xFB50..=0xFDFF // Arabic Presentation Forms-A
+        | 0xFE70..=0xFEFF // Arabic Presentation Forms-B
+    )
+}
+
+/// Detect the dominant script of a page by Unicode block statistics.
+///
+/// Intentionally coarse: a page is "CJK" or "RTL" when more than 80% of
+/// its letters are in those blocks, and "Latin" otherwise.
+pub fn get_page_text_language(
+    store: &DocumentStore,
+    params: GetPageTextLanguageParams,
+) -> Result<GetPageTextLanguageResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        let (mut cjk, mut rtl, mut total) = (0usize, 0usize, 0usize);
+        for block in text_page.blocks() {
+            for line in block.lines() {
+                for c in line.chars().filter_map(|ch| ch.char()) {
+                    if !c.is_alphabetic() {
+                        continue;
+                    }
+                    total += 1;
+                    if is_cjk(c) {
+                        cjk += 1;
+                    } else if is_rtl(c) {
+                        rtl += 1;
+                    }
+                }
+            }
+        }
+
+        let share = |count: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f32 / total as f32
+            }
+        };
+        let (detected_script, language_hint, confidence) = if share(cjk) > SCRIPT_THRESHOLD {
+            ("CJK", Some("zh/ja/ko"), share(cjk))
+        } else if share(rtl) > SCRIPT_THRESHOLD {
+            ("RTL", Some("ar/he"), share(rtl))
+        } else {
+            ("Latin", None, share(total - cjk - rtl))
+        };
+
+        Ok(GetPageTextLanguageResult {
+            detected_script: detected_script.to_string(),
+            language_hint: language_hint.map(str::to_string),
+            confidence,
+            char_count: total,
+        })
+    })
+}
+
+// ============== Get Page Text Encoding ==============
+
+/// Parameters for checking the text encoding of a page.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextEncodingParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+}
+
+/// Text encoding statistics of a page.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextEncodingResult {
+    /// Number of characters extracted from the page.
+    pub total_chars: usize,
+    /// Characters that could not be mapped to Unicode (U+FFFD or an invalid
+    /// code point).
+    pub replacement_chars: usize,
+    /// Whether any character could not be mapped.
+    pub has_encoding_issues: bool,
+    /// Share of unmapped characters (0-100).
+    pub encoding_issue_percent: f32,
+}
+
+/// Count the characters of a page that could not be mapped to Unicode.
+///
+/// MuPDF emits U+FFFD for glyphs without a Unicode mapping (typically a
+/// font without a usable `/ToUnicode` or encoding), which makes the
+/// extracted text unreliable.
+pub fn get_page_text_encoding(
+    store: &DocumentStore,
+    params: GetPageTextEncodingParams,
+) -> Result<GetPageTextEncodingResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+
+        let (mut total_chars, mut replacement_chars) = (0usize, 0usize);
+        for block in text_page.blocks() {
+            for line in block.lines() {
+                for ch in line.chars() {
+                    total_chars += 1;
+                    if !matches!(ch.char(), Some(c) if c != char::REPLACEMENT_CHARACTER) {
+                        replacement_chars += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(GetPageTextEncodingResult {
+            total_chars,
+            replacement_chars,
+            has_encoding_issues: replacement_chars > 0,
+            encoding_issue_percent: if total_chars == 0 {
+                0.0
+            } else {
+                replacement_chars as f32 / total_chars as f32 * 100.0
+            },
+        })
+    })
+}
+
+// ============== Get Text For Region ==============
+
+/// Parameters for extracting the text inside a rectangle.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTextForRegionParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Left edge of the region.
+    pub x0: f32,
+    /// Top edge of the region.
+    pub y0: f32,
+    /// Right edge of the region.
+    pub x1: f32,
+    /// Bottom edge of the region.
+    pub y1: f32,
+    /// Minimum fraction of a character's area that must lie inside the
+    /// region for it to be included (default 0.5, 0 = any overlap).
+    #[serde(default)]
+    pub overlap: Option<f32>,
+}
+
+/// Text found inside a region.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetTextForRegionResult {
+    /// Text in reading order, one line per text line.
+    pub text: String,
+    /// Number of characters included (excluding line breaks).
+    pub char_count: usize,
+}
+
+/// Default minimum overlap between a character and the region.
+pub(crate) const DEFAULT_REGION_OVERLAP: f32 = 0.5;
+
+/// Fraction of the area of `rect` that lies inside `region`.
+///
+/// Degenerate (zero-area) rectangles count as inside when their
+/// origin corner is.
+fn overlap_fraction(rect: [f32; 4], region: [f32; 4]) -> f32 {
+    let width = (rect[2].min(region[2]) - rect[0].max(region[0])).max(0.0);
+    let height = (rect[3].min(region[3]) - rect[1].max(region[1])).max(0.0);
+    let area = (rect[2] - rect[0]) * (rect[3] - rect[1]);
+    if area <= 0.0 {
+        let inside = rect[0] >= region[0]
+            && rect[0] <= region[2]
+            && rect[1] >= region[1]
+            && rect[1] <= region[3];
+        return if inside { 1.0 } else { 0.0 };
+    }
+    width * height / area
+}
+
+/// Text of the characters of a text page lying inside `region`, in
+/// reading order, with the number of characters included.
+pub(crate) fn region_text(text_page: &TextPage, region: [f32; 4], overlap: f32) -> (String, usize) {
+    // Selected characters of each line, with the line's top-left corner
+    let mut lines: Vec<(f32, f32, Vec<(f32, char)>)> = Vec::new();
+    for block in text_page.blocks() {
+        for line in block.lines() {
+            let mut chars = Vec::new();
+            let (mut top, mut left) = (f32::INFINITY, f32::INFINITY);
+            for ch in line.chars() {
+                let Some(c) = ch.char() else {
+                    continue;
+                };
+                let bounds = quad_bounds(&ch.quad());
+                let fraction = overlap_fraction(bounds, region);
+                if fraction > 0.0 && fraction >= overlap {
+                    top = top.min(bounds[1]);
+                    left = left.min(bounds[0]);
+                    chars.push((bounds[0], c));
+                }
+            }
+            if !chars.is_empty() {
+                lines.push((top, left, chars));
+            }
+        }
+    }
+
+    // Natural reading order: top to bottom, then left to right
+    lines.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut char_count = 0;
+    let text = lines
+        .into_iter()
+        .map(|(_, _, mut chars)| {
+            chars.sort_by(|a, b| a.0.total_cmp(&b.0));
+            char_count += chars.len();
+            chars.into_iter().map(|(_, c)| c).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (text, char_count)
+}
+
+/// Extract the text whose characters lie inside a rectangle of a page.
+pub fn get_text_for_region(
+    store: &DocumentStore,
+    params: GetTextForRegionParams,
+) -> Result<GetTextForRegionResult> {
+    let region = [params.x0, params.y0, params.x1, params.y1];
+    if !(params.x0 < params.x1 && params.y0 < params.y1) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "region {:?} is empty",
+            region
+        )));
+    }
+    let overlap = params.overlap.unwrap_or(DEFAULT_REGION_OVERLAP);
+    if !(0.0..=1.0).contains(&overlap) {
+        return Err(MupdfServerError::InvalidParams(format!(
+            "overlap must be between 0 and 1, got {}",
+            overlap
+        )));
+    }
+
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let (text, char_count) = region_text(&text_page, region, overlap);
+
+        Ok(GetTextForRegionResult { text, char_count })
+    })
+}
+
+// ============== Get Page Text Diff ==============
+
+/// Parameters for comparing the text of two pages.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextDiffParams {
+    /// Document ID of the original page.
+    pub document_id_a: String,
+    /// Original page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page_a: i32,
+    /// Document ID of the revised page (may equal `document_id_a`).
+    pub document_id_b: String,
+    /// Revised page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page_b: i32,
+    /// Unit of comparison: "line" (default) or "word".
+    #[serde(default)]
+    pub diff_granularity: Option<String>,
+}
+
+/// Differences between the text of two pages.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextDiffResult {
+    /// Units only in the revised page, in order.
+    pub added: Vec<String>,
+    /// Units only in the original page, in order.
+    pub removed: Vec<String>,
+    /// Number of units common to both pages.
+    pub unchanged_lines: usize,
+    /// Similarity from 0 to 1: twice the common units over the total units.
+    pub similarity: f32,
+}
+
+/// Plain text of one page.
+fn page_plain_text(store: &DocumentStore, document_id: &str, page: i32) -> Result<String> {
+    store.with_document(document_id, |doc| {
+        let page_number = validate_page_number(doc, page)?;
+        extract_text_from_page(&doc.load_page(page_number)?, "plain")
+    })
+}
+
+/// Split text into trimmed, non-empty lines or into words.
+fn diff_units(text: &str, by_word: bool) -> Vec<&str> {
+    if by_word {
+        text.split_whitespace().collect()
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+/// Compare two pages' text with a longest-common-subsequence diff.
+pub fn get_page_text_diff(
+    store: &DocumentStore,
+    params: GetPageTextDiffParams,
+) -> Result<GetPageTextDiffResult> {
+    let by_word = match params.diff_granularity.as_deref().unwrap_or("line") {
+        "line" => false,
+        "word" => true,
+        other => {
+            return Err(MupdfServerError::InvalidParams(format!(
+                "diff_granularity must be \"line\" or \"word\", got {:?}",
+                other
+            )))
+        }
+    };
+
+    // Pages are read one after the other; both may come from one document
+    let text_a = page_plain_text(store, &params.document_id_a, params.page_a)?;
+    let text_b = page_plain_text(store, &params.document_id_b, params.page_b)?;
+    let a = diff_units(&text_a, by_word);
+    let b = diff_units(&text_b, by_word);
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            removed.push(a[i].to_string());
+            i += 1;
+        } else {
+            added.push(b[j].to_string());
+            j += 1;
+        }
+    }
+    removed.extend(a[i..].iter().map(|unit| unit.to_string()));
+    added.extend(b[j..].iter().map(|unit| unit.to_string()));
+
+    let common = lcs[0][0];
+    let total = a.len() + b.len();
+    Ok(GetPageTextDiffResult {
+        added,
+        removed,
+        unchanged_lines: common,
+        similarity: if total == 0 {
+            1.0
+        } else {
+            2.0 * common as f32 / total as f32
+        },
+    })
+}
+
+// ============== Search Page Context ==============
+
+/// Default number of context characters on each side of a search hit.
+const DEFAULT_CONTEXT_CHARS: usize = 80;
+
+/// Parameters for searching a page with surrounding text.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchPageContextParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Text to search for (case-insensitive).
+    pub query: String,
+    /// Characters of context on each side of a hit (default 80).
+    #[serde(default)]
+    pub context_chars: Option<usize>,
+}
+
+/// A search hit with the text around it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchHitWithContext {
+    /// Bounding quad of the hit.
+    pub quad: SearchHit,
+    /// Text preceding the hit (lines joined by spaces).
+    pub text_before: String,
+    /// Text following the hit (lines joined by spaces).
+    pub text_after: String,
+    /// Full text of the line containing the hit.
+    pub full_line: String,
+}
+
+/// Result of searching a page with context.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchPageContextResult {
+    /// Search hits in page order.
+    pub hits: Vec<SearchHitWithContext>,
+}
+
+/// A character of a page flattened into reading order.
+struct FlatChar {
+    c: char,
+    /// Index of the line the character belongs to.
+    line: usize,
+    /// Center of the character's quad; `None` for synthetic line breaks.
+    center: Option<(f32, f32)>,
+}
+
+/// Search a page and return each hit with its surrounding text.
+///
+/// Hits come from MuPDF's search; the matched characters are located in
+/// the text page by comparing their centers against the hit quad.
+pub fn search_page_context(
+    store: &DocumentStore,
+    params: SearchPageContextParams,
+) -> Result<SearchPageContextResult> {
+    let context_chars = params.context_chars.unwrap_or(DEFAULT_CONTEXT_CHARS);
+
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let quads = page.search(&params.query, DEFAULT_MAX_HITS)?;
+
+        // Flatten the page; lines are separated by a space without bounds
+        let text_page = page.to_text_page(TextPageFlags::empty())?;
+        let mut chars = Vec::new();
+        let mut lines = Vec::new();
+        for block in text_page.blocks() {
+            for line in block.lines() {
+                let mut text = String::new();
+                for ch in line.chars() {
+                    if let Some(c) = ch.char() {
+                        let [x0, y0, x1, y1] = quad_bounds(&ch.quad());
+                        chars.push(FlatChar {
+                            c,
+                            line: lines.len(),
+                            center: Some(((x0 + x1) / 2.0, (y0 + y1) / 2.0)),
+                        });
+                        text.push(c);
+                    }
+                }
+                chars.push(FlatChar {
+                    c: ' ',
+                    line: lines.len(),
+                    center: None,
+                });
+                lines.push(text);
+            }
+        }
+
+        let collect = |range: std::ops::Range<usize>| chars[range].iter().map(|ch| ch.c).collect();
+
+        let hits = quads
+            .iter()
+            .map(|quad| {
+                let [x0, y0, x1, y1] = quad_bounds(quad);
+                let inside = |ch: &FlatChar| {
+                    ch.center
+                        .is_some_and(|(x, y)| x >= x0 && x <= x1 && y >= y0 && y <= y1)
+                };
+
+                let located = chars.iter().position(inside).map(|first| {
+                    let line = chars[first].line;
+                    let last = (first..chars.len())
+                        .take_while(|&i| chars[i].line == line)
+                        .filter(|&i| inside(&chars[i]))
+                        .last()
+                        .unwrap_or(first);
+                    (first, last, line)
+                });
+
+                match located {
+                    Some((first, last, line)) => SearchHitWithContext {
+                        quad: quad_to_hit(quad),
+                        text_before: collect(first.saturating_sub(context_chars)..first),
+                        text_after: collect(last + 1..(last + 1 + context_chars).min(chars.len())),
+                        full_line: lines[line].clone(),
+                    },
+                    None => SearchHitWithContext {
+                        quad: quad_to_hit(quad),
+                        text_before: String::new(),
+                        text_after: String::new(),
+                        full_line: String::new(),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(SearchPageContextResult { hits })
+    })
+}
+
+// ============== Get Page Text Structured ==============
+
+/// Parameters for extracting a page's text as a structured tree.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPageTextStructuredParams {
+    /// Document ID.
+    pub document_id: String,
+    /// Page number (0-indexed; negative counts from the end, -1 = last page).
+    pub page: i32,
+    /// Include the font name and size of each span (default false).
+    #[serde(default)]
+    pub include_font_info: bool,
+    /// Include the `#rrggbb` color of each character (default false).
+    #[serde(default)]
+    pub include_color: bool,
+}
+
+/// Result of extracting a structured text tree.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GetPageTextStructuredResult {
+    /// `{width, height, blocks: [{type, bbox, lines: [{bbox, wmode, spans:
+    /// [{font, size, chars: [{c, bbox, origin, color}]}]}]}]}`.
+    pub tree: Value,
+}
+
+/// A partially built tree node: its fields and its children.
+type XmlNode = (Map<String, Value>, Vec<Value>);
+
+/// Read the attributes of an XML element as unescaped strings.
+fn xml_attributes(element: &BytesStart) -> Result<HashMap<String, String>> {
+    element
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute.map_err(|e| {
+                MupdfServerError::internal(format!("Invalid stext XML attribute: {}", e))
+            })?;
+            let value = attribute.unescape_value().map_err(|e| {
+                MupdfServerError::internal(format!("Invalid stext XML attribute: {}", e))
+            })?;
+            Ok((
+                String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                value.into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// Parse a whitespace-separated list of numbers from an attribute.
+fn attribute_numbers(attributes: &HashMap<String, String>, name: &str) -> Vec<f64> {
+    attributes
+        .get(name)
+        .map(|value| {
+            value
+                .split_whitespace()
+                .filter_map(|n| n.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bounding box `[x0, y0, x1, y1]` of a character's `quad` attribute.
+fn quad_attribute_bbox(attributes: &HashMap<String, String>) -> Value {
+    let quad = attribute_numbers(attributes, "quad");
+    if quad.len() != 8 {
+        return Value::Null;
+    }
+    let xs = [quad[0], quad[2], quad[4], quad[6]];
+    let ys = [quad[1], quad[3], quad[5], quad[7]];
+    json!([
+        xs.iter().copied().fold(f64::INFINITY, f64::min),
+        ys.iter().copied().fold(f64::INFINITY, f64::min),
+        xs.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        ys.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    ])
+}
+
+/// Finish a node, storing its children under `children_key`.
+fn close_node(node: Option<XmlNode>, children_key: &str) -> Option<Value> {
+    node.map(|(mut fields, children)| {
+        fields.insert(children_key.to_string(), Value::Array(children));
+        Value::Object(fields)
+    })
+}
+
+/// Convert MuPDF's stext XML into a `page → blocks → lines → spans → chars`
+/// JSON tree.
+fn stext_xml_to_tree(xml: &str, include_font_info: bool, include_color: bool) -> Result<Value> {
+    let mut reader = Reader::from_str(xml);
+    let mut page = Map::new();
+    let mut blocks = Vec::new();
+    let mut block: Option<XmlNode> = None;
+    let mut line: Option<XmlNode> = None;
+    let mut span: Option<XmlNode> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| MupdfServerError::internal(format!("Invalid stext XML: {}", e)))?;
+        match event {
+            Event::Start(element) => {
+                let attributes = xml_attributes(&element)?;
+                match element.name().as_ref() {
+                    b"page" => {
+                        for key in ["width", "height"] {
+                            if let Some(&value) = attribute_numbers(&attributes, key).first() {
+                                page.insert(key.to_string(), json!(value));
+                            }
+                        }
+                    }
+                    b"block" => {
+                        let mut fields = Map::new();
+                        fields.insert("type".to_string(), json!("text"));
+                        fields.insert(
+                            "bbox".to_string(),
+                            json!(attribute_numbers(&attributes, "bbox")),
+                        );
+                        block = Some((fields, Vec::new()));
+                    }
+                    b"line" => {
+                        let mut fields = Map::new();
+                        fields.insert(
+                            "bbox".to_string(),
+                            json!(attribute_numbers(&attributes, "bbox")),
+                        );
+                        if let Some(&wmode) = attribute_numbers(&attributes, "wmode").first() {
+                            fields.insert("wmode".to_string(), json!(wmode as i32));
+                        }
+                        line = Some((fields, Vec::new()));
+                    }
+                    b"font" => {
+                        let mut fields = Map::new();
+                        if include_font_info {
+                            if let Some(name) = attributes.get("name") {
+                                fields.insert("font".to_string(), json!(name));
+                            }
+                            if let Some(&size) = attribute_numbers(&attributes, "size").first() {
+                                fields.insert("size".to_string(), json!(size));
+                            }
+                        }
+                        span = Some((fields, Vec::new()));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Empty(element) => {
+                let attributes = xml_attributes(&element)?;
+                match element.name().as_ref() {
+                    b"char" => {
+                        let mut fields = Map::new();
+                        fields.insert(
+                            "c".to_string(),
+                            json!(attributes.get("c").cloned().unwrap_or_default()),
+                        );
+                        fields.insert("bbox".to_string(), quad_attribute_bbox(&attributes));
+                        let x = attribute_numbers(&attributes, "x");
+                        let y = attribute_numbers(&attributes, "y");
+                        if let (Some(x), Some(y)) = (x.first(), y.first()) {
+                            fields.insert("origin".to_string(), json!([x, y]));
+                        }
+                        if include_color {
+                            if let Some(color) = attributes.get("color") {
+                                fields.insert("color".to_string(), json!(color));
+                            }
+                        }
+                        span.get_or_insert_with(|| (Map::new(), Vec::new()))
+                            .1
+                            .push(Value::Object(fields));
+                    }
+                    b"image" => blocks.push(json!({
+                        "type": "image",
+                        "bbox": attribute_numbers(&attributes, "bbox"),
+                    })),
+                    _ => {}
+                }
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"font" => {
+                    if let (Some(value), Some((_, spans))) =
+                        (close_node(span.take(), "chars"), line.as_mut())
+                    {
+                        spans.push(value);
+                    }
+                }
+                b"line" => {
+                    if let Some(value) = close_node(span.take(), "chars") {
+                        if let Some((_, spans)) = line.as_mut() {
+                            spans.push(value);
+                        }
+                    }
+                    if let (Some(value), Some((_, lines))) =
+                        (close_node(line.take(), "spans"), block.as_mut())
+                    {
+                        lines.push(value);
+                    }
+                }
+                b"block" => blocks.extend(close_node(block.take(), "lines")),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    page.insert("blocks".to_string(), Value::Array(blocks));
+    Ok(Value::Object(page))
+}
+
+/// Extract a page's text as a JSON tree parsed from MuPDF's stext XML.
+pub fn get_page_text_structured(
+    store: &DocumentStore,
+    params: GetPageTextStructuredParams,
+) -> Result<GetPageTextStructuredResult> {
+    store.with_document(&params.document_id, |doc| {
+        let page_number = validate_page_number(doc, params.page)?;
+        let page = doc.load_page(page_number)?;
+        let xml = page.to_text_page(TextPageFlags::empty())?.to_xml(0)?;
+
+        Ok(GetPageTextStructuredResult {
+            tree: stext_xml_to_tree(&xml, params.include_font_info, params.include_color)?,
+        })
     })
 }
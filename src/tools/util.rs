@@ -0,0 +1,16 @@
+//! Shared helpers for tool implementations.
+
+use crate::error::Result;
+
+/// Resolve a possibly negative page index against a document.
+///
+/// Negative indices count from the end, Python style: `-1` is the last
+/// page, `-2` the one before it. Non-negative indices are returned as is;
+/// range checking is left to the caller.
+pub(crate) fn resolve_page_index(doc: &mupdf::Document, page: i32) -> Result<i32> {
+    if page < 0 {
+        Ok(page + doc.page_count()?)
+    } else {
+        Ok(page)
+    }
+}
@@ -4,14 +4,41 @@
 
 use mupdf_rs_mcp_server::state::DocumentStore;
 use mupdf_rs_mcp_server::tools::*;
+use mupdf_rs_mcp_server::{MupdfServer, MupdfServerError};
 
 /// Path to test PDF file.
 const DUMMY_PDF: &[u8] = include_bytes!("fixtures/dummy.pdf");
 
-/// Path to encrypted test PDF file (for future password tests).
-#[allow(dead_code)]
+/// Path to encrypted test PDF file.
 const DUMMY_ENCRYPTED_PDF: &[u8] = include_bytes!("fixtures/dummy-encrypted.pdf");
 
+/// Test PDF with a single embedded image.
+const IMAGE_PDF: &[u8] = include_bytes!("fixtures/image.pdf");
+
+/// Test PDF with page labels (i, ii, A-1).
+const LABELS_PDF: &[u8] = include_bytes!("fixtures/labels.pdf");
+
+/// Test PDF with an embedded `data.csv` attachment.
+const ATTACHMENT_PDF: &[u8] = include_bytes!("fixtures/attachment.pdf");
+
+/// Test PDF with a text field `name` set to "Alice".
+const FORM_PDF: &[u8] = include_bytes!("fixtures/form.pdf");
+
+/// Test PDF with a 200x100 page rotated by 90 degrees.
+const ROTATED_PDF: &[u8] = include_bytes!("fixtures/rotated.pdf");
+
+/// Tagged test PDF whose structure tree is Document > H1, P.
+const TAGGED_PDF: &[u8] = include_bytes!("fixtures/tagged.pdf");
+
+/// Test PDF with a single empty A4 page.
+const BLANK_PDF: &[u8] = include_bytes!("fixtures/blank.pdf");
+
+/// Test PDF with one line "Plain bold text" whose "bold" is red Helvetica-Bold.
+const STYLED_PDF: &[u8] = include_bytes!("fixtures/styled.pdf");
+
+/// Test PDF with a full-width heading above two columns of two lines each.
+const COLUMNS_PDF: &[u8] = include_bytes!("fixtures/columns.pdf");
+
 // ============== Session Management Tests ==============
 
 mod session {
@@ -50,7 +77,7 @@ mod session {
         let store = DocumentStore::new();
 
         // Initially empty
-        let list = list_documents(&store, ListDocumentsParams {}).unwrap();
+        let list = list_documents(&store, ListDocumentsParams::default()).unwrap();
         assert!(list.documents.is_empty());
 
         // Import a document
@@ -69,9 +96,11 @@ mod session {
         .unwrap();
 
         // Now should have one document
-        let list = list_documents(&store, ListDocumentsParams {}).unwrap();
+        let list = list_documents(&store, ListDocumentsParams::default()).unwrap();
         assert_eq!(list.documents.len(), 1);
         assert_eq!(list.documents[0].document_id, import_result.document_id);
+        assert_eq!(list.documents[0].filename, Some("dummy.pdf".to_string()));
+        assert_eq!(list.documents[0].source_hint, "base64:dummy.pdf");
 
         // Clean up
         close_document(
@@ -112,135 +141,308 @@ mod session {
         assert!(close_result.success);
 
         // Should be empty now
-        let list = list_documents(&store, ListDocumentsParams {}).unwrap();
+        let list = list_documents(&store, ListDocumentsParams::default()).unwrap();
         assert!(list.documents.is_empty());
     }
 
     #[test]
-    fn test_close_nonexistent_document() {
+    fn test_close_all_documents() {
         let store = DocumentStore::new();
+        let import = || {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            DUMMY_PDF,
+                        ),
+                        filename: Some("dummy.pdf".to_string()),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap()
+            .document_id
+        };
+        let mut ids = vec![import(), import(), import()];
+        ids.sort();
 
-        let result = close_document(
+        // Nothing has been idle for an hour
+        let idle = close_all_documents(
             &store,
-            CloseDocumentParams {
-                document_id: "nonexistent-id".to_string(),
+            CloseAllDocumentsParams {
+                older_than_seconds: Some(3600),
             },
-        );
-
-        assert!(result.is_err());
-    }
-}
-
-// ============== Document Operations Tests ==============
+        )
+        .unwrap();
+        assert_eq!(idle.closed_count, 0);
+        assert_eq!(store.len().unwrap(), 3);
 
-mod document {
-    use super::*;
+        let result = close_all_documents(&store, CloseAllDocumentsParams::default()).unwrap();
+        assert_eq!(result.closed_count, 3);
+        assert_eq!(result.document_ids, ids);
 
-    fn setup_document(store: &DocumentStore) -> String {
-        let base64_content =
-            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
-        import_document(
-            store,
-            ImportDocumentParams {
-                source: DocumentSource::Base64 {
-                    base64: base64_content,
-                    filename: Some("dummy.pdf".to_string()),
-                },
-                password: None,
-            },
-        )
-        .unwrap()
-        .document_id
+        let list = list_documents(&store, ListDocumentsParams::default()).unwrap();
+        assert!(list.documents.is_empty());
     }
 
     #[test]
-    fn test_get_page_count() {
+    fn test_import_path_after_edit() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dummy.pdf");
+        let import = || {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::FilePath {
+                        path: path.to_string(),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap()
+            .document_id
+        };
 
-        let result = get_page_count(
+        let edited = import();
+        rotate_pages(
             &store,
-            GetPageCountParams {
-                document_id: doc_id.clone(),
+            RotatePagesParams {
+                document_id: edited.clone(),
+                pages: vec![0],
+                degrees: 90,
             },
         )
         .unwrap();
 
-        assert!(result.page_count > 0);
+        // The edited document no longer matches the file on disk
+        let fresh = import();
+        assert_ne!(edited, fresh);
+        assert_eq!(
+            get_page_rotation(
+                &store,
+                GetPageRotationParams {
+                    document_id: fresh.clone(),
+                    page: 0,
+                },
+            )
+            .unwrap()
+            .rotation_degrees,
+            0
+        );
 
-        close_document(
-            &store,
-            CloseDocumentParams {
-                document_id: doc_id,
-            },
-        )
-        .unwrap();
+        for document_id in [edited, fresh] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
     }
 
     #[test]
-    fn test_get_metadata() {
+    fn test_import_encrypted_path_checks_password() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/dummy-encrypted.pdf"
+        );
+        let import = |password: &str| {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::FilePath {
+                        path: path.to_string(),
+                    },
+                    password: Some(password.to_string()),
+                },
+            )
+        };
 
-        let result = get_metadata(
-            &store,
-            GetMetadataParams {
-                document_id: doc_id.clone(),
-            },
-        )
-        .unwrap();
+        let unlocked = import("").unwrap();
 
-        // The metadata may or may not be present, but the call should succeed
-        // Just verify it returns a valid result structure
-        let _ = result.title;
-        let _ = result.author;
-        let _ = result.subject;
+        // An unlocked copy of the file must not be handed out without the password
+        assert!(matches!(
+            import("wrong"),
+            Err(MupdfServerError::InvalidPassword)
+        ));
+
+        // Encrypted files are not shared between imports
+        let second = import("").unwrap();
+        assert_ne!(unlocked.document_id, second.document_id);
+
+        for document_id in [unlocked.document_id, second.document_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_import_same_path_twice() {
+        let store = DocumentStore::new();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dummy.pdf");
+
+        let import = || {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::FilePath {
+                        path: path.to_string(),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap()
+        };
+
+        let first = import();
+        let second = import();
+        assert_eq!(first.document_id, second.document_id);
+        assert_eq!(first.page_count, second.page_count);
+        assert_eq!(store.len().unwrap(), 1);
+
+        let list = list_documents(&store, ListDocumentsParams::default()).unwrap();
+        assert_eq!(list.documents[0].filename, Some("dummy.pdf".to_string()));
 
         close_document(
             &store,
             CloseDocumentParams {
-                document_id: doc_id,
+                document_id: first.document_id,
             },
         )
         .unwrap();
     }
 
     #[test]
-    fn test_get_outlines() {
+    fn test_list_documents_pagination() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
 
-        let result = get_outlines(
+        let mut imported = Vec::new();
+        for _ in 0..5 {
+            let result = import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64_content.clone(),
+                        filename: Some("dummy.pdf".to_string()),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap();
+            imported.push(result.document_id);
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = list_documents(
+                &store,
+                ListDocumentsParams {
+                    cursor: cursor.take(),
+                    limit: Some(2),
+                    sort_by: None,
+                    descending: false,
+                    tag_filter: None,
+                },
+            )
+            .unwrap();
+            assert_eq!(page.total, 5);
+            assert!(page.documents.len() <= 2);
+            seen.extend(page.documents.into_iter().map(|d| d.document_id));
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen.len(), 5);
+        let mut unique = seen.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 5);
+        imported.sort();
+        assert_eq!(unique, imported);
+    }
+
+    #[test]
+    fn test_list_documents_access_count() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let result = import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64_content.clone(),
+                        filename: Some("dummy.pdf".to_string()),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap();
+            ids.push(result.document_id);
+        }
+
+        get_page_count(
             &store,
-            GetOutlinesParams {
-                document_id: doc_id.clone(),
+            GetPageCountParams {
+                document_id: ids[0].clone(),
             },
         )
         .unwrap();
 
-        // May or may not have outlines
-        let _ = result.outlines;
+        let list = |sort_by: &str, descending| {
+            list_documents(
+                &store,
+                ListDocumentsParams {
+                    cursor: None,
+                    limit: None,
+                    sort_by: Some(sort_by.to_string()),
+                    descending,
+                    tag_filter: None,
+                },
+            )
+            .unwrap()
+        };
 
-        close_document(
+        let result = list("access_count", true);
+        assert_eq!(result.documents[0].document_id, ids[0]);
+        assert!(result.documents[0].access_count >= 1);
+        assert!(result.documents[0].access_count > result.documents[1].access_count);
+        assert_eq!(list("access_count", false).documents[0].document_id, ids[1]);
+
+        let invalid = list_documents(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            ListDocumentsParams {
+                cursor: None,
+                limit: None,
+                sort_by: Some("name".to_string()),
+                descending: false,
+                tag_filter: None,
             },
-        )
-        .unwrap();
+        );
+        assert!(matches!(invalid, Err(MupdfServerError::InvalidParams(_))));
     }
-}
 
-// ============== Page Operations Tests ==============
+    #[test]
+    fn test_get_store_stats() {
+        let store = DocumentStore::new();
+        let started_at = std::time::Instant::now();
 
-mod page {
-    use super::*;
+        let empty = get_store_stats(&store, started_at).unwrap();
+        assert_eq!(empty.document_count, 0);
+        assert_eq!(empty.total_estimated_memory_bytes, 0);
+        assert!(empty.oldest_document_age_seconds.is_none());
+        assert!(empty.most_accessed_count.is_none());
+        assert!(empty.server_uptime_seconds < 60);
 
-    fn setup_document(store: &DocumentStore) -> String {
         let base64_content =
             base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
         import_document(
-            store,
+            &store,
             ImportDocumentParams {
                 source: DocumentSource::Base64 {
                     base64: base64_content,
@@ -249,214 +451,336 @@ mod page {
                 password: None,
             },
         )
-        .unwrap()
-        .document_id
+        .unwrap();
+
+        let stats = get_store_stats(&store, started_at).unwrap();
+        assert_eq!(stats.document_count, 1);
+        assert_eq!(stats.total_estimated_memory_bytes, DUMMY_PDF.len());
+        assert!(stats.oldest_document_age_seconds.is_some());
     }
 
     #[test]
-    fn test_get_page_bounds() {
+    fn test_document_tags() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
 
-        let result = get_page_bounds(
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let result = import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64_content.clone(),
+                        filename: Some("dummy.pdf".to_string()),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap();
+            ids.push(result.document_id);
+        }
+
+        set_document_tag(
             &store,
-            GetPageBoundsParams {
-                document_id: doc_id.clone(),
-                page: 0,
+            SetDocumentTagParams {
+                document_id: ids[0].clone(),
+                key: "name".to_string(),
+                value: "contract_jan2024".to_string(),
             },
         )
         .unwrap();
 
-        assert!(result.width > 0.0);
-        assert!(result.height > 0.0);
-
-        close_document(
+        let tag = get_document_tag(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            GetDocumentTagParams {
+                document_id: ids[0].clone(),
+                key: "name".to_string(),
             },
         )
         .unwrap();
-    }
-
-    #[test]
-    fn test_get_page_bounds_invalid_page() {
-        let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        let result = get_page_bounds(
-            &store,
-            GetPageBoundsParams {
-                document_id: doc_id.clone(),
-                page: 9999, // Invalid page
-            },
-        );
-
-        assert!(result.is_err());
+        assert_eq!(tag.value.as_deref(), Some("contract_jan2024"));
 
-        close_document(
+        let missing = get_document_tag(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            GetDocumentTagParams {
+                document_id: ids[1].clone(),
+                key: "name".to_string(),
             },
         )
         .unwrap();
-    }
+        assert!(missing.value.is_none());
 
-    #[test]
-    fn test_get_page_links() {
-        let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        let result = get_page_links(
+        let tags = list_document_tags(
             &store,
-            GetPageLinksParams {
-                document_id: doc_id.clone(),
-                page: 0,
+            ListDocumentTagsParams {
+                document_id: ids[0].clone(),
             },
         )
         .unwrap();
+        assert_eq!(tags.tags.len(), 1);
 
-        // May or may not have links
-        let _ = result.links;
-
-        close_document(
+        let filter =
+            std::collections::HashMap::from([("name".to_string(), "contract_jan2024".to_string())]);
+        let listed = list_documents(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            ListDocumentsParams {
+                cursor: None,
+                limit: None,
+                sort_by: None,
+                descending: false,
+                tag_filter: Some(filter),
             },
         )
         .unwrap();
+        assert_eq!(listed.total, 1);
+        assert_eq!(listed.documents[0].document_id, ids[0]);
+        assert_eq!(listed.documents[0].tags["name"], "contract_jan2024");
     }
-}
-
-// ============== Text Extraction Tests ==============
-
-mod text {
-    use super::*;
 
-    fn setup_document(store: &DocumentStore) -> String {
-        let base64_content =
-            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
-        import_document(
-            store,
+    #[test]
+    fn test_export_document() {
+        let dir = std::env::temp_dir().join(format!("mupdf-mcp-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = DocumentStore::new().allowed_paths(vec![dir.clone()]);
+        let doc_id = import_document(
+            &store,
             ImportDocumentParams {
                 source: DocumentSource::Base64 {
-                    base64: base64_content,
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        DUMMY_PDF,
+                    ),
                     filename: Some("dummy.pdf".to_string()),
                 },
                 password: None,
             },
         )
         .unwrap()
-        .document_id
+        .document_id;
+        let export = |output_path: &std::path::Path, overwrite: bool| {
+            export_document(
+                &store,
+                ExportDocumentParams {
+                    document_id: doc_id.clone(),
+                    output_path: output_path.to_string_lossy().into_owned(),
+                    overwrite,
+                },
+            )
+        };
+        let output_path = dir.join("export.pdf");
+
+        let result = export(&output_path, false).unwrap();
+        let bytes = std::fs::read(&result.path).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+        assert_eq!(bytes.len() as u64, result.size_bytes);
+
+        assert!(matches!(
+            export(&output_path, false),
+            Err(MupdfServerError::FileAlreadyExists(_))
+        ));
+        assert!(export(&output_path, true).is_ok());
+
+        assert!(matches!(
+            export(&std::env::temp_dir().join("mupdf-mcp-outside.pdf"), true),
+            Err(MupdfServerError::PathNotAllowed(_))
+        ));
+
+        // A dangling symlink must not redirect the write outside the directory
+        #[cfg(unix)]
+        {
+            let outside = std::env::temp_dir().join(format!(
+                "mupdf-mcp-export-target-{}.pdf",
+                std::process::id()
+            ));
+            let link = dir.join("link.pdf");
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(matches!(
+                export(&link, true),
+                Err(MupdfServerError::PathNotAllowed(_))
+            ));
+            assert!(!outside.exists());
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_get_page_text_plain() {
+    fn test_export_document_requires_allowed_paths() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        let result = get_page_text(
+        let doc_id = import_document(
             &store,
-            GetPageTextParams {
-                document_id: doc_id.clone(),
-                page: 0,
-                format: "plain".to_string(),
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        DUMMY_PDF,
+                    ),
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
             },
         )
-        .unwrap();
-
-        // Text extraction should succeed
-        let _ = result.text;
+        .unwrap()
+        .document_id;
 
-        close_document(
+        let result = export_document(
             &store,
-            CloseDocumentParams {
+            ExportDocumentParams {
                 document_id: doc_id,
+                output_path: std::env::temp_dir()
+                    .join("mupdf-mcp-export.pdf")
+                    .to_string_lossy()
+                    .into_owned(),
+                overwrite: true,
             },
-        )
-        .unwrap();
+        );
+        assert!(matches!(result, Err(MupdfServerError::PathNotAllowed(_))));
     }
 
     #[test]
-    fn test_get_page_text_html() {
+    fn test_import_document_from_file_paths() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/dummy.pdf");
 
-        let result = get_page_text(
+        let single = import_document(
             &store,
-            GetPageTextParams {
-                document_id: doc_id.clone(),
-                page: 0,
-                format: "html".to_string(),
+            ImportDocumentParams {
+                source: DocumentSource::FilePath {
+                    path: path.to_string(),
+                },
+                password: None,
             },
         )
         .unwrap();
-
-        // HTML output should contain HTML tags
-        assert!(result.text.contains("<") || result.text.is_empty());
-
-        close_document(
+        let combined = import_document(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            ImportDocumentParams {
+                source: DocumentSource::FilePaths {
+                    paths: vec![path.to_string(), path.to_string()],
+                },
+                password: None,
             },
         )
         .unwrap();
-    }
 
-    #[test]
-    fn test_get_page_text_json() {
-        let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        assert_ne!(combined.document_id, single.document_id);
+        assert_eq!(combined.page_count, 2 * single.page_count);
 
-        let result = get_page_text(
+        let empty = import_document(
             &store,
-            GetPageTextParams {
-                document_id: doc_id.clone(),
-                page: 0,
-                format: "json".to_string(),
+            ImportDocumentParams {
+                source: DocumentSource::FilePaths { paths: Vec::new() },
+                password: None,
             },
-        )
-        .unwrap();
+        );
+        assert!(matches!(empty, Err(MupdfServerError::InvalidParams(_))));
 
-        // JSON should be valid
-        if !result.text.is_empty() {
-            let parsed: Result<serde_json::Value, _> = serde_json::from_str(&result.text);
-            assert!(parsed.is_ok(), "JSON parsing failed: {}", result.text);
+        for document_id in [single.document_id, combined.document_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
         }
+    }
 
-        close_document(
+    #[test]
+    fn test_import_document_subrange() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LABELS_PDF);
+        let source_id = import_document(
             &store,
-            CloseDocumentParams {
-                document_id: doc_id,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("labels.pdf".to_string()),
+                },
+                password: None,
             },
         )
-        .unwrap();
+        .unwrap()
+        .document_id;
+
+        let subrange = |start_page: i32, end_page: i32| {
+            import_document_subrange(
+                &store,
+                ImportFromSubrangeParams {
+                    source_document_id: source_id.clone(),
+                    start_page,
+                    end_page,
+                },
+            )
+        };
+
+        let first = subrange(0, 0).unwrap();
+        assert_ne!(first.document_id, source_id);
+        assert_eq!(first.page_count, 1);
+        let tail = subrange(1, -1).unwrap();
+        assert_eq!(tail.page_count, 2);
+        assert!(matches!(
+            subrange(2, 1),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+
+        // The source document is left untouched
+        assert_eq!(store.get_info(&source_id).unwrap().page_count, 3);
+        assert_eq!(store.len().unwrap(), 3);
+
+        for document_id in [first.document_id, tail.document_id, source_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
     }
 
     #[test]
-    fn test_search_page() {
+    fn test_snapshot_round_trip() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        // Search for a common word that might be in the document
-        let result = search_page(
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        let doc_id = import_document(
             &store,
-            SearchPageParams {
-                document_id: doc_id.clone(),
-                page: 0,
-                query: "the".to_string(),
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
             },
         )
-        .unwrap();
+        .unwrap()
+        .document_id;
+        store
+            .set_tag(&doc_id, "project".to_string(), "alpha".to_string())
+            .unwrap();
 
-        // Results may or may not be found
-        let _ = result.hits;
+        let snapshot_path = std::env::temp_dir().join(format!(
+            "mupdf-mcp-snapshot-{}-{}.zst",
+            std::process::id(),
+            doc_id
+        ));
+        store.export_snapshot(&snapshot_path).unwrap();
+
+        let restored = DocumentStore::new();
+        assert_eq!(restored.import_snapshot(&snapshot_path).unwrap(), 1);
+        // Documents already in the store are not loaded twice
+        assert_eq!(restored.import_snapshot(&snapshot_path).unwrap(), 0);
+        std::fs::remove_file(&snapshot_path).unwrap();
+
+        let list = list_documents(&restored, ListDocumentsParams::default()).unwrap();
+        assert_eq!(list.documents.len(), 1);
+        assert_eq!(list.documents[0].document_id, doc_id);
+        assert_eq!(list.documents[0].filename, Some("dummy.pdf".to_string()));
+        assert_eq!(list.documents[0].tags.get("project").unwrap(), "alpha");
+
+        let page_count = get_page_count(
+            &restored,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+        assert_eq!(page_count, 1);
 
         close_document(
-            &store,
+            &restored,
             CloseDocumentParams {
                 document_id: doc_id,
             },
@@ -465,21 +789,34 @@ mod text {
     }
 
     #[test]
-    fn test_get_page_text_blocks() {
+    fn test_snapshot_skips_encrypted_documents() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        let result = get_page_text_blocks(
+        let doc_id = import_document(
             &store,
-            GetPageTextBlocksParams {
-                document_id: doc_id.clone(),
-                page: 0,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        DUMMY_ENCRYPTED_PDF,
+                    ),
+                    filename: Some("dummy-encrypted.pdf".to_string()),
+                },
+                password: Some(String::new()),
             },
         )
-        .unwrap();
+        .unwrap()
+        .document_id;
 
-        // Should have some blocks
-        let _ = result.blocks;
+        let snapshot_path = std::env::temp_dir().join(format!(
+            "mupdf-mcp-snapshot-{}-{}.zst",
+            std::process::id(),
+            doc_id
+        ));
+        store.export_snapshot(&snapshot_path).unwrap();
+
+        let restored = DocumentStore::new();
+        assert_eq!(restored.import_snapshot(&snapshot_path).unwrap(), 0);
+        std::fs::remove_file(&snapshot_path).unwrap();
 
         close_document(
             &store,
@@ -489,18 +826,14 @@ mod text {
         )
         .unwrap();
     }
-}
-
-// ============== Render Tests ==============
-
-mod render {
-    use super::*;
 
-    fn setup_document(store: &DocumentStore) -> String {
+    #[test]
+    fn test_copy_document() {
+        let store = DocumentStore::new();
         let base64_content =
             base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
-        import_document(
-            store,
+        let doc_id = import_document(
+            &store,
             ImportDocumentParams {
                 source: DocumentSource::Base64 {
                     base64: base64_content,
@@ -510,147 +843,4990 @@ mod render {
             },
         )
         .unwrap()
-        .document_id
-    }
-
-    #[test]
-    fn test_render_page() {
-        let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
+        .document_id;
 
-        let result = render_page(
+        let copy = copy_document(
             &store,
-            RenderPageParams {
+            CopyDocumentParams {
                 document_id: doc_id.clone(),
-                page: 0,
-                scale: 1.0,
             },
         )
         .unwrap();
+        assert_ne!(copy.new_document_id, doc_id);
 
-        // Should return valid PNG data
-        assert!(!result.image.is_empty());
-        assert!(result.width > 0);
-        assert!(result.height > 0);
-
-        // Verify it's valid base64
-        let decoded =
-            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.image);
-        assert!(decoded.is_ok());
+        let listed = list_documents(&store, ListDocumentsParams::default()).unwrap();
+        let ids: Vec<_> = listed.documents.iter().map(|d| &d.document_id).collect();
+        assert!(ids.contains(&&doc_id));
+        assert!(ids.contains(&&copy.new_document_id));
 
-        // Verify PNG magic bytes
-        let bytes = decoded.unwrap();
-        assert!(bytes.len() > 8);
-        assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]); // PNG signature
+        let count = |document_id: &str| {
+            get_page_count(
+                &store,
+                GetPageCountParams {
+                    document_id: document_id.to_string(),
+                },
+            )
+            .unwrap()
+            .page_count
+        };
+        assert_eq!(count(&doc_id), count(&copy.new_document_id));
+        assert_eq!(copy.page_count, count(&doc_id));
 
-        close_document(
-            &store,
-            CloseDocumentParams {
-                document_id: doc_id,
-            },
-        )
-        .unwrap();
+        for document_id in [doc_id, copy.new_document_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
     }
 
     #[test]
-    fn test_render_page_with_scale() {
+    fn test_copy_encrypted_document() {
         let store = DocumentStore::new();
-        let doc_id = setup_document(&store);
-
-        let result_1x = render_page(
+        let base64_content = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            DUMMY_ENCRYPTED_PDF,
+        );
+        let doc_id = import_document(
             &store,
-            RenderPageParams {
-                document_id: doc_id.clone(),
-                page: 0,
-                scale: 1.0,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy-encrypted.pdf".to_string()),
+                },
+                password: Some(String::new()),
             },
         )
-        .unwrap();
+        .unwrap()
+        .document_id;
 
-        let result_2x = render_page(
+        // The copy is written decrypted, so it opens without the password
+        let copy = copy_document(
             &store,
-            RenderPageParams {
+            CopyDocumentParams {
                 document_id: doc_id.clone(),
-                page: 0,
-                scale: 2.0,
             },
         )
         .unwrap();
+        assert_eq!(copy.page_count, 1);
 
-        // 2x scale should produce larger dimensions
-        assert_eq!(result_2x.width, result_1x.width * 2);
-        assert_eq!(result_2x.height, result_1x.height * 2);
+        for document_id in [doc_id, copy.new_document_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
 
-        close_document(
+    #[test]
+    fn test_close_nonexistent_document() {
+        let store = DocumentStore::new();
+
+        let result = close_document(
             &store,
             CloseDocumentParams {
-                document_id: doc_id,
+                document_id: "nonexistent-id".to_string(),
             },
-        )
-        .unwrap();
+        );
+
+        assert!(result.is_err());
     }
-}
 
-// ============== Oneshot Tests ==============
+    #[test]
+    fn test_document_source_url_deserialization() {
+        let source: DocumentSource = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com/report.pdf",
+            "filename": "report.pdf"
+        }))
+        .unwrap();
 
-mod oneshot {
-    use super::*;
+        match source {
+            DocumentSource::Url {
+                url,
+                filename,
+                timeout_seconds,
+            } => {
+                assert_eq!(url, "https://example.com/report.pdf");
+                assert_eq!(filename.as_deref(), Some("report.pdf"));
+                assert_eq!(timeout_seconds, None);
+            }
+            other => panic!("expected Url source, got {:?}", other),
+        }
+    }
 
     #[test]
-    fn test_oneshot_get_bookmarks() {
-        let base64_content =
-            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+    fn test_import_document_from_unreachable_url() {
+        let store = DocumentStore::new();
 
-        let result = oneshot_get_bookmarks(OneshotGetBookmarksParams {
-            source: DocumentSource::Base64 {
-                base64: base64_content,
-                filename: Some("dummy.pdf".to_string()),
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Url {
+                    url: "http://127.0.0.1:1/dummy.pdf".to_string(),
+                    filename: None,
+                    timeout_seconds: Some(5),
+                },
+                password: None,
             },
-            password: None,
-        })
-        .unwrap();
+        );
 
-        // Should return page count
-        assert!(result.page_count > 0);
-        // Bookmarks may or may not exist
-        let _ = result.bookmarks;
+        assert!(matches!(result, Err(MupdfServerError::HttpError(_))));
+        assert!(store.is_empty().unwrap());
     }
-}
 
-// ============== Error Handling Tests ==============
+    #[tokio::test]
+    async fn test_import_url_on_current_thread_runtime() {
+        let store = DocumentStore::new();
 
-mod errors {
-    use super::*;
+        // Downloading must not panic on a current-thread runtime
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Url {
+                    url: "http://127.0.0.1:1/dummy.pdf".to_string(),
+                    filename: None,
+                    timeout_seconds: Some(5),
+                },
+                password: None,
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::HttpError(_))));
+    }
 
     #[test]
-    fn test_document_not_found() {
-        let store = DocumentStore::new();
+    fn test_import_url_too_large() {
+        use std::io::{Read, Write};
 
-        let result = get_page_count(
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            // Announce a body above the download limit without sending it
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000000000000\r\n\r\n")
+                .unwrap();
+        });
+
+        let store = DocumentStore::new();
+        let result = import_document(
             &store,
-            GetPageCountParams {
-                document_id: "nonexistent-doc-id".to_string(),
+            ImportDocumentParams {
+                source: DocumentSource::Url {
+                    url: format!("http://{}/huge.pdf", addr),
+                    filename: None,
+                    timeout_seconds: Some(5),
+                },
+                password: None,
             },
         );
+        assert!(matches!(result, Err(MupdfServerError::PayloadTooLarge(_))));
+        assert!(store.is_empty().unwrap());
+        server.join().unwrap();
+    }
 
-        assert!(result.is_err());
+    #[test]
+    fn test_import_url_generic_content_type() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            // A generic content type must not be used as the document format
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n",
+                        DUMMY_PDF.len()
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            stream.write_all(DUMMY_PDF).unwrap();
+        });
+
+        let store = DocumentStore::new();
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Url {
+                    url: format!("http://{}/download", addr),
+                    filename: None,
+                    timeout_seconds: Some(5),
+                },
+                password: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.page_count, 1);
+        server.join().unwrap();
     }
 
     #[test]
-    fn test_invalid_base64() {
+    fn test_import_url_disabled_with_allowed_paths() {
+        let source = DocumentSource::Url {
+            url: "http://127.0.0.1:1/dummy.pdf".to_string(),
+            filename: None,
+            timeout_seconds: Some(5),
+        };
+        let import = |store: &DocumentStore| {
+            import_document(
+                store,
+                ImportDocumentParams {
+                    source: source.clone(),
+                    password: None,
+                },
+            )
+        };
+
+        let sandboxed = DocumentStore::new().allowed_paths(vec![std::env::temp_dir()]);
+        assert!(!sandboxed.url_sources_allowed());
+        assert!(matches!(
+            import(&sandboxed),
+            Err(MupdfServerError::UrlNotAllowed(_))
+        ));
+
+        // Explicitly re-enabled URLs are downloaded again
+        let opted_in = sandboxed.allow_url_sources(true);
+        assert!(matches!(
+            import(&opted_in),
+            Err(MupdfServerError::HttpError(_))
+        ));
+
+        let disabled = DocumentStore::new().allow_url_sources(false);
+        assert!(matches!(
+            import(&disabled),
+            Err(MupdfServerError::UrlNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_chunked_upload() {
         let store = DocumentStore::new();
+        let engine = &base64::engine::general_purpose::STANDARD;
+        // Split at a multiple of 3 so each chunk encodes without padding
+        let (first, second) = DUMMY_PDF.split_at(DUMMY_PDF.len() / 2 / 3 * 3);
 
-        let result = import_document(
+        for (chunk_index, chunk) in [first, second].into_iter().enumerate() {
+            let result = upload_chunk(
+                &store,
+                UploadChunkParams {
+                    stream_id: "upload-1".to_string(),
+                    chunk_index: chunk_index as u32,
+                    data: base64::Engine::encode(engine, chunk),
+                },
+            )
+            .unwrap();
+            assert_eq!(result.chunks_received, chunk_index as u32 + 1);
+        }
+
+        let result = finalize_stream(
+            &store,
+            FinalizeStreamParams {
+                stream_id: "upload-1".to_string(),
+                filename: Some("dummy.pdf".to_string()),
+                password: None,
+            },
+        )
+        .unwrap();
+
+        let single = import_document(
             &store,
             ImportDocumentParams {
                 source: DocumentSource::Base64 {
-                    base64: "not-valid-base64!!!".to_string(),
-                    filename: Some("test.pdf".to_string()),
+                    base64: base64::Engine::encode(engine, DUMMY_PDF),
+                    filename: Some("dummy.pdf".to_string()),
                 },
                 password: None,
             },
+        )
+        .unwrap();
+        assert_eq!(result.page_count, single.page_count);
+        assert_eq!(
+            store.get_info(&result.document_id).unwrap().size_bytes,
+            DUMMY_PDF.len()
         );
 
-        assert!(result.is_err());
+        // The upload is consumed by finalizing
+        let again = finalize_stream(
+            &store,
+            FinalizeStreamParams {
+                stream_id: "upload-1".to_string(),
+                filename: None,
+                password: None,
+            },
+        );
+        assert!(matches!(again, Err(MupdfServerError::InvalidParams(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrent_access_to_two_documents() {
+        let store = DocumentStore::new();
+        let import = |filename: &str| {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            DUMMY_PDF,
+                        ),
+                        filename: Some(filename.to_string()),
+                    },
+                    password: None,
+                },
+            )
+            .unwrap()
+            .document_id
+        };
+        let ids = [import("first.pdf"), import("second.pdf")];
+
+        let tasks: Vec<_> = (0..16)
+            .map(|i| {
+                let store = store.clone();
+                let document_id = ids[i % 2].clone();
+                tokio::spawn(async move {
+                    get_page_text(
+                        &store,
+                        GetPageTextParams {
+                            document_id,
+                            page: 0,
+                            format: "plain".to_string(),
+                        },
+                    )
+                    .map(|result| result.text)
+                })
+            })
+            .collect();
+
+        tokio::time::timeout(std::time::Duration::from_secs(30), async {
+            for task in tasks {
+                assert!(task.await.unwrap().unwrap().contains("Dummy"));
+            }
+        })
+        .await
+        .expect("concurrent document access did not complete");
+
+        for id in ids {
+            assert_eq!(store.get_info(&id).unwrap().access_count, 8);
+        }
+    }
+
+    #[test]
+    fn test_upload_chunk_out_of_order() {
+        let store = DocumentStore::new();
+
+        let result = upload_chunk(
+            &store,
+            UploadChunkParams {
+                stream_id: "upload-2".to_string(),
+                chunk_index: 1,
+                data: "AAAA".to_string(),
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+    }
+}
+
+// ============== Document Operations Tests ==============
+
+mod document {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_get_page_count() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_metadata() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_metadata(
+            &store,
+            GetMetadataParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        // The metadata may or may not be present, but the call should succeed
+        // Just verify it returns a valid result structure
+        let _ = result.title;
+        let _ = result.author;
+        let _ = result.subject;
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_xmp_metadata() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_xmp_metadata(
+            &store,
+            GetXmpMetadataParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.has_xmp, result.xmp.is_some());
+        if let Some(xmp) = &result.xmp {
+            assert!(xmp.starts_with('<'));
+            assert!(xmp.contains("xmpmeta"));
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_pdf_version() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_pdf_version(
+            &store,
+            GetPdfVersionParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        let (major, minor) = result.version.split_once('.').unwrap();
+        assert_eq!(major.len(), 1);
+        assert_eq!(minor.len(), 1);
+        assert!(major.chars().all(|c| c.is_ascii_digit()));
+        assert!(minor.chars().all(|c| c.is_ascii_digit()));
+        assert!(result.major >= 1);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_validate_pdf() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = validate_pdf(
+            &store,
+            ValidatePdfParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(result.is_valid, "{:?}", result.issues);
+        assert!(result.is_pdf);
+        assert!(result.cross_ref_ok);
+        assert!(result.page_tree_ok);
+        assert!(result.metadata_ok);
+
+        // Cutting off the trailer leaves a file MuPDF can only open by
+        // rebuilding the cross-reference table
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        let truncated_len = (base64_content.len() - 800) / 4 * 4;
+        let truncated_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content[..truncated_len].to_string(),
+                    filename: Some("truncated.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let truncated = validate_pdf(
+            &store,
+            ValidatePdfParams {
+                document_id: truncated_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(!truncated.is_valid);
+        assert!(!truncated.cross_ref_ok);
+        assert!(truncated
+            .issues
+            .iter()
+            .any(|issue| issue.severity == "error"));
+
+        for document_id in [doc_id, truncated_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_get_pdf_objects_summary() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_pdf_objects_summary(
+            &store,
+            GetPdfObjectsSummaryParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(result.xref_len > 0);
+        // At least the page content stream
+        assert!(result.stream_count >= 1);
+        assert!(result.page_tree_depth >= 1);
+        assert_eq!(result.file_size_bytes, Some(DUMMY_PDF.len()));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_fonts() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_fonts(
+            &store,
+            GetFontsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        for font in &result.fonts {
+            assert!(!font.name.is_empty());
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_document_format() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_document_format(
+            &store,
+            GetDocumentFormatParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.format, "pdf");
+        assert!(result.is_pdf);
+        assert!(!result.is_reflowable);
+        assert_eq!(result.mime_type, "application/pdf");
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_outlines() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_outlines(
+            &store,
+            GetOutlinesParams {
+                document_id: doc_id.clone(),
+                depth_limit: None,
+            },
+        )
+        .unwrap();
+
+        // May or may not have outlines
+        let _ = result.outlines;
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_outline_depth() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_outline_depth(
+            &store,
+            GetOutlineDepthParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(result.max_depth >= 1);
+        assert!(result.total_entries >= 1);
+
+        // Limiting to the top level drops every child
+        let top_level = get_outlines(
+            &store,
+            GetOutlinesParams {
+                document_id: doc_id.clone(),
+                depth_limit: Some(1),
+            },
+        )
+        .unwrap();
+        assert!(top_level.outlines.iter().all(|e| e.children.is_empty()));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_outline_depth_without_outline() {
+        let store = DocumentStore::new();
+        let doc_id = setup_labels_document(&store);
+
+        let result = get_outline_depth(
+            &store,
+            GetOutlineDepthParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(result.max_depth, 0);
+        assert_eq!(result.total_entries, 0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_document_outline_as_markdown() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let labels_id = setup_labels_document(&store);
+
+        let markdown = |document_id: &str| {
+            get_document_outline_as_markdown(
+                &store,
+                GetOutlineMarkdownParams {
+                    document_id: document_id.to_string(),
+                    include_page_numbers: true,
+                    max_depth: None,
+                },
+            )
+            .unwrap()
+        };
+
+        let result = markdown(&doc_id);
+        assert!(result.markdown.starts_with("- "));
+        assert!(result.entry_count > 0);
+        assert_eq!(result.markdown.lines().count(), result.entry_count);
+
+        let empty = markdown(&labels_id);
+        assert_eq!(empty.markdown, "");
+        assert_eq!(empty.entry_count, 0);
+
+        for document_id in [doc_id, labels_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_page_chapter_info_round_trip() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let info = get_page_chapter_info(
+            &store,
+            GetPageChapterInfoParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert!(info.chapter >= 0);
+        assert!(info.chapter < info.chapter_count);
+
+        let resolved = resolve_location(
+            &store,
+            ResolveLocationParams {
+                document_id: doc_id.clone(),
+                chapter: info.chapter,
+                page_in_chapter: info.page_in_chapter,
+            },
+        )
+        .unwrap();
+        assert_eq!(resolved.page_number, 0);
+
+        for (chapter, page_in_chapter) in [
+            (info.chapter_count, 0),
+            (-1, 0),
+            (info.chapter, 1000),
+            (info.chapter, -1),
+        ] {
+            let missing = resolve_location(
+                &store,
+                ResolveLocationParams {
+                    document_id: doc_id.clone(),
+                    chapter,
+                    page_in_chapter,
+                },
+            );
+            assert!(matches!(missing, Err(MupdfServerError::InvalidParams(_))));
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_outline_flat() {
+        fn count_entries(entries: &[OutlineEntry]) -> usize {
+            entries.iter().map(|e| 1 + count_entries(&e.children)).sum()
+        }
+
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let nested = get_outlines(
+            &store,
+            GetOutlinesParams {
+                document_id: doc_id.clone(),
+                depth_limit: None,
+            },
+        )
+        .unwrap();
+
+        let flat = get_outline_flat(
+            &store,
+            GetOutlineFlatParams {
+                document_id: doc_id.clone(),
+                max_depth: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(flat.total, count_entries(&nested.outlines));
+        assert!(flat.entries.iter().all(|e| e.depth >= 0));
+
+        // Siblings at the top level all have depth 0
+        let top_level = get_outline_flat(
+            &store,
+            GetOutlineFlatParams {
+                document_id: doc_id.clone(),
+                max_depth: Some(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(top_level.total, nested.outlines.len());
+        assert!(top_level.entries.iter().all(|e| e.depth == 0));
+
+        // max_depth is 1-based, like depth_limit of get_outlines
+        let invalid = get_outline_flat(
+            &store,
+            GetOutlineFlatParams {
+                document_id: doc_id.clone(),
+                max_depth: Some(0),
+            },
+        );
+        assert!(matches!(invalid, Err(MupdfServerError::InvalidParams(_))));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_document_statistics() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_document_statistics(
+            &store,
+            GetDocumentStatsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+        assert!(result.pages_with_text >= 0);
+        assert!(result.pages_without_text >= 0);
+        assert_eq!(
+            result.pages_with_text + result.pages_without_text,
+            result.page_count
+        );
+        // The fixture reads "Dummy PDF file"
+        assert_eq!(result.total_words, 3);
+        assert_eq!(result.total_characters, 12);
+        assert!(result.total_lines >= 1);
+        assert!(result.total_blocks >= 1);
+        assert!(result.estimated_reading_minutes > 0.0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_security_info_unencrypted() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_security_info(
+            &store,
+            GetSecurityInfoParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(!result.needs_password);
+        assert!(!result.is_encrypted);
+        assert!(result.permissions.can_print);
+        assert!(result.permissions.can_copy);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_security_info_encrypted() {
+        let store = DocumentStore::new();
+        let base64_content = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            DUMMY_ENCRYPTED_PDF,
+        );
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy-encrypted.pdf".to_string()),
+                },
+                password: Some(String::new()),
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = get_security_info(
+            &store,
+            GetSecurityInfoParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(result.is_encrypted);
+        assert!(result.encryption.is_some());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_form_fields_without_form() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_form_fields(
+            &store,
+            GetFormFieldsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(!result.has_form);
+        assert!(result.fields.is_empty());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_action_tree() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_action_tree(
+            &store,
+            GetActionTreeParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.has_javascript,
+            result.actions.iter().any(|a| a.action_type == "JavaScript")
+        );
+        for action in &result.actions {
+            assert!(!action.trigger.is_empty());
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_structural_tree() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_structural_tree(
+            &store,
+            GetStructuralTreeParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(result.is_tagged, result.tree.is_some());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_check_accessibility() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = check_accessibility(
+            &store,
+            CheckAccessibilityParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(!result.is_tagged);
+        assert!(result.has_title);
+        assert!(!result.reading_order_defined);
+        assert_eq!(result.images_with_alt_text, 0);
+        assert_eq!(result.form_fields_with_labels, 0);
+        assert!(result
+            .issues
+            .iter()
+            .any(|issue| issue.severity == "error" && issue.description.contains("not tagged")));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_structural_tree_tagged() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, TAGGED_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("tagged.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = get_structural_tree(
+            &store,
+            GetStructuralTreeParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(result.is_tagged);
+
+        let tree = result.tree.unwrap();
+        let document = &tree["children"][0];
+        assert_eq!(document["role"], "Document");
+        let children = document["children"].as_array().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0]["role"], "H1");
+        assert_eq!(children[0]["alt_text"], "Document heading");
+        assert_eq!(children[0]["page"], 0);
+        assert_eq!(children[1]["role"], "P");
+        assert!(children[1]["alt_text"].is_null());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    fn setup_labels_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LABELS_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("labels.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_get_page_labels() {
+        let store = DocumentStore::new();
+        let doc_id = setup_labels_document(&store);
+
+        let result = get_page_labels(
+            &store,
+            GetPageLabelsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.labels.len(), 2);
+        assert_eq!(result.labels[0].start_page, 0);
+        assert_eq!(result.labels[0].style, "roman_lower");
+        assert_eq!(result.labels[1].start_page, 2);
+        assert_eq!(result.labels[1].style, "arabic");
+        assert_eq!(result.labels[1].prefix.as_deref(), Some("A-"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_labels_without_labels() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_labels(
+            &store,
+            GetPageLabelsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert!(result.labels.is_empty());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_resolve_page_label() {
+        let store = DocumentStore::new();
+        let doc_id = setup_labels_document(&store);
+
+        let resolve = |label: &str| {
+            resolve_page_label(
+                &store,
+                ResolvePageLabelParams {
+                    document_id: doc_id.clone(),
+                    label: label.to_string(),
+                },
+            )
+            .unwrap()
+            .page
+        };
+
+        assert_eq!(resolve("i"), Some(0));
+        assert_eq!(resolve("ii"), Some(1));
+        assert_eq!(resolve("A-1"), Some(2));
+        assert_eq!(resolve("iii"), None);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_embedded_files_without_attachments() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_embedded_files(
+            &store,
+            GetEmbeddedFilesParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(result.files.is_empty());
+
+        let result = extract_embedded_file(
+            &store,
+            ExtractEmbeddedFileParams {
+                document_id: doc_id.clone(),
+                name: "missing.txt".to_string(),
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(MupdfServerError::EmbeddedFileNotFound(_))
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_named_destinations_without_destinations() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_named_destinations(
+            &store,
+            GetNamedDestinationsParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert!(result.destinations.is_empty());
+
+        let result = resolve_named_destination(
+            &store,
+            ResolveNamedDestinationParams {
+                document_id: doc_id.clone(),
+                name: "missing".to_string(),
+            },
+        )
+        .unwrap();
+        assert!(result.page.is_none());
+        assert!(result.dest_type.is_none());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_embedded_files() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, ATTACHMENT_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("attachment.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = get_embedded_files(
+            &store,
+            GetEmbeddedFilesParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(result.files.len(), 1);
+        let file = &result.files[0];
+        assert_eq!(file.name, "data.csv");
+        assert_eq!(file.description.as_deref(), Some("Sample data"));
+        assert_eq!(file.mime_type.as_deref(), Some("text/csv"));
+        assert_eq!(file.size, Some(14));
+
+        let extracted = extract_embedded_file(
+            &store,
+            ExtractEmbeddedFileParams {
+                document_id: doc_id.clone(),
+                name: "data.csv".to_string(),
+            },
+        )
+        .unwrap();
+        let content = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            extracted.content_base64,
+        )
+        .unwrap();
+        assert_eq!(content, b"id,value\n1,42\n");
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Page Operations Tests ==============
+
+mod page {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_get_page_bounds() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_bounds(
+            &store,
+            GetPageBoundsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        assert!(result.width > 0.0);
+        assert!(result.height > 0.0);
+        assert_eq!(result.rotation, 0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_size_preset() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        // The fixture is an A4 page (595x842 pt)
+        let result = get_page_size_preset(
+            &store,
+            GetPageSizePresetParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.preset.as_deref(), Some("A4"));
+        assert_eq!(result.orientation, "portrait");
+        assert!((result.width_mm - 210.0).abs() < 1.0);
+        assert!((result.height_mm - 297.0).abs() < 1.0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_bounds_negative_index() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let page_count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+
+        let bounds = |page| {
+            get_page_bounds(
+                &store,
+                GetPageBoundsParams {
+                    document_id: doc_id.clone(),
+                    page,
+                },
+            )
+        };
+
+        let last = bounds(-1).unwrap();
+        let expected = bounds(page_count - 1).unwrap();
+        assert_eq!(last.width, expected.width);
+        assert_eq!(last.height, expected.height);
+        assert_eq!(last.rotation, expected.rotation);
+
+        // Counting back past the first page is still out of range
+        assert!(matches!(
+            bounds(-page_count - 1),
+            Err(MupdfServerError::InvalidPageNumber { .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_rotation() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_rotation(
+            &store,
+            GetPageRotationParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        assert!([0, 90, 180, 270].contains(&result.rotation_degrees));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_pages_bounds_all_pages() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let page_count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+
+        let result = get_pages_bounds(
+            &store,
+            GetPagesBoundsParams {
+                document_id: doc_id.clone(),
+                pages: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.pages.len() as i32, page_count);
+        assert_eq!(result.pages[0].page, 0);
+        assert!(result.pages[0].bounds.width > 0.0);
+
+        let result = get_pages_bounds(
+            &store,
+            GetPagesBoundsParams {
+                document_id: doc_id.clone(),
+                pages: Some(vec![0, 9999]),
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(MupdfServerError::InvalidPageNumber { page: 9999, .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_bounds_invalid_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_bounds(
+            &store,
+            GetPageBoundsParams {
+                document_id: doc_id.clone(),
+                page: 9999, // Invalid page
+            },
+        );
+
+        assert!(result.is_err());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_links_all() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_links_all(
+            &store,
+            GetPageLinksAllParams {
+                document_id: doc_id.clone(),
+                include_internal: true,
+                include_external: true,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.total, result.links.len());
+        for entry in &result.links {
+            assert_eq!(entry.page, 0);
+            assert!(entry.link.bounds.x0 < entry.link.bounds.x1);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_links() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_links(
+            &store,
+            GetPageLinksParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                include_text: false,
+            },
+        )
+        .unwrap();
+
+        // May or may not have links
+        for link in &result.links {
+            assert!(link.link_text.is_none());
+        }
+
+        let with_text = get_page_links(
+            &store,
+            GetPageLinksParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                include_text: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(with_text.links.len(), result.links.len());
+        for link in &with_text.links {
+            assert!(link.link_text.is_some());
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Text Extraction Tests ==============
+
+mod text {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    fn setup_styled_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, STYLED_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("styled.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_get_page_text_plain() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Text extraction should succeed
+        let _ = result.text;
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_extract_text_from_page_range() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let single = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        let result = extract_text_from_page_range(
+            &store,
+            ExtractTextRangeParams {
+                document_id: doc_id.clone(),
+                start_page: 0,
+                end_page: 0,
+                format: None,
+                join_separator: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.pages.len(), 1);
+        assert_eq!(result.pages[0].page, 0);
+        assert_eq!(result.pages[0].text, single.text);
+        assert_eq!(result.concatenated, single.text);
+
+        let out_of_range = extract_text_from_page_range(
+            &store,
+            ExtractTextRangeParams {
+                document_id: doc_id.clone(),
+                start_page: 0,
+                end_page: 5,
+                format: None,
+                join_separator: None,
+            },
+        );
+        assert!(matches!(
+            out_of_range,
+            Err(MupdfServerError::InvalidPageNumber { .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_html() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "html".to_string(),
+            },
+        )
+        .unwrap();
+
+        // HTML output should contain HTML tags
+        assert!(result.text.contains("<") || result.text.is_empty());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_json() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "json".to_string(),
+            },
+        )
+        .unwrap();
+
+        // JSON should be valid
+        if !result.text.is_empty() {
+            let parsed: Result<serde_json::Value, _> = serde_json::from_str(&result.text);
+            assert!(parsed.is_ok(), "JSON parsing failed: {}", result.text);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        // Search for a common word that might be in the document
+        let result = search_page(
+            &store,
+            SearchPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                query: "the".to_string(),
+                case_sensitive: true,
+                whole_word: false,
+            },
+        )
+        .unwrap();
+
+        // Results may or may not be found
+        let _ = result.hits;
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    fn search_hit_count(
+        store: &DocumentStore,
+        doc_id: &str,
+        query: &str,
+        case_sensitive: bool,
+        whole_word: bool,
+    ) -> usize {
+        search_page(
+            store,
+            SearchPageParams {
+                document_id: doc_id.to_string(),
+                page: 0,
+                query: query.to_string(),
+                case_sensitive,
+                whole_word,
+            },
+        )
+        .unwrap()
+        .hits
+        .len()
+    }
+
+    #[test]
+    fn test_search_page_case_insensitive() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let upper = search_hit_count(&store, &doc_id, "DUMMY", false, false);
+        let lower = search_hit_count(&store, &doc_id, "dummy", false, false);
+        assert!(upper > 0);
+        assert_eq!(upper, lower);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_page_case_sensitive() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        assert_eq!(search_hit_count(&store, &doc_id, "DUMMY", true, false), 0);
+        assert!(search_hit_count(&store, &doc_id, "Dummy", true, false) > 0);
+        // Case-insensitive matching must agree with the case-sensitive path
+        assert_eq!(
+            search_hit_count(&store, &doc_id, "dummy", false, true),
+            search_hit_count(&store, &doc_id, "Dummy", true, true),
+        );
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_page_whole_word() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        assert!(search_hit_count(&store, &doc_id, "Dumm", true, false) > 0);
+        assert_eq!(search_hit_count(&store, &doc_id, "Dumm", true, true), 0);
+        assert!(search_hit_count(&store, &doc_id, "Dummy", true, true) > 0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_document() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let page_count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+
+        let result = search_document(
+            &store,
+            SearchDocumentParams {
+                document_id: doc_id.clone(),
+                query: "Dummy".to_string(),
+                max_hits_per_page: None,
+                page_range: None,
+                case_sensitive: true,
+                whole_word: false,
+            },
+        )
+        .unwrap();
+
+        assert!(result.total_hits >= 1);
+        assert_eq!(result.total_hits, result.hits.len());
+        for hit in &result.hits {
+            assert!(hit.page >= 0 && hit.page < page_count);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_document_invalid_range() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = search_document(
+            &store,
+            SearchDocumentParams {
+                document_id: doc_id.clone(),
+                query: "Dummy".to_string(),
+                max_hits_per_page: None,
+                page_range: Some((0, 9999)),
+                case_sensitive: true,
+                whole_word: false,
+            },
+        );
+
+        assert!(result.is_err());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_document_regex() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let search = |pattern: &str| {
+            search_document_regex(
+                &store,
+                SearchDocumentRegexParams {
+                    document_id: doc_id.clone(),
+                    pattern: pattern.to_string(),
+                    page_range: None,
+                    max_matches: None,
+                },
+            )
+        };
+
+        let years = search(r"\d{4}").unwrap();
+        assert!(!years.truncated);
+        for m in &years.matches {
+            assert_eq!(m.text.chars().count(), 4);
+            assert!(m.text.chars().all(|c| c.is_ascii_digit()));
+            assert_eq!(m.end_char - m.start_char, 4);
+            assert!(m.groups.is_empty());
+        }
+
+        let dummy = search(r"D(u)(m+)y|(x)").unwrap();
+        assert!(!dummy.matches.is_empty());
+        let first = &dummy.matches[0];
+        assert_eq!(first.text, "Dummy");
+        assert_eq!(first.end_char - first.start_char, 5);
+        assert_eq!(
+            first.groups,
+            vec![Some("u".to_string()), Some("mm".to_string()), None]
+        );
+
+        let err = search("(unclosed").unwrap_err();
+        assert!(matches!(err, MupdfServerError::InvalidRegex(_)));
+        assert_eq!(err.code(), 400);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_blocks() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_blocks(
+            &store,
+            GetPageTextBlocksParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        // Should have some blocks
+        let _ = result.blocks;
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_chars() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_chars(
+            &store,
+            GetPageCharsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        let plain = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Plain text adds a newline after every line and block
+        let expected = plain.text.chars().filter(|c| *c != '\n').count();
+        assert_eq!(result.chars.len(), expected);
+
+        for ch in &result.chars {
+            assert!(ch.font_size > 0.0);
+            assert!(ch.x0 <= ch.x1);
+            assert!(ch.y0 <= ch.y1);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_chars_font_change_within_line() {
+        let store = DocumentStore::new();
+        let doc_id = setup_styled_document(&store);
+
+        let result = get_page_chars(
+            &store,
+            GetPageCharsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        let text: String = result.chars.iter().map(|c| c.ch).collect();
+        let bold_start = text.find("bold").unwrap();
+        assert!(result.chars.iter().all(|c| c.line == 0));
+        assert!(!result.chars[0].font_name.contains("Bold"));
+        assert!(result.chars[bold_start].font_name.contains("Bold"));
+        assert!(!result.chars.last().unwrap().font_name.contains("Bold"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_structured() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_structured(
+            &store,
+            GetPageTextStructuredParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                include_font_info: true,
+                include_color: true,
+            },
+        )
+        .unwrap();
+
+        let blocks = result.tree["blocks"].as_array().unwrap();
+        assert!(!blocks.is_empty());
+        let span = &blocks[0]["lines"][0]["spans"][0];
+        assert!(span["font"].is_string());
+        assert!(span["size"].as_f64().unwrap() > 0.0);
+        let text: String = blocks
+            .iter()
+            .flat_map(|b| b["lines"].as_array().unwrap())
+            .flat_map(|l| l["spans"].as_array().unwrap())
+            .flat_map(|s| s["chars"].as_array().unwrap())
+            .filter_map(|c| c["c"].as_str())
+            .collect();
+        assert!(text.contains("Dummy PDF file"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_spans() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_spans(
+            &store,
+            GetPageTextSpansParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        assert!(!result.spans.is_empty());
+        for span in &result.spans {
+            assert!(!span.text.is_empty());
+            assert!(span.font_size > 0.0);
+            assert!(span.x0 <= span.x1);
+        }
+        let text: String = result.spans.iter().map(|s| s.text.as_str()).collect();
+        assert!(text.contains("Dummy"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_spans_inline_emphasis() {
+        let store = DocumentStore::new();
+        let doc_id = setup_styled_document(&store);
+
+        let result = get_page_text_spans(
+            &store,
+            GetPageTextSpansParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        let bold = result
+            .spans
+            .iter()
+            .find(|span| span.text == "bold")
+            .unwrap();
+        assert!(bold.is_bold);
+        assert_eq!(bold.color, 0xFF0000);
+        let first = &result.spans[0];
+        assert!(first.text.starts_with("Plain"));
+        assert!(!first.is_bold);
+        assert_eq!(first.color, 0);
+
+        let fonts = get_fonts_on_page(
+            &store,
+            GetFontsOnPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert!(fonts.fonts.iter().any(|font| font.name.contains("Bold")));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_fonts_on_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_fonts_on_page(
+            &store,
+            GetFontsOnPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        assert!(!result.fonts.is_empty());
+        for font in &result.fonts {
+            assert!(font.char_count > 0);
+            assert!(font.size > 0.0);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_encoding() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_encoding(
+            &store,
+            GetPageTextEncodingParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert!(result.total_chars > 0);
+        assert!((0.0..=100.0).contains(&result.encoding_issue_percent));
+        assert_eq!(result.has_encoding_issues, result.replacement_chars > 0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_language() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_language(
+            &store,
+            GetPageTextLanguageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        // "Dummy PDF file"
+        assert!(!result.detected_script.is_empty());
+        assert_eq!(result.detected_script, "Latin");
+        assert!(result.language_hint.is_none());
+        assert!((0.0..=1.0).contains(&result.confidence));
+        assert_eq!(result.char_count, 12);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_diff_same_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        for granularity in ["line", "word"] {
+            let result = get_page_text_diff(
+                &store,
+                GetPageTextDiffParams {
+                    document_id_a: doc_id.clone(),
+                    page_a: 0,
+                    document_id_b: doc_id.clone(),
+                    page_b: 0,
+                    diff_granularity: Some(granularity.to_string()),
+                },
+            )
+            .unwrap();
+
+            assert!(result.added.is_empty());
+            assert!(result.removed.is_empty());
+            assert!(result.unchanged_lines > 0);
+            assert_eq!(result.similarity, 1.0);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_text_for_region() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let bounds = get_page_bounds(
+            &store,
+            GetPageBoundsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        let result = get_text_for_region(
+            &store,
+            GetTextForRegionParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                x0: bounds.x0,
+                y0: bounds.y0,
+                x1: bounds.x0 + bounds.width,
+                y1: bounds.y0 + bounds.height,
+                overlap: None,
+            },
+        )
+        .unwrap();
+
+        let plain = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Same characters; only line and block breaks differ
+        let strip = |s: &str| s.chars().filter(|c| *c != '\n').collect::<String>();
+        assert_eq!(strip(&result.text), strip(&plain.text));
+        assert_eq!(result.char_count, strip(&plain.text).chars().count());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_page_context() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = search_page_context(
+            &store,
+            SearchPageContextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                query: "pdf".to_string(),
+                context_chars: Some(10),
+            },
+        )
+        .unwrap();
+
+        assert!(!result.hits.is_empty());
+        for hit in &result.hits {
+            assert!(!hit.full_line.is_empty());
+            assert!(hit.full_line.to_lowercase().contains("pdf"));
+            assert!(hit.text_before.chars().count() <= 10);
+            assert!(hit.text_after.chars().count() <= 10);
+        }
+        assert!(result.hits[0].text_before.contains("Dummy"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_lines() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_lines(
+            &store,
+            GetPageTextLinesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        let text = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        let expected = text
+            .text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+        assert_eq!(result.total, expected);
+        assert_eq!(result.lines.len(), result.total);
+        for line in &result.lines {
+            assert!(line.x0 <= line.x1 && line.y0 <= line.y1);
+            assert!(line.writing_mode == "horizontal" || line.writing_mode == "vertical");
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_reading_order() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_text_reading_order(
+            &store,
+            GetPageTextReadingOrderParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                column_gap_threshold: None,
+            },
+        )
+        .unwrap();
+        assert!(result.text.contains("Dummy PDF file"));
+        assert!(result.column_count >= 1);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_text_reading_order_spanning_heading() {
+        let store = DocumentStore::new();
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        COLUMNS_PDF,
+                    ),
+                    filename: Some("columns.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = get_page_text_reading_order(
+            &store,
+            GetPageTextReadingOrderParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                column_gap_threshold: None,
+            },
+        )
+        .unwrap();
+        // The heading does not merge the columns below it
+        assert_eq!(result.column_count, 2);
+        let position = |needle: &str| result.text.find(needle).unwrap();
+        assert!(position("A heading") < position("Left one"));
+        assert!(position("Left one") < position("Left two"));
+        assert!(position("Left two") < position("Right one"));
+        assert!(position("Right one") < position("Right two"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_text_blocks_with_reading_zones() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_text_blocks_with_reading_zones(
+            &store,
+            GetTextBlocksWithZonesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                column_gap_pts: None,
+            },
+        )
+        .unwrap();
+        // The fixture is a single column
+        assert_eq!(result.columns.len(), 1);
+        assert_eq!(result.columns[0].column_index, 0);
+        assert!(result.columns[0].x_min < result.columns[0].x_max);
+        assert!(!result.blocks.is_empty());
+        assert!(result.blocks.iter().all(|block| block.column == Some(0)));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_text_blocks_with_reading_zones_spanning_heading() {
+        let store = DocumentStore::new();
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        COLUMNS_PDF,
+                    ),
+                    filename: Some("columns.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = get_text_blocks_with_reading_zones(
+            &store,
+            GetTextBlocksWithZonesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                column_gap_pts: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.columns.len(), 2);
+        let column_of = |needle: &str| {
+            result
+                .blocks
+                .iter()
+                .find(|b| b.block.lines.iter().any(|l| l.text.contains(needle)))
+                .unwrap()
+                .column
+        };
+        assert_eq!(column_of("A heading"), None);
+        assert_eq!(column_of("Left one"), Some(0));
+        assert_eq!(column_of("Right one"), Some(1));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_words_at_point() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let words = get_page_words(
+            &store,
+            GetPageWordsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        let first = &words.words[0];
+
+        let result = get_page_words_at_point(
+            &store,
+            GetPageWordsAtPointParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                x: (first.x0 + first.x1) / 2.0,
+                y: (first.y0 + first.y1) / 2.0,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.word.as_deref(), Some(first.text.as_str()));
+        assert_eq!(result.block_index, Some(first.block_index));
+        assert_eq!(result.line_index, Some(0));
+
+        let miss = get_page_words_at_point(
+            &store,
+            GetPageWordsAtPointParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                x: -100.0,
+                y: -100.0,
+            },
+        )
+        .unwrap();
+        assert!(miss.word.is_none());
+        assert!(miss.bounds.is_none());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_tables() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_tables(
+            &store,
+            GetPageTablesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        for table in &result.tables {
+            assert!((0.0..=1.0).contains(&table.confidence));
+            assert!(table.rows.len() >= 2);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_words() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_words(
+            &store,
+            GetPageWordsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        let plain = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Words should match a whitespace split of the plain text
+        let expected: Vec<&str> = plain.text.split_whitespace().collect();
+        let actual: Vec<&str> = result.words.iter().map(|w| w.text.as_str()).collect();
+        assert!(!actual.is_empty());
+        assert_eq!(actual, expected);
+
+        for word in &result.words {
+            assert!(word.x0 < word.x1, "bad x range for {:?}", word);
+            assert!(word.y0 < word.y1, "bad y range for {:?}", word);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Render Tests ==============
+
+mod render {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_render_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(1.0),
+                dpi: None,
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        // Should return valid PNG data
+        let image = result.image.expect("PNG data");
+        assert!(!image.is_empty());
+        assert!(result.svg.is_none());
+        assert!(result.width > 0);
+        assert!(result.height > 0);
+
+        // Verify it's valid base64
+        let decoded = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &image);
+        assert!(decoded.is_ok());
+
+        // Verify PNG magic bytes
+        let bytes = decoded.unwrap();
+        assert!(bytes.len() > 8);
+        assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]); // PNG signature
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_region() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page_region(
+            &store,
+            RenderPageRegionParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                x0: 10.0,
+                y0: 20.0,
+                x1: 110.0,
+                y1: 70.0,
+                scale: Some(2.0),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.format, "png");
+        assert_eq!(result.width, 200);
+        assert_eq!(result.height, 100);
+
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.image)
+                .unwrap();
+        assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]); // PNG signature
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_color_info() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_color_info(
+            &store,
+            GetPageColorInfoParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        // A single line of black text on a white page
+        assert!((0.0..=1.0).contains(&result.estimated_ink_coverage));
+        assert!(result.estimated_ink_coverage < 0.1);
+        assert!(!result.is_color);
+        assert!(!result.has_images);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_ink_coverage() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let blank_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        BLANK_PDF,
+                    ),
+                    filename: Some("blank.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let blank = get_page_ink_coverage(
+            &store,
+            GetPageInkCoverageParams {
+                document_id: blank_id.clone(),
+                page: 0,
+                sample_scale: None,
+            },
+        )
+        .unwrap();
+        assert!(blank.is_blank);
+        assert!(blank.coverage_percent < 1.0);
+        assert!(blank.render_width > 0 && blank.render_height > 0);
+
+        // A line of text is visible at a higher sample scale
+        let text = get_page_ink_coverage(
+            &store,
+            GetPageInkCoverageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                sample_scale: Some(0.5),
+            },
+        )
+        .unwrap();
+        assert!(!text.is_blank);
+        assert!(text.coverage_percent < 10.0);
+
+        let invalid = get_page_ink_coverage(
+            &store,
+            GetPageInkCoverageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                sample_scale: Some(0.0),
+            },
+        );
+        assert!(matches!(invalid, Err(MupdfServerError::InvalidParams(_))));
+
+        for document_id in [doc_id, blank_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_get_page_color_space_usage() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_color_space_usage(
+            &store,
+            GetPageColorSpaceParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        // The page's transparency group is DeviceRGB
+        assert!(result.colorspaces.contains(&"DeviceRGB".to_string()));
+        assert!(!result.has_spot_colors);
+        assert!(result.spot_color_names.is_empty());
+        assert!(!result.has_icc);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_region_invalid() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let render = |x0, y0, x1, y1| {
+            render_page_region(
+                &store,
+                RenderPageRegionParams {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                    x0,
+                    y0,
+                    x1,
+                    y1,
+                    scale: None,
+                },
+            )
+        };
+
+        // Degenerate rectangle
+        assert!(matches!(
+            render(50.0, 50.0, 50.0, 100.0),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+        // Outside the page
+        assert!(matches!(
+            render(-10.0, 0.0, 50.0, 50.0),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_pages() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LABELS_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("labels.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = render_pages(
+            &store,
+            RenderPagesParams {
+                document_id: doc_id.clone(),
+                pages: vec![0, 1],
+                scale: None,
+                dpi: None,
+                format: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.images.len(), 2);
+        for (entry, expected_page) in result.images.iter().zip([0, 1]) {
+            assert_eq!(entry.page, expected_page);
+            let image = entry.render.image.as_ref().expect("PNG data");
+            let bytes =
+                base64::Engine::decode(&base64::engine::general_purpose::STANDARD, image).unwrap();
+            assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_all_pages() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let page_count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+
+        let result = render_all_pages(
+            &store,
+            RenderAllPagesParams {
+                document_id: doc_id.clone(),
+                scale: Some(0.25),
+                dpi: None,
+                max_pages: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.page_count, page_count);
+        assert_eq!(result.images.len(), page_count as usize);
+        for (index, entry) in result.images.iter().enumerate() {
+            assert_eq!(entry.page, index as i32);
+            let image = entry.render.image.as_ref().expect("PNG data");
+            let bytes =
+                base64::Engine::decode(&base64::engine::general_purpose::STANDARD, image).unwrap();
+            assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_all_pages_max_pages() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LABELS_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("labels.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = render_all_pages(
+            &store,
+            RenderAllPagesParams {
+                document_id: doc_id.clone(),
+                scale: Some(0.25),
+                dpi: None,
+                max_pages: Some(2),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.page_count, 3);
+        let pages: Vec<i32> = result.images.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![0, 1]);
+
+        let result = render_all_pages(
+            &store,
+            RenderAllPagesParams {
+                document_id: doc_id.clone(),
+                scale: Some(0.25),
+                dpi: None,
+                max_pages: Some(100_000),
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_pages_too_many() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_pages(
+            &store,
+            RenderPagesParams {
+                document_id: doc_id.clone(),
+                pages: vec![0; 51],
+                scale: None,
+                dpi: None,
+                format: None,
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_grayscale() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let render = |colorspace: &str| {
+            render_page(
+                &store,
+                RenderPageParams {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                    scale: Some(1.0),
+                    dpi: None,
+                    format: "png".to_string(),
+                    colorspace: Some(colorspace.to_string()),
+                    transparent_background: None,
+                    rotate_to_upright: None,
+                },
+            )
+            .unwrap()
+        };
+
+        let rgb = render("rgb");
+        let gray = render("gray");
+        assert_eq!(rgb.colorspace, "rgb");
+        assert_eq!(gray.colorspace, "gray");
+        assert_eq!(gray.format, "png");
+        assert_eq!((gray.width, gray.height), (rgb.width, rgb.height));
+
+        // One channel instead of three
+        let decode = |image: Option<String>| {
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, image.unwrap())
+                .unwrap()
+        };
+        assert!(decode(gray.image).len() < decode(rgb.image).len());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_transparent_background() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(0.5),
+                dpi: None,
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: Some(true),
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+        assert!(result.has_alpha);
+
+        // IHDR: 8-bit depth, color type 6 (truecolor with alpha)
+        let png = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            result.image.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[24], 8);
+        assert_eq!(png[25], 6);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_rotate_to_upright() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, ROTATED_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("rotated.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let render = |rotate_to_upright: bool| {
+            render_page(
+                &store,
+                RenderPageParams {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                    scale: Some(1.0),
+                    dpi: None,
+                    format: "png".to_string(),
+                    colorspace: None,
+                    transparent_background: None,
+                    rotate_to_upright: Some(rotate_to_upright),
+                },
+            )
+            .unwrap()
+        };
+
+        let upright = render(true);
+        let unrotated = render(false);
+        assert_eq!((upright.width, upright.height), (100, 200));
+        assert_eq!((unrotated.width, unrotated.height), (200, 100));
+
+        let bounds = get_page_display_bounds(
+            &store,
+            GetPageDisplayBoundsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(bounds.rotation, 90);
+        assert_eq!(
+            (bounds.display_width, bounds.display_height),
+            (100.0, 200.0)
+        );
+        assert_eq!(
+            (bounds.unrotated_width, bounds.unrotated_height),
+            (200.0, 100.0)
+        );
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_with_scale() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result_1x = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(1.0),
+                dpi: None,
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        let result_2x = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(2.0),
+                dpi: None,
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        // 2x scale should produce larger dimensions
+        assert_eq!(result_2x.width, result_1x.width * 2);
+        assert_eq!(result_2x.height, result_1x.height * 2);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_with_dpi() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let baseline = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: None,
+                dpi: Some(72.0),
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: None,
+                dpi: Some(144.0),
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        // 144 DPI should be exactly twice the 72 DPI baseline
+        assert_eq!(result.width, baseline.width * 2);
+        assert_eq!(result.height, baseline.height * 2);
+        assert_eq!(result.effective_dpi, 144.0);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_scale_and_dpi_conflict() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(1.0),
+                dpi: Some(144.0),
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        );
+
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_svg() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(1.0),
+                dpi: None,
+                format: "svg".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        )
+        .unwrap();
+
+        assert!(result.image.is_none());
+        let svg = result.svg.expect("SVG markup");
+        // MuPDF emits an XML declaration before the root element
+        assert!(svg.contains("<svg"));
+        assert_eq!(result.format, "svg");
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_render_page_invalid_format() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(1.0),
+                dpi: None,
+                format: "gif".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        );
+
+        assert!(result.is_err());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Image Tests ==============
+
+mod images {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore, content: &[u8]) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, content);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("test.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_extract_page_images() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store, IMAGE_PDF);
+
+        let result = extract_page_images(
+            &store,
+            ExtractPageImagesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                include_data: true,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.images.len(), 1);
+        let image = &result.images[0];
+        assert_eq!(image.width, 4);
+        assert_eq!(image.height, 4);
+        assert!(image.bounds[0] < image.bounds[2]);
+        assert!(image.bounds[1] < image.bounds[3]);
+
+        // Verify PNG magic bytes
+        let data = image.data.as_ref().expect("image data requested");
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data).unwrap();
+        assert_eq!(&bytes[0..4], &[0x89, 0x50, 0x4E, 0x47]);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_extract_page_images_without_images() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store, DUMMY_PDF);
+
+        let result = extract_page_images(
+            &store,
+            ExtractPageImagesParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                include_data: false,
+            },
+        )
+        .unwrap();
+
+        assert!(result.images.is_empty());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_image_blocks() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store, IMAGE_PDF);
+
+        let result = get_page_image_blocks(
+            &store,
+            GetPageImageBlocksParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.image_blocks.len(), 1);
+        for block in &result.image_blocks {
+            assert!(block.width > 0);
+            assert!(block.height > 0);
+            assert!(block.x0 < block.x1);
+            assert!(block.y0 < block.y1);
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Annotation Tests ==============
+
+mod annotations {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_get_page_annotations() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_annotations(
+            &store,
+            GetPageAnnotationsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+
+        // The fixture has no markup annotations.
+        assert!(result.annotations.is_empty());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_annotations_all() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_annotations_all(
+            &store,
+            GetAnnotationsAllParams {
+                document_id: doc_id.clone(),
+                annotation_type_filter: Some(vec!["highlight".to_string()]),
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+        assert_eq!(result.total, result.annotations.len());
+        assert!(result.annotations.iter().all(|a| a
+            .annotation
+            .annotation_type
+            .eq_ignore_ascii_case("highlight")));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_annotations_summary() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let page_count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap()
+        .page_count;
+
+        let result = get_page_annotations_summary(
+            &store,
+            GetAnnotationsSummaryParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.by_page.len(), page_count as usize);
+        assert_eq!(result.total, result.by_page.iter().sum::<usize>());
+        assert_eq!(result.total, result.by_type.values().sum::<usize>());
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_page_annotations_invalid_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = get_page_annotations(
+            &store,
+            GetPageAnnotationsParams {
+                document_id: doc_id.clone(),
+                page: 5,
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(MupdfServerError::InvalidPageNumber { .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Manipulation Tests ==============
+
+mod manipulation {
+    use super::*;
+
+    fn setup_document(store: &DocumentStore) -> String {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        import_document(
+            store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id
+    }
+
+    #[test]
+    fn test_split_document() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = split_document(
+            &store,
+            SplitDocumentParams {
+                document_id: doc_id.clone(),
+                start_page: 0,
+                end_page: 0,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.page_count, 1);
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.base64)
+                .unwrap();
+        assert_eq!(bytes.len(), result.size_bytes);
+        assert_eq!(&bytes[0..4], b"%PDF");
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_split_document_invalid_range() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let split = |start_page, end_page| {
+            split_document(
+                &store,
+                SplitDocumentParams {
+                    document_id: doc_id.clone(),
+                    start_page,
+                    end_page,
+                },
+            )
+        };
+
+        assert!(matches!(
+            split(0, 5),
+            Err(MupdfServerError::InvalidPageNumber { .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_merge_documents() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = merge_documents(
+            &store,
+            MergeDocumentsParams {
+                document_id_a: doc_id.clone(),
+                pages_a: None,
+                document_id_b: doc_id.clone(),
+                pages_b: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.page_count, 2);
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.base64)
+                .unwrap();
+        assert_eq!(&bytes[0..4], b"%PDF");
+
+        // The merged document is not added to the store
+        assert_eq!(store.len().unwrap(), 1);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_merge_documents_invalid_page() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = merge_documents(
+            &store,
+            MergeDocumentsParams {
+                document_id_a: doc_id.clone(),
+                pages_a: None,
+                document_id_b: doc_id.clone(),
+                pages_b: Some(vec![3]),
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(MupdfServerError::InvalidPageNumber { .. })
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_create_document_from_pages() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let selections = || {
+            vec![
+                PageSelection {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                },
+                PageSelection {
+                    document_id: doc_id.clone(),
+                    page: -1,
+                },
+            ]
+        };
+
+        let result = create_document_from_pages(
+            &store,
+            CreateDocumentFromPagesParams {
+                selections: selections(),
+                store_result: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.page_count, 2);
+        assert!(result.document_id.is_none());
+        assert!(result.base64.is_some());
+
+        let stored = create_document_from_pages(
+            &store,
+            CreateDocumentFromPagesParams {
+                selections: selections(),
+                store_result: true,
+            },
+        )
+        .unwrap();
+        assert!(stored.base64.is_none());
+        let new_id = stored.document_id.unwrap();
+        let count = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: new_id.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(count.page_count, 2);
+
+        for document_id in [doc_id, new_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_set_document_password() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = set_document_password(
+            &store,
+            SetDocumentPasswordParams {
+                document_id: doc_id.clone(),
+                user_password: Some("secret".to_string()),
+                owner_password: Some("owner".to_string()),
+                permissions: None,
+            },
+        )
+        .unwrap();
+        assert!(result.size_bytes > 0);
+
+        let import = |password: Option<&str>| {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: result.base64.clone(),
+                        filename: None,
+                    },
+                    password: password.map(str::to_string),
+                },
+            )
+        };
+        assert!(matches!(
+            import(None),
+            Err(MupdfServerError::PasswordRequired)
+        ));
+        assert!(import(Some("wrong")).is_err());
+        let reimported = import(Some("secret")).unwrap();
+        assert_eq!(reimported.page_count, 1);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_set_document_password_requires_password() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = set_document_password(
+            &store,
+            SetDocumentPasswordParams {
+                document_id: doc_id,
+                user_password: None,
+                owner_password: None,
+                permissions: None,
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn test_flatten_document() {
+        let store = DocumentStore::new();
+        let import = |bytes: &[u8]| {
+            import_document(
+                &store,
+                ImportDocumentParams {
+                    source: DocumentSource::Base64 {
+                        base64: base64::Engine::encode(
+                            &base64::engine::general_purpose::STANDARD,
+                            bytes,
+                        ),
+                        filename: None,
+                    },
+                    password: None,
+                },
+            )
+            .unwrap()
+            .document_id
+        };
+        let form_fields = |document_id: &str| {
+            get_form_fields(
+                &store,
+                GetFormFieldsParams {
+                    document_id: document_id.to_string(),
+                },
+            )
+            .unwrap()
+        };
+
+        let doc_id = import(FORM_PDF);
+        assert!(form_fields(&doc_id).has_form);
+
+        let result = flatten_document(
+            &store,
+            FlattenDocumentParams {
+                document_id: doc_id.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(result.fields_flattened, 1);
+        // The stored document keeps its form
+        assert!(form_fields(&doc_id).has_form);
+
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.base64)
+                .unwrap();
+        assert_eq!(bytes.len(), result.size_bytes);
+        let flattened_id = import(&bytes);
+        assert!(!form_fields(&flattened_id).has_form);
+
+        let text = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: flattened_id,
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+        assert!(text.text.contains("Alice"));
+    }
+
+    #[test]
+    fn test_optimize_document() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let result = optimize_document(
+            &store,
+            OptimizeDocumentParams {
+                document_id: doc_id.clone(),
+                linearize: false,
+                garbage_collect: true,
+                compress_fonts: true,
+                compress_images: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(result.original_size_bytes, DUMMY_PDF.len());
+        assert!(result.optimized_size_bytes > 0);
+        assert!(result.reduction_percent >= 0.0);
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &result.base64)
+                .unwrap();
+        assert_eq!(bytes.len(), result.optimized_size_bytes);
+        assert!(bytes.starts_with(b"%PDF"));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_add_text_watermark() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+
+        let result = add_text_watermark(
+            &store,
+            AddTextWatermarkParams {
+                document_id: doc_id.clone(),
+                text: "CONFIDENTIAL (draft)".to_string(),
+                pages: None,
+                opacity: Some(0.5),
+                font_size: Some(24.0),
+                color: Some([1.0, 0.0, 0.0]),
+                position: Some("bottom-right".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(result.pages_modified, 1);
+
+        let watermarked_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: result.base64,
+                    filename: Some("watermarked.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+        let page_text = |document_id: &str| {
+            get_page_text(
+                &store,
+                GetPageTextParams {
+                    document_id: document_id.to_string(),
+                    page: 0,
+                    format: "plain".to_string(),
+                },
+            )
+            .unwrap()
+            .text
+        };
+
+        let text = page_text(&watermarked_id);
+        assert!(text.contains("CONFIDENTIAL (draft)"));
+        assert!(text.contains("Dummy"));
+        // The stored document is not modified
+        assert!(!page_text(&doc_id).contains("CONFIDENTIAL"));
+
+        let invalid = add_text_watermark(
+            &store,
+            AddTextWatermarkParams {
+                document_id: doc_id.clone(),
+                text: "DRAFT".to_string(),
+                pages: None,
+                opacity: None,
+                font_size: None,
+                color: None,
+                position: Some("middle".to_string()),
+            },
+        );
+        assert!(matches!(invalid, Err(MupdfServerError::InvalidParams(_))));
+
+        for document_id in [doc_id, watermarked_id] {
+            close_document(&store, CloseDocumentParams { document_id }).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rotate_pages() {
+        let store = DocumentStore::new();
+        let doc_id = setup_document(&store);
+        let rotate = |degrees: i32| {
+            rotate_pages(
+                &store,
+                RotatePagesParams {
+                    document_id: doc_id.clone(),
+                    pages: vec![0],
+                    degrees,
+                },
+            )
+        };
+        let rotation = || {
+            get_page_rotation(
+                &store,
+                GetPageRotationParams {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                },
+            )
+            .unwrap()
+            .rotation_degrees
+        };
+
+        assert_eq!(rotate(90).unwrap().rotated_count, 1);
+        assert_eq!(rotation(), 90);
+        let bounds = get_page_display_bounds(
+            &store,
+            GetPageDisplayBoundsParams {
+                document_id: doc_id.clone(),
+                page: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(bounds.rotation, 90);
+        assert_eq!(bounds.display_width, bounds.unrotated_height);
+
+        // Rotations accumulate
+        rotate(270).unwrap();
+        assert_eq!(rotation(), 0);
+
+        assert!(matches!(
+            rotate(45),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_delete_pages() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, LABELS_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("labels.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+        let delete = |pages: Vec<i32>| {
+            delete_pages(
+                &store,
+                DeletePagesParams {
+                    document_id: doc_id.clone(),
+                    pages,
+                },
+            )
+        };
+
+        let result = delete(vec![0, 0]).unwrap();
+        assert_eq!(result.deleted_count, 1);
+        assert_eq!(result.new_page_count, 2);
+        assert_eq!(store.get_info(&doc_id).unwrap().page_count, 2);
+
+        // The remaining pages cannot all be deleted
+        assert!(matches!(
+            delete(vec![0, -1]),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+        assert!(delete(vec![5]).is_err());
+        assert_eq!(store.get_info(&doc_id).unwrap().page_count, 2);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Oneshot Tests ==============
+
+mod oneshot {
+    use super::*;
+
+    #[test]
+    fn test_oneshot_get_bookmarks() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_get_bookmarks(
+            &DocumentStore::new(),
+            OneshotGetBookmarksParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap();
+
+        // Should return page count
+        assert!(result.page_count > 0);
+        // Bookmarks may or may not exist
+        let _ = result.bookmarks;
+    }
+
+    #[test]
+    fn test_oneshot_render_page_png() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_render_page(
+            &DocumentStore::new(),
+            OneshotRenderPageParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+                page: 0,
+                scale: None,
+                dpi: Some(36.0),
+                format: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.format, "png");
+        assert!(result.width > 0);
+        assert!(result.height > 0);
+
+        let png_data = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            result.image.unwrap(),
+        )
+        .unwrap();
+        assert_eq!(&png_data[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn test_oneshot_render_page_svg() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_render_page(
+            &DocumentStore::new(),
+            OneshotRenderPageParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+                page: 0,
+                scale: None,
+                dpi: None,
+                format: Some("svg".to_string()),
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+        assert!(result.image.is_none());
+        assert!(result.svg.unwrap().contains("<svg"));
+    }
+
+    #[test]
+    fn test_oneshot_get_page_count() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_get_page_count(
+            &DocumentStore::new(),
+            OneshotGetPageCountParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+    }
+
+    #[test]
+    fn test_oneshot_get_metadata() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_get_metadata(
+            &DocumentStore::new(),
+            OneshotGetMetadataParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+    }
+
+    #[test]
+    fn test_oneshot_search() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_search(
+            &DocumentStore::new(),
+            OneshotSearchParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+                query: "Dummy".to_string(),
+                max_hits_per_page: None,
+                page_range: None,
+                case_sensitive: None,
+                whole_word: None,
+            },
+        )
+        .unwrap();
+
+        assert!(result.page_count > 0);
+        assert_eq!(result.total_hits, result.hits.len());
+        assert!(result.hits.iter().all(|hit| hit.page == 0));
+    }
+
+    #[test]
+    fn test_oneshot_extract_full_text() {
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let result = oneshot_extract_full_text(
+            &DocumentStore::new(),
+            OneshotExtractFullTextParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+                format: None,
+            },
+        )
+        .unwrap();
+
+        assert!(result.total_pages > 0);
+        assert_eq!(result.pages.len(), result.total_pages as usize);
+        assert!(result.pages.iter().any(|p| !p.text.trim().is_empty()));
+    }
+
+    #[test]
+    fn test_oneshot_get_page_text_matches_stateful() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+
+        let oneshot = oneshot_get_page_text(
+            &store,
+            OneshotGetPageTextParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content.clone(),
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+                page: 0,
+                format: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(oneshot.format, "plain");
+        assert!(oneshot.page_count > 0);
+        assert!(oneshot.text.contains("Dummy PDF file"));
+
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+        let stateful = get_page_text(
+            &store,
+            GetPageTextParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                format: "plain".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(oneshot.text, stateful.text);
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Error Handling Tests ==============
+
+mod errors {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(MupdfServerError::DocumentNotFound("x".into()).code(), 404);
+        assert_eq!(
+            MupdfServerError::InvalidPageNumber {
+                page: 5,
+                total: 1,
+                max: 0
+            }
+            .code(),
+            400
+        );
+        assert_eq!(MupdfServerError::PasswordRequired.code(), 401);
+        assert_eq!(MupdfServerError::InvalidPassword.code(), 403);
+        assert_eq!(MupdfServerError::UrlNotAllowed("x".into()).code(), 403);
+        assert_eq!(MupdfServerError::NotAPdf.code(), 415);
+        assert_eq!(MupdfServerError::StoreFull { max: 1 }.code(), 503);
+        assert_eq!(MupdfServerError::internal("boom").code(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_document_not_found_code() {
+        let server = MupdfServer::new();
+
+        let mut args = serde_json::Map::new();
+        args.insert("document_id".to_string(), "nonexistent-doc-id".into());
+        let err = server
+            .handle_tool_call("get_page_count", args)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code.0, 404);
+        assert!(err.message.contains("nonexistent-doc-id"));
+    }
+
+    #[test]
+    fn test_document_not_found() {
+        let store = DocumentStore::new();
+
+        let result = get_page_count(
+            &store,
+            GetPageCountParams {
+                document_id: "nonexistent-doc-id".to_string(),
+            },
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        let store = DocumentStore::new();
+
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: "not-valid-base64!!!".to_string(),
+                    filename: Some("test.pdf".to_string()),
+                },
+                password: None,
+            },
+        );
+
+        assert!(result.is_err());
+    }
+}
+
+// ============== Server Configuration Tests ==============
+
+mod config {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+    }
+
+    #[test]
+    fn test_builder_construction() {
+        let _server = MupdfServer::builder()
+            .max_documents(8)
+            .sweep_interval_seconds(5)
+            .max_render_scale(4.0)
+            .max_render_megapixels(20)
+            .allowed_paths(vec![fixtures_dir()])
+            .build();
+    }
+
+    #[test]
+    fn test_import_inside_allowed_paths() {
+        let store = DocumentStore::new().allowed_paths(vec![fixtures_dir()]);
+
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::FilePath {
+                    path: fixtures_dir().join("dummy.pdf").display().to_string(),
+                },
+                password: None,
+            },
+        )
+        .unwrap();
+        assert!(result.page_count > 0);
+    }
+
+    #[test]
+    fn test_import_outside_allowed_paths() {
+        let store = DocumentStore::new().allowed_paths(vec![fixtures_dir()]);
+
+        // Escapes the allowed directory via `..`
+        let path = fixtures_dir().join("../fixtures/../../Cargo.toml");
+        let result = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::FilePath {
+                    path: path.display().to_string(),
+                },
+                password: None,
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::PathNotAllowed(_))));
+
+        let result = oneshot_get_metadata(
+            &store,
+            OneshotGetMetadataParams {
+                source: DocumentSource::FilePath {
+                    path: path.display().to_string(),
+                },
+                password: None,
+            },
+        );
+        assert!(matches!(result, Err(MupdfServerError::PathNotAllowed(_))));
+    }
+
+    #[test]
+    fn test_render_too_large() {
+        let store = DocumentStore::new();
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let result = render_page(
+            &store,
+            RenderPageParams {
+                document_id: doc_id.clone(),
+                page: 0,
+                scale: Some(100.0),
+                dpi: None,
+                format: "png".to_string(),
+                colorspace: None,
+                transparent_background: None,
+                rotate_to_upright: None,
+            },
+        );
+        match result {
+            Err(MupdfServerError::RenderTooLarge {
+                width,
+                height,
+                max_pixels,
+            }) => {
+                assert_eq!(max_pixels, 50_000_000);
+                assert!(width * height > max_pixels);
+            }
+            other => panic!("expected RenderTooLarge, got {:?}", other.map(|r| r.width)),
+        }
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_max_render_scale() {
+        let store = DocumentStore::new().max_render_scale(2.0);
+        let base64_content =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, DUMMY_PDF);
+        let doc_id = import_document(
+            &store,
+            ImportDocumentParams {
+                source: DocumentSource::Base64 {
+                    base64: base64_content,
+                    filename: Some("dummy.pdf".to_string()),
+                },
+                password: None,
+            },
+        )
+        .unwrap()
+        .document_id;
+
+        let render = |scale: f32| {
+            render_page(
+                &store,
+                RenderPageParams {
+                    document_id: doc_id.clone(),
+                    page: 0,
+                    scale: Some(scale),
+                    dpi: None,
+                    format: "png".to_string(),
+                    colorspace: None,
+                    transparent_background: None,
+                    rotate_to_upright: None,
+                },
+            )
+        };
+        assert!(render(2.0).is_ok());
+        assert!(matches!(
+            render(3.0),
+            Err(MupdfServerError::InvalidParams(_))
+        ));
+
+        close_document(
+            &store,
+            CloseDocumentParams {
+                document_id: doc_id,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ============== Tracing Tests ==============
+
+mod tracing_spans {
+    use super::*;
+    use tracing_test::traced_test;
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_tool_call_span() {
+        let server = MupdfServer::new();
+
+        let result = server
+            .handle_tool_call("list_documents", serde_json::Map::new())
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+
+        assert!(logs_contain("tool"));
+        assert!(logs_contain("list_documents"));
+        assert!(logs_contain("result=\"ok\""));
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn test_tool_call_span_error() {
+        let server = MupdfServer::new();
+
+        let mut args = serde_json::Map::new();
+        args.insert("document_id".to_string(), "missing".into());
+        let result = server.handle_tool_call("get_page_count", args).await;
+        assert!(result.is_err());
+
+        assert!(logs_contain("document_id=\"missing\""));
+        assert!(logs_contain("result=\"error\""));
+    }
+}
+
+mod http_transport {
+    use super::*;
+    use mupdf_rs_mcp_server::HTTP_MCP_PATH;
+    use serde_json::{json, Value};
+
+    /// A local port that is free at the time of the call.
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    /// POST one JSON-RPC message, returning the response and its session ID.
+    async fn post(
+        client: &reqwest::Client,
+        url: &str,
+        session_id: Option<&str>,
+        message: Value,
+    ) -> reqwest::Result<(Option<String>, String)> {
+        let mut request = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json, text/event-stream")
+            .body(message.to_string());
+        if let Some(session_id) = session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        let response = request.send().await?.error_for_status()?;
+        let session_id = response
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok((session_id, response.text().await?))
+    }
+
+    /// The JSON-RPC response with the given id in a server-sent event body.
+    fn sse_response(body: &str, id: i64) -> Value {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message["id"] == id)
+            .unwrap_or_else(|| panic!("no response with id {} in {:?}", id, body))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_http_tools_list() {
+        let port = free_port();
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+        let server = tokio::spawn(MupdfServer::new().serve_http(addr));
+        let url = format!("http://{}{}", addr, HTTP_MCP_PATH);
+        let client = reqwest::Client::new();
+
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "integration-test", "version": "0.0.0" }
+            }
+        });
+        // Retry until the listener is up
+        let mut attempt = 0;
+        let (session_id, body) = loop {
+            match post(&client, &url, None, initialize.clone()).await {
+                Ok(response) => break response,
+                Err(_) if attempt < 50 => {
+                    attempt += 1;
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                }
+                Err(e) => panic!("server did not start: {}", e),
+            }
+        };
+        let session_id = session_id.expect("initialize returns a session ID");
+        assert!(sse_response(&body, 1)["result"]["serverInfo"].is_object());
+
+        post(
+            &client,
+            &url,
+            Some(&session_id),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await
+        .unwrap();
+
+        let (_, body) = post(
+            &client,
+            &url,
+            Some(&session_id),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        )
+        .await
+        .unwrap();
+        let tools = sse_response(&body, 2)["result"]["tools"]
+            .as_array()
+            .cloned()
+            .unwrap();
+        assert!(tools.iter().any(|tool| tool["name"] == "import_document"));
+        assert!(tools.iter().all(|tool| tool["inputSchema"].is_object()));
+
+        server.abort();
     }
 }